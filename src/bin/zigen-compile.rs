@@ -0,0 +1,113 @@
+//! 把方案列表引用的字根集编译为预编译格式（见 zigen_trainer::compact）。
+//!
+//! 用法：
+//!   zigen-compile [schemes.json]
+//!
+//! 每个本地字根集都会先经过 zigen-lint 的检查：没有错误的字根集会被展开并编译，
+//! 输出至原文件旁、扩展名为 .zgb 的文件；有错误的则跳过，练习器会继续使用其JSON。
+//! 编译后的文件记录了方案的版本，版本不符时练习器同样会改用JSON。
+//! 应在打包练习器之前执行；部署的工作流程（.github/workflows/deploy.yaml）会自动执行。
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use zigen_trainer::compact;
+use zigen_trainer::lint::{self, Severity};
+use zigen_trainer::overlay;
+use zigen_trainer::scheme::Scheme;
+
+const DEFAULT_CATALOG: &str = "assets/trainer/schemes.json";
+
+fn usage() -> ExitCode {
+    eprintln!("用法：zigen-compile [schemes.json]");
+    ExitCode::from(2)
+}
+
+/// 编译一个字根集。version 为方案的版本，会写入编译后的文件。返回 Ok(false) 表示字根集有错误而被跳过。
+fn compile(path: &Path, keys: &str, version: &str) -> Result<bool, String> {
+    let errors = lint::lint_zigen_file(path, keys)
+        .into_iter()
+        .filter(|d| d.severity == Severity::Error)
+        .collect::<Vec<_>>();
+
+    if !errors.is_empty() {
+        for error in errors.iter() {
+            eprintln!("{error}");
+        }
+        return Ok(false);
+    }
+
+    let scheme = overlay::load_file(&path.display().to_string())?;
+    let output = path.with_extension(compact::EXTENSION);
+    let bytes = compact::encode(&scheme, version);
+
+    std::fs::write(&output, &bytes)
+        .map_err(|err| format!("无法写入「{}」：{err}", output.display()))?;
+
+    let original = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    println!(
+        "{} → {}（{}字节 → {}字节）",
+        path.display(),
+        output.display(),
+        original,
+        bytes.len()
+    );
+
+    Ok(true)
+}
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    let catalog = match args.as_slice() {
+        [] => PathBuf::from(DEFAULT_CATALOG),
+        [flag] if flag == "-h" || flag == "--help" => return usage(),
+        [catalog] => PathBuf::from(catalog),
+        _ => return usage(),
+    };
+
+    let schemes = match std::fs::read_to_string(&catalog)
+        .map_err(|err| err.to_string())
+        .and_then(|source| {
+            serde_json::from_str::<Vec<Scheme>>(&source).map_err(|err| err.to_string())
+        }) {
+        Ok(schemes) => schemes,
+        Err(err) => {
+            eprintln!("无法读取「{}」：{err}", catalog.display());
+            return ExitCode::from(2);
+        }
+    };
+
+    let base_dir = catalog.parent().unwrap_or(Path::new("."));
+    let mut compiled: Vec<PathBuf> = Vec::new();
+    let mut skipped: Vec<PathBuf> = Vec::new();
+
+    for scheme in schemes.iter() {
+        let url = &scheme.zigen_url;
+
+        // 远程的字根集不在本地，也不以 .json 结尾的则无法由练习器找到编译后的文件
+        if url.contains("://") || url.starts_with('/') || compact::compiled_url(url).is_none() {
+            continue;
+        }
+
+        let path = base_dir.join(url.trim_start_matches("./"));
+        if compiled.contains(&path) || skipped.contains(&path) {
+            continue;
+        }
+
+        match compile(&path, &scheme.keyboard.keys, &scheme.version) {
+            Ok(true) => compiled.push(path),
+            Ok(false) => {
+                eprintln!("「{}」有错误，已跳过", path.display());
+                skipped.push(path);
+            }
+            Err(err) => {
+                eprintln!("{err}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    println!("共编译{}个字根集，跳过{}个", compiled.len(), skipped.len());
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,120 @@
+//! 根据字形的相似度，为字根集建议新的混淆集。
+//!
+//! 用法：
+//!   zigen-confusables <字根集文件> --font <字体>... [--threshold <0~1>] [--max-size <数量>]
+//!
+//! 字体按所列顺序查找字形：先列出方案的字根字体（含私用区字形），再列出通用的中文字体作为后备。
+//! 只能渲染 TrueType 轮廓（glyf 表）的 TTF、WOFF 与 WOFF2 字体。常用的中文字体如 Noto Sans CJK
+//! 与思源黑体为 CFF 轮廓的 OTF，须先用 FontForge 等工具转换为 TTF；字体集合（TTC）也须先拆开。
+//! 建议的混淆集按相似度由高至低，以"混"的格式输出至标准输出；其他信息输出至标准错误。
+
+use std::process::ExitCode;
+
+use zigen_trainer::confusable::{self, SuggestOptions};
+use zigen_trainer::font::Font;
+use zigen_trainer::overlay;
+
+fn usage() -> ExitCode {
+    eprintln!(
+        "用法：zigen-confusables <字根集文件> --font <字体>... [--threshold <0~1>] [--max-size <数量>]"
+    );
+    eprintln!(
+        "字体须为 TrueType 轮廓的 TTF、WOFF 或 WOFF2。Noto Sans CJK、思源黑体等 CFF 轮廓的 OTF 须先转换为 TTF。"
+    );
+    ExitCode::from(2)
+}
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    let mut zigen_file = None;
+    let mut font_files = Vec::new();
+    let mut options = SuggestOptions::default();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return usage(),
+            "--font" => match args.next() {
+                Some(font) => font_files.push(font.clone()),
+                None => return usage(),
+            },
+            "--threshold" => match args.next().and_then(|v| v.parse().ok()) {
+                Some(threshold) => options.threshold = threshold,
+                None => return usage(),
+            },
+            "--max-size" => match args.next().and_then(|v| v.parse().ok()) {
+                Some(max_size) if max_size >= 2 => options.max_size = max_size,
+                _ => return usage(),
+            },
+            file if zigen_file.is_none() => zigen_file = Some(file.to_owned()),
+            _ => return usage(),
+        }
+    }
+
+    let (Some(zigen_file), false) = (zigen_file, font_files.is_empty()) else {
+        return usage();
+    };
+
+    let scheme = match overlay::load_file(&zigen_file) {
+        Ok(scheme) => scheme,
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let fonts = match font_files
+        .iter()
+        .map(|file| Font::load(file))
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(fonts) => fonts,
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let rendered = match confusable::render_roots(&scheme, &fonts, options.bitmap_size) {
+        Ok(rendered) => rendered,
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::from(2);
+        }
+    };
+
+    if !rendered.missing.is_empty() {
+        eprintln!(
+            "以下{}个字根在所有字体中都没有字形，已跳过：{}",
+            rendered.missing.len(),
+            rendered.missing.join(" ")
+        );
+    }
+
+    let suggestions = confusable::suggest(&scheme, &rendered, &options);
+
+    for suggestion in suggestions.iter() {
+        let members = suggestion
+            .zigens
+            .iter()
+            .zip(suggestion.codes.iter())
+            .map(|(zigen, code)| format!("{zigen}（{code}）"))
+            .collect::<Vec<_>>();
+        eprintln!("{:.3}  {}", suggestion.score, members.join(" "));
+    }
+
+    eprintln!(
+        "共渲染{}个字根，建议{}个混淆集",
+        rendered.roots.len(),
+        suggestions.len()
+    );
+
+    let confusables = suggestions
+        .iter()
+        .map(|suggestion| suggestion.to_confusable())
+        .collect::<Vec<_>>();
+    println!("{}", serde_json::to_string_pretty(&confusables).unwrap());
+
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,43 @@
+//! 比较两个字根集文件（可以是叠加文件）。
+//!
+//! 用法：
+//!   zigen-diff <旧字根集> <新字根集>
+//!
+//! 有差异时以状态码1退出，方便在脚本中使用。
+
+use std::process::ExitCode;
+
+use zigen_trainer::diff;
+use zigen_trainer::overlay;
+
+fn usage() -> ExitCode {
+    eprintln!("用法：zigen-diff <旧字根集> <新字根集>");
+    ExitCode::from(2)
+}
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    let [old, new] = args.as_slice() else {
+        return usage();
+    };
+
+    let load = |path: &str| overlay::load_file(path).inspect_err(|err| eprintln!("{err}"));
+    let (Ok(old), Ok(new)) = (load(old), load(new)) else {
+        return ExitCode::from(2);
+    };
+
+    let changes = diff::diff(&old, &new);
+
+    for change in changes.iter() {
+        println!("{change}");
+    }
+
+    println!("共{}项差异", changes.len());
+
+    if changes.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
@@ -0,0 +1,75 @@
+//! 将码表转换为字根集JSON。
+//!
+//! 用法：
+//!   zigen-import [--format tsv|rime|csv] <码表文件> [-o <输出文件>]
+//!
+//! 未指定格式时，根据文件扩展名猜测。未指定输出文件时，输出至标准输出。
+
+use std::process::ExitCode;
+
+use zigen_trainer::import::{self, ImportFormat};
+
+fn usage() -> ExitCode {
+    eprintln!("用法：zigen-import [--format tsv|rime|csv] <码表文件> [-o <输出文件>]");
+    ExitCode::from(2)
+}
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+
+    let mut format = None;
+    let mut input = None;
+    let mut output = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => match args.next().as_deref().and_then(ImportFormat::from_name) {
+                Some(f) => format = Some(f),
+                None => return usage(),
+            },
+            "-o" => match args.next() {
+                Some(o) => output = Some(o),
+                None => return usage(),
+            },
+            "-h" | "--help" => return usage(),
+            _ if input.is_none() => input = Some(arg),
+            _ => return usage(),
+        }
+    }
+
+    let Some(input) = input else {
+        return usage();
+    };
+
+    let source = match std::fs::read_to_string(&input) {
+        Ok(source) => source,
+        Err(err) => {
+            eprintln!("{input}: 无法读取文件：{err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let format = format.unwrap_or_else(|| ImportFormat::guess(&input));
+
+    let scheme = match import::import(&source, format) {
+        Ok(scheme) => scheme,
+        Err(err) => {
+            eprintln!("{input}: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let json = serde_json::to_string_pretty(&scheme).unwrap();
+
+    match output {
+        Some(output) => {
+            if let Err(err) = std::fs::write(&output, json) {
+                eprintln!("{output}: 无法写入文件：{err}");
+                return ExitCode::FAILURE;
+            }
+        }
+        None => println!("{json}"),
+    }
+
+    ExitCode::SUCCESS
+}
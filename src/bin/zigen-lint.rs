@@ -0,0 +1,85 @@
+//! 检查方案列表与字根集文件。
+//!
+//! 用法：
+//!   zigen-lint [schemes.json]           检查方案列表及其引用的所有字根集
+//!   zigen-lint --zigen [--keys <按键>] <文件>...
+//!                                       仅检查单个或多个字根集文件，按键默认为26个字母
+//!   zigen-lint --flatten <文件>          将叠加文件展开为完整的字根集，输出至标准输出
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use zigen_trainer::lint::{self, Diagnostic, Severity};
+use zigen_trainer::overlay;
+
+const DEFAULT_CATALOG: &str = "assets/trainer/schemes.json";
+
+fn usage() -> ExitCode {
+    eprintln!("用法：zigen-lint [schemes.json]");
+    eprintln!("      zigen-lint --zigen [--keys <按键>] <字根集文件>...");
+    eprintln!("      zigen-lint --flatten <叠加文件>");
+    ExitCode::from(2)
+}
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    let diagnostics: Vec<Diagnostic> = match args.first().map(String::as_str) {
+        Some("-h" | "--help") => return usage(),
+
+        Some("--zigen") => {
+            let (keys, files) = match &args[1..] {
+                [flag, keys, files @ ..] if flag == "--keys" => (keys.as_str(), files),
+                files => (lint::DEFAULT_KEYS, files),
+            };
+
+            if files.is_empty() {
+                return usage();
+            }
+
+            files
+                .iter()
+                .flat_map(|file| lint::lint_zigen_file(&PathBuf::from(file), keys))
+                .collect()
+        }
+
+        Some("--flatten") => {
+            if args.len() != 2 {
+                return usage();
+            }
+
+            return match overlay::load_file(&args[1]) {
+                Ok(scheme) => {
+                    println!("{}", serde_json::to_string_pretty(&scheme).unwrap());
+                    ExitCode::SUCCESS
+                }
+                Err(err) => {
+                    eprintln!("{err}");
+                    ExitCode::FAILURE
+                }
+            };
+        }
+
+        Some(catalog) if args.len() == 1 => lint::lint_catalog(&PathBuf::from(catalog)),
+        None => lint::lint_catalog(&PathBuf::from(DEFAULT_CATALOG)),
+        Some(_) => return usage(),
+    };
+
+    for diagnostic in diagnostics.iter() {
+        println!("{diagnostic}");
+    }
+
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .count();
+    let warnings = diagnostics.len() - errors;
+
+    println!("共{errors}个错误，{warnings}个警告");
+
+    if errors > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
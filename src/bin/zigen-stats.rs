@@ -0,0 +1,85 @@
+//! 统计字根集文件（可以是叠加文件）的各项数据。
+//!
+//! 用法：
+//!   zigen-stats <字根集>...
+//!
+//! 列出多个字根集时，各字根集的数据并排显示，方便比较。
+
+use std::process::ExitCode;
+
+use zigen_trainer::overlay;
+use zigen_trainer::stats::{self, SchemeStats};
+
+fn usage() -> ExitCode {
+    eprintln!("用法：zigen-stats <字根集>...");
+    ExitCode::from(2)
+}
+
+/// 字符串在终端中的显示宽度：中文等全角字符计为2。
+fn width(text: &str) -> usize {
+    text.chars().map(|c| if c.is_ascii() { 1 } else { 2 }).sum()
+}
+
+fn pad(text: &str, to: usize) -> String {
+    format!("{text}{}", " ".repeat(to.saturating_sub(width(text))))
+}
+
+fn main() -> ExitCode {
+    let files = std::env::args().skip(1).collect::<Vec<_>>();
+
+    if files.is_empty() || files.iter().any(|arg| arg == "-h" || arg == "--help") {
+        return usage();
+    }
+
+    let mut all = Vec::new();
+    for file in files.iter() {
+        match overlay::load_file(file) {
+            Ok(scheme) => all.push(SchemeStats::compute(&scheme)),
+            Err(err) => {
+                eprintln!("{err}");
+                return ExitCode::from(2);
+            }
+        }
+    }
+
+    let sections = stats::sections(&all.iter().collect::<Vec<_>>());
+
+    let label_width = sections
+        .iter()
+        .flat_map(|section| section.rows.iter().map(|(label, _)| width(label)))
+        .max()
+        .unwrap_or(0);
+    let value_width = sections
+        .iter()
+        .flat_map(|section| section.rows.iter().flat_map(|(_, values)| values))
+        .map(|value| width(value))
+        .chain(files.iter().map(|file| width(file)))
+        .max()
+        .unwrap_or(0);
+
+    if files.len() > 1 {
+        let header = files
+            .iter()
+            .map(|file| pad(file, value_width))
+            .collect::<Vec<_>>();
+        println!("{}  {}", pad("", label_width), header.join("  "));
+    }
+
+    for section in sections.iter() {
+        println!("【{}】", section.title);
+
+        for (label, values) in section.rows.iter() {
+            let values = values
+                .iter()
+                .map(|value| pad(value, value_width))
+                .collect::<Vec<_>>();
+            println!(
+                "{}  {}",
+                pad(label, label_width),
+                values.join("  ").trim_end()
+            );
+        }
+    }
+
+    ExitCode::SUCCESS
+}
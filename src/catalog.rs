@@ -0,0 +1,94 @@
+//! 方案列表（schemes.json）。除了练习器自带的方案列表，用户也可以添加第三方的方案列表。
+//!
+//! 方案列表中的相对地址以方案列表本身的所在目录为根目录。加载后，所有地址都会被解析为
+//! 可以直接下载的地址，练习器的其他部分无需再关心方案来自哪个方案列表。
+
+use crate::overlay::resolve_relative;
+use crate::scheme::Scheme;
+
+/// 练习器自带的方案列表。
+pub const BUNDLED_CATALOG: &str = "./assets/trainer/schemes.json";
+
+/// 解析方案列表中的地址。空地址、data URL与绝对URL保持不变。
+///
+/// 方案列表本身位于绝对URL时，以「/」开头的路径相对于其来源（协议与主机），相对路径则以方案列表的
+/// 所在目录为根目录；「../」最多只能回到来源的根目录，不会越过主机名。自带的方案列表位于相对路径，
+/// 其中的地址与练习器同源，以「/」开头的路径因此保持不变。
+pub fn resolve_url(catalog_url: &str, url: &str) -> String {
+    if url.is_empty() || url.starts_with("data:") || url.starts_with("//") || url.contains("://") {
+        return url.to_owned();
+    }
+
+    let Some((origin, path)) = split_origin(catalog_url) else {
+        return resolve_relative(catalog_url, url);
+    };
+
+    let mut segments = Vec::new();
+    if !url.starts_with('/')
+        && let Some((dir, _)) = path.rsplit_once('/')
+    {
+        segments.extend(dir.split('/').filter(|segment| !segment.is_empty()));
+    }
+
+    for segment in url.split('/') {
+        match segment {
+            "" | "." => (),
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+
+    format!("{origin}/{}", segments.join("/"))
+}
+
+/// 把绝对URL分为来源（如「https://example.com」）与路径（如「/schemes/schemes.json」），
+/// 路径不含查询与片段。不是绝对URL时返回 None。
+fn split_origin(url: &str) -> Option<(&str, &str)> {
+    let (protocol, rest) = url.split_once("://")?;
+    let host_len = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let origin = &url[..protocol.len() + 3 + host_len];
+
+    let path = &rest[host_len..];
+    let path = &path[..path.find(['?', '#']).unwrap_or(path.len())];
+
+    Some((origin, path))
+}
+
+/// 第三方方案列表在方案选择界面中显示的名称，即其所在的主机名。
+pub fn catalog_name(catalog_url: &str) -> &str {
+    match catalog_url.split_once("://") {
+        Some((_, rest)) => rest.split('/').next().unwrap_or(rest),
+        None => catalog_url,
+    }
+}
+
+/// 解析位于 catalog_url 的方案列表中的所有地址。
+///
+/// 第三方方案列表（third_party 为真）的方案ID会加上方案列表的地址作为前缀，以免与其他方案列表冲突；
+/// 其方案也会被归入以 catalog_name 命名的主分类，原有的分类则成为次分类。
+pub fn resolve_catalog(catalog_url: &str, schemes: Vec<Scheme>, third_party: bool) -> Vec<Scheme> {
+    schemes
+        .into_iter()
+        .map(|mut scheme| {
+            scheme.zigen_url = resolve_url(catalog_url, &scheme.zigen_url);
+            for font in scheme.zigen_font.iter_mut() {
+                *font = resolve_url(catalog_url, font);
+            }
+
+            if scheme.icon.contains('/') {
+                scheme.icon = resolve_url(catalog_url, &scheme.icon);
+            }
+
+            if third_party {
+                scheme.id = format!("{catalog_url}#{}", scheme.id);
+                scheme
+                    .category
+                    .insert(0, catalog_name(catalog_url).to_owned());
+            }
+
+            scheme
+        })
+        .collect()
+}
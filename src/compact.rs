@@ -0,0 +1,326 @@
+//! 预编译的字根集格式：体积更小，解析也比JSON快。
+//!
+//! 文件以 MAGIC 开头，接着是编译时方案的版本（见 Scheme::version）、字符串表，最后是字根集本身。
+//! 字根、编码、描述等字符串只在字符串表中出现一次，其他地方均以其在表中的序号引用。所有整数
+//! 都以 LEB128 变长格式储存。
+//!
+//! 编译后的文件与原字根集同名，扩展名为 EXTENSION，由 `zigen-compile` 生成。练习器的release版
+//! 会优先下载编译后的文件；文件不存在、格式不符，或者版本与方案列表中的不一致（即字根集更新后
+//! 未重新编译）时，再改用原本的JSON。
+
+use std::collections::HashMap;
+
+use crate::scheme::{
+    LoadedScheme, SchemeZigen, Zigen, ZigenClass, ZigenCluster, ZigenConfusableUnpopulated,
+    ZigenDecomposition, ZigenExample, ZigenGroup,
+};
+
+/// 文件开头的标识，最后一个字节是格式的版本。
+pub const MAGIC: &[u8; 4] = b"ZGB\x02";
+
+/// 编译后文件的扩展名。
+pub const EXTENSION: &str = "zgb";
+
+/// 编译后的字根集地址：把 .json 换成 EXTENSION。地址不以 .json 结尾时返回 None。
+pub fn compiled_url(zigen_url: &str) -> Option<String> {
+    zigen_url
+        .strip_suffix(".json")
+        .map(|stem| format!("{stem}.{EXTENSION}"))
+}
+
+/// 写入时使用的字符串表。
+#[derive(Default)]
+struct Writer<'a> {
+    strings: Vec<&'a str>,
+    indices: HashMap<&'a str, usize>,
+    body: Vec<u8>,
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: usize) {
+    while value >= 0x80 {
+        out.push((value as u8 & 0x7F) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+impl<'a> Writer<'a> {
+    fn varint(&mut self, value: usize) {
+        write_varint(&mut self.body, value);
+    }
+
+    fn string(&mut self, string: &'a str) {
+        let next = self.strings.len();
+        let index = *self.indices.entry(string).or_insert(next);
+        if index == next {
+            self.strings.push(string);
+        }
+        self.varint(index);
+    }
+
+    fn strings(&mut self, strings: impl ExactSizeIterator<Item = &'a str>) {
+        self.varint(strings.len());
+        for string in strings {
+            self.string(string);
+        }
+    }
+
+    fn zigens(&mut self, zigens: &'a [Zigen]) {
+        self.strings(zigens.iter().map(|zigen| zigen.0.as_str()));
+    }
+
+    fn group(&mut self, group: &'a ZigenGroup) {
+        self.zigens(&group.zigens);
+        self.string(&group.code);
+        self.strings(group.alt_codes.iter().map(String::as_str));
+        self.body.push(match group.classify {
+            ZigenClass::Common => 0,
+            ZigenClass::Simplified => 1,
+            ZigenClass::Traditional => 2,
+            ZigenClass::Uncommon => 3,
+        });
+        self.string(&group.description);
+
+        self.varint(group.examples.len());
+        for example in group.examples.iter() {
+            self.string(&example.character);
+            self.strings(example.split.iter().map(String::as_str));
+        }
+
+        self.strings(group.tags.iter().map(String::as_str));
+    }
+}
+
+/// 把字根集编码为预编译格式。version 为方案列表中该方案的版本。
+pub fn encode(scheme: &LoadedScheme<ZigenConfusableUnpopulated>, version: &str) -> Vec<u8> {
+    let mut writer = Writer::default();
+
+    writer.varint(scheme.0.len());
+    for entry in scheme.0.iter() {
+        match entry {
+            SchemeZigen::Cluster(cluster) => {
+                writer.body.push(0);
+                writer.string(&cluster.description);
+                writer.varint(cluster.groups.len());
+                for group in cluster.groups.iter() {
+                    writer.group(group);
+                }
+            }
+            SchemeZigen::Confusable(confusable) => {
+                writer.body.push(1);
+                writer.string(&confusable.description);
+                writer.zigens(&confusable.zigens);
+            }
+            SchemeZigen::Decomposition(decomposition) => {
+                writer.body.push(2);
+                writer.string(&decomposition.description);
+                writer.string(&decomposition.character);
+                writer.zigens(&decomposition.split);
+            }
+        }
+    }
+
+    let mut out = MAGIC.to_vec();
+    write_varint(&mut out, version.len());
+    out.extend_from_slice(version.as_bytes());
+    write_varint(&mut out, writer.strings.len());
+    for string in writer.strings.iter() {
+        write_varint(&mut out, string.len());
+        out.extend_from_slice(string.as_bytes());
+    }
+    out.extend_from_slice(&writer.body);
+    out
+}
+
+/// 读取时的游标。
+struct Reader<'a> {
+    bytes: &'a [u8],
+    strings: Vec<&'a str>,
+}
+
+impl<'a> Reader<'a> {
+    fn byte(&mut self) -> Result<u8, String> {
+        let (first, rest) = self
+            .bytes
+            .split_first()
+            .ok_or_else(|| String::from("预编译字根集不完整"))?;
+        self.bytes = rest;
+        Ok(*first)
+    }
+
+    fn varint(&mut self) -> Result<usize, String> {
+        let mut value = 0usize;
+
+        for shift in (0..usize::BITS).step_by(7) {
+            let byte = self.byte()?;
+            value |= ((byte & 0x7F) as usize) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+
+        Err(String::from("预编译字根集中的整数过大"))
+    }
+
+    /// 读取数量。每个元素至少占一个字节，超出剩余长度的数量必然有误。
+    fn count(&mut self) -> Result<usize, String> {
+        let count = self.varint()?;
+        if count > self.bytes.len() {
+            return Err(String::from("预编译字根集不完整"));
+        }
+        Ok(count)
+    }
+
+    /// 读取以长度开头的UTF-8字符串本身（而不是字符串表中的序号）。
+    fn raw_string(&mut self) -> Result<&'a str, String> {
+        let len = self.varint()?;
+        if len > self.bytes.len() {
+            return Err(String::from("预编译字根集不完整"));
+        }
+
+        let (string, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        std::str::from_utf8(string)
+            .map_err(|_| String::from("预编译字根集中的字符串不是有效的UTF-8"))
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let index = self.varint()?;
+        self.strings
+            .get(index)
+            .map(|string| string.to_string())
+            .ok_or_else(|| format!("预编译字根集引用了不存在的字符串#{index}"))
+    }
+
+    fn strings(&mut self) -> Result<Vec<String>, String> {
+        (0..self.count()?).map(|_| self.string()).collect()
+    }
+
+    fn zigens(&mut self) -> Result<Vec<Zigen>, String> {
+        (0..self.count()?)
+            .map(|_| self.string().map(Zigen))
+            .collect()
+    }
+
+    fn group(&mut self) -> Result<ZigenGroup, String> {
+        let zigens = self.zigens()?;
+        let code = self.string()?;
+        let alt_codes = self.strings()?;
+        let classify = match self.byte()? {
+            0 => ZigenClass::Common,
+            1 => ZigenClass::Simplified,
+            2 => ZigenClass::Traditional,
+            3 => ZigenClass::Uncommon,
+            other => return Err(format!("预编译字根集中有未知的分类#{other}")),
+        };
+        let description = self.string()?;
+
+        let examples = (0..self.count()?)
+            .map(|_| {
+                Ok(ZigenExample {
+                    character: self.string()?,
+                    split: self.strings()?,
+                })
+            })
+            .collect::<Result<_, String>>()?;
+
+        let tags = self.strings()?;
+
+        Ok(ZigenGroup {
+            zigens,
+            code,
+            alt_codes,
+            classify,
+            description,
+            examples,
+            tags,
+            id: String::new(),
+        })
+    }
+}
+
+/// 解码预编译格式的字根集，并检查其编译时的版本是否为 version。版本不符的文件多半是字根集
+/// 更新后未重新编译的旧文件，应改用JSON。
+pub fn decode_version(
+    bytes: &[u8],
+    version: &str,
+) -> Result<LoadedScheme<ZigenConfusableUnpopulated>, String> {
+    let (compiled_version, scheme) = decode_with_version(bytes)?;
+
+    if compiled_version != version {
+        return Err(format!(
+            "预编译字根集的版本（{compiled_version}）与方案的版本（{version}）不符"
+        ));
+    }
+
+    Ok(scheme)
+}
+
+/// 解码预编译格式的字根集，不检查版本。
+pub fn decode(bytes: &[u8]) -> Result<LoadedScheme<ZigenConfusableUnpopulated>, String> {
+    decode_with_version(bytes).map(|(_, scheme)| scheme)
+}
+
+/// 解码预编译格式的字根集，同时返回其编译时的版本。
+pub fn decode_with_version(
+    bytes: &[u8],
+) -> Result<(String, LoadedScheme<ZigenConfusableUnpopulated>), String> {
+    let bytes = bytes
+        .strip_prefix(MAGIC.as_slice())
+        .ok_or_else(|| String::from("不是预编译字根集，或其格式版本不受支持"))?;
+
+    let mut reader = Reader {
+        bytes,
+        strings: Vec::new(),
+    };
+
+    let version = reader.raw_string()?.to_owned();
+
+    for _ in 0..reader.count()? {
+        let string = reader.raw_string()?;
+        reader.strings.push(string);
+    }
+
+    let entries = (0..reader.count()?)
+        .map(|_| {
+            Ok(match reader.byte()? {
+                0 => {
+                    let description = reader.string()?;
+                    let groups = (0..reader.count()?)
+                        .map(|_| reader.group())
+                        .collect::<Result<_, String>>()?;
+                    SchemeZigen::Cluster(ZigenCluster {
+                        groups,
+                        description,
+                    })
+                }
+                1 => {
+                    let description = reader.string()?;
+                    let zigens = reader.zigens()?;
+                    SchemeZigen::Confusable(ZigenConfusableUnpopulated {
+                        zigens,
+                        description,
+                    })
+                }
+                2 => {
+                    let description = reader.string()?;
+                    let character = reader.string()?;
+                    let split = reader.zigens()?;
+                    SchemeZigen::Decomposition(ZigenDecomposition {
+                        character,
+                        split,
+                        description,
+                        groups: Vec::new(),
+                    })
+                }
+                other => return Err(format!("预编译字根集中有未知的条目类型#{other}")),
+            })
+        })
+        .collect::<Result<_, String>>()?;
+
+    if !reader.bytes.is_empty() {
+        return Err(String::from("预编译字根集的末尾有多余的数据"));
+    }
+
+    Ok((version, LoadedScheme(entries)))
+}
@@ -1,5 +1,7 @@
 use dioxus::prelude::*;
 
+use crate::scheme::KeySet;
+
 const KEYBOARD_ROWS: &[&[&str]] = &[
     &[
         "`",
@@ -14,7 +16,7 @@ const KEYBOARD_ROWS: &[&[&str]] = &[
         "9",
         "0",
         "-",
-        "+",
+        "=",
         "Backspace",
     ],
     &[
@@ -43,12 +45,63 @@ const KEYBOARD_ROWS: &[&[&str]] = &[
 
 #[derive(PartialEq, Props, Clone)]
 pub struct KeyboardProps {
-    highlighted_keys: Vec<String>,
+    /// 方案的按键定义。不属于方案的按键会以淡色显示，方案中不在键盘上的按键则另起一行显示
+    keyboard: KeySet,
+    highlighted_keys: Vec<char>,
+}
+
+/// 键帽对应的方案按键。功能键（如 Shift）没有对应的按键。
+fn key_of(label: &str, keyboard: &KeySet) -> Option<char> {
+    let mut chars = label.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => keyboard.normalize(c),
+        _ => None,
+    }
 }
 
 /// QWERTY 键盘组件
 #[component]
 pub fn QwertyKeyboard(props: KeyboardProps) -> Element {
+    let extra_keys = props
+        .keyboard
+        .keys
+        .chars()
+        .filter(|key| {
+            !KEYBOARD_ROWS
+                .iter()
+                .flat_map(|row| row.iter())
+                .any(|label| key_of(label, &props.keyboard) == Some(*key))
+        })
+        .map(String::from)
+        .collect::<Vec<_>>();
+
+    let render_key = |label: &str| {
+        let key = key_of(label, &props.keyboard);
+        let is_highlighted = key.is_some_and(|key| props.highlighted_keys.contains(&key));
+
+        // 根据按键类型调整宽度和样式
+        let base_style = if is_highlighted {
+            "padding: 10px 12px; border: 1px solid #aaa; border-radius: 6px; background-color: #f9e74a; font-weight: bold; text-align: center;"
+        } else {
+            "padding: 10px 12px; border: 1px solid #ccc; border-radius: 6px; background-color: #f0f0f0; text-align: center;"
+        };
+
+        let scheme_style = if key.is_some() { "" } else { "opacity: 0.4;" };
+
+        let key_style = match label {
+            "Backspace" | "\\" | "Tab" | "Space" | "Shift" | "Enter" => "flex: 1;",
+            "Ctrl" | "Alt" | "Win" => "flex: 0.1;",
+            _ => "min-width: 40px;",
+        };
+
+        rsx! {
+            button {
+                style: "{base_style} {scheme_style} {key_style}",
+                "{label}"
+            }
+        }
+    };
+
     rsx! {
         div {
             style: "display: flex; flex-direction: column; align-items: center; gap: 4px;",
@@ -56,29 +109,16 @@ pub fn QwertyKeyboard(props: KeyboardProps) -> Element {
                 div {
                     style: "display: flex; gap: 4px; width: 100%;",
                     for key in *row {
-                        {
-                            let is_highlighted = props.highlighted_keys.iter().any(|k| k == key);
-
-                            // 根据按键类型调整宽度和样式
-                            let base_style = if is_highlighted {
-                                "padding: 10px 12px; border: 1px solid #aaa; border-radius: 6px; background-color: #f9e74a; font-weight: bold; text-align: center;"
-                            } else {
-                                "padding: 10px 12px; border: 1px solid #ccc; border-radius: 6px; background-color: #f0f0f0; text-align: center;"
-                            };
-
-                            let key_style = match *key {
-                                "Backspace" | "\\" | "Tab" | "Space" | "Shift" | "Enter" => "flex: 1;",
-                                "Ctrl" | "Alt" | "Win" => "flex: 0.1;",
-                                _ => "min-width: 40px;",
-                            };
+                        {render_key(key)}
+                    }
+                }
+            }
 
-                            rsx! {
-                                button {
-                                    style: "{base_style} {key_style}",
-                                    "{key}"
-                                }
-                            }
-                        }
+            if !extra_keys.is_empty() {
+                div {
+                    style: "display: flex; gap: 4px; width: 100%;",
+                    for key in extra_keys.iter() {
+                        {render_key(key)}
                     }
                 }
             }
@@ -1,6 +1,6 @@
 mod keyboard;
-mod tooltip;
+mod rich_text;
 
 // pub use select::Select;
-// pub use tooltip::Tooltip;
 pub use keyboard::QwertyKeyboard;
+pub use rich_text::RichText;
@@ -0,0 +1,31 @@
+use dioxus::prelude::*;
+
+use zigen_trainer::rich_text::{self, Inline};
+
+/// 显示字根集中的富文本描述。格式有误的描述会原样显示为纯文本。
+#[component]
+pub fn RichText(source: String) -> Element {
+    render_nodes(&rich_text::parse_or_plain(&source))
+}
+
+fn render_nodes(nodes: &[Inline]) -> Element {
+    rsx! {
+        for node in nodes.iter() {
+            match node {
+                Inline::Text(text) => rsx! { "{text}" },
+                Inline::Bold(children) => rsx! { b { {render_nodes(children)} } },
+                Inline::Italic(children) => rsx! { i { {render_nodes(children)} } },
+                Inline::Link { href, children } => rsx! {
+                    a {
+                        href: "{href}",
+                        target: "_blank",
+                        rel: "noopener noreferrer",
+                        {render_nodes(children)}
+                    }
+                },
+                Inline::Zigen(zigen) => rsx! { span { class: "rich-text-zigen", "{zigen}" } },
+                Inline::Break => rsx! { br {} },
+            }
+        }
+    }
+}
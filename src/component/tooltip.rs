@@ -1,43 +0,0 @@
-use dioxus::prelude::*;
-
-// #[component]
-// pub fn Tooltip(props: TooltipProps) -> Element {
-//     rsx! {
-//         document::Link { rel: "stylesheet", href: asset!("./dioxus-style.css") }
-//         tooltip::Tooltip {
-//             class: "tooltip",
-//             disabled: props.disabled,
-//             open: props.open,
-//             default_open: props.default_open,
-//             on_open_change: props.on_open_change,
-//             attributes: props.attributes,
-//             {props.children}
-//         }
-//     }
-// }
-
-// #[component]
-// pub fn TooltipTrigger(props: TooltipTriggerProps) -> Element {
-//     rsx! {
-//         tooltip::TooltipTrigger {
-//             class: "tooltip-trigger",
-//             id: props.id,
-//             attributes: props.attributes,
-//             {props.children}
-//         }
-//     }
-// }
-
-// #[component]
-// pub fn TooltipContent(props: TooltipContentProps) -> Element {
-//     rsx! {
-//         tooltip::TooltipContent {
-//             class: "tooltip-content",
-//             id: props.id,
-//             side: props.side,
-//             align: props.align,
-//             attributes: props.attributes,
-//             {props.children}
-//         }
-//     }
-// }
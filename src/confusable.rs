@@ -0,0 +1,245 @@
+//! 根据字形的相似度，为字根集建议新的混淆集。
+//!
+//! 每个归并组的代表性字根都会以字体渲染为位图。编码不同、且尚未被放在同一聚类或
+//! 同一混淆集的两个字根，若位图足够相似，便可能被学习者搞混。
+
+use std::collections::{HashMap, HashSet};
+
+use crate::font::{Bitmap, Font};
+use crate::scheme::{LoadedScheme, SchemeZigen, Zigen, ZigenConfusableUnpopulated, ZigenGroup};
+
+#[derive(Clone, Debug)]
+pub struct SuggestOptions {
+    /// 相似度不低于此值的两个字根才会被放在同一混淆集。
+    pub threshold: f32,
+    /// 每个混淆集最多包含的字根数。
+    pub max_size: usize,
+    /// 渲染位图的边长（像素）。
+    pub bitmap_size: usize,
+}
+
+impl Default for SuggestOptions {
+    fn default() -> Self {
+        Self {
+            threshold: 0.8,
+            max_size: 4,
+            bitmap_size: 32,
+        }
+    }
+}
+
+/// 一个建议的混淆集。
+#[derive(Clone, Debug, PartialEq)]
+pub struct Suggestion {
+    /// 混淆集内的代表性字根。
+    pub zigens: Vec<String>,
+    /// 各字根的编码，与 zigens 一一对应。
+    pub codes: Vec<String>,
+    /// 字根两两之间的平均相似度。
+    pub score: f32,
+}
+
+impl Suggestion {
+    /// 转换为字根集中的混淆集（"混"）。
+    pub fn to_confusable(&self) -> SchemeZigen<ZigenConfusableUnpopulated> {
+        SchemeZigen::Confusable(ZigenConfusableUnpopulated {
+            zigens: self.zigens.iter().cloned().map(Zigen).collect(),
+            description: format!("（自动建议，相似度{:.2}）", self.score),
+        })
+    }
+}
+
+/// 渲染结果：能够渲染的字根，以及所有字体都缺少字形的字根。
+pub struct Rendered {
+    /// (代表性字根, 编码, 所在聚类的编号, 模糊后的位图)
+    pub roots: Vec<(String, String, usize, Bitmap)>,
+    pub missing: Vec<String>,
+}
+
+/// 以 fonts 中第一个含有该字形的字体渲染所有代表性字根。
+pub fn render_roots(
+    scheme: &LoadedScheme<ZigenConfusableUnpopulated>,
+    fonts: &[Font],
+    size: usize,
+) -> Result<Rendered, String> {
+    let mut rendered = Rendered {
+        roots: Vec::new(),
+        missing: Vec::new(),
+    };
+    let mut seen = HashSet::new();
+
+    let groups = scheme
+        .0
+        .iter()
+        .enumerate()
+        .filter_map(|(i, entry)| match entry {
+            SchemeZigen::Cluster(cluster) => Some(cluster.groups.iter().map(move |g| (i, g))),
+            SchemeZigen::Confusable(_) | SchemeZigen::Decomposition(_) => None,
+        })
+        .flatten();
+
+    for (cluster, group) in groups {
+        let Some(root) = group.zigens.first() else {
+            continue;
+        };
+
+        if !seen.insert(root.0.clone()) {
+            continue;
+        }
+
+        let mut chars = root.chars();
+        let (Some(c), None) = (chars.next(), chars.next()) else {
+            rendered.missing.push(root.0.clone());
+            continue;
+        };
+
+        let mut bitmap = None;
+        for font in fonts.iter() {
+            if let Some(rendered) = font.render(c, size)? {
+                bitmap = Some(rendered);
+                break;
+            }
+        }
+
+        match bitmap.filter(|bitmap| !bitmap.is_blank()) {
+            Some(bitmap) => rendered.roots.push((
+                root.0.clone(),
+                group.code.clone(),
+                cluster,
+                bitmap.blurred(),
+            )),
+            None => rendered.missing.push(root.0.clone()),
+        }
+    }
+
+    Ok(rendered)
+}
+
+/// 两个字根是否已经会被一同练习：同一聚类，或者同一混淆集。
+fn existing_pairs(scheme: &LoadedScheme<ZigenConfusableUnpopulated>) -> HashSet<(String, String)> {
+    let mut pairs = HashSet::new();
+
+    let mut add_all = |zigens: Vec<&str>| {
+        for a in zigens.iter() {
+            for b in zigens.iter() {
+                pairs.insert((a.to_string(), b.to_string()));
+            }
+        }
+    };
+
+    for entry in scheme.0.iter() {
+        match entry {
+            SchemeZigen::Cluster(cluster) => add_all(
+                cluster
+                    .groups
+                    .iter()
+                    .filter_map(|group: &ZigenGroup| group.zigens.first())
+                    .map(|zigen| zigen.0.as_str())
+                    .collect(),
+            ),
+            SchemeZigen::Confusable(confusable) => add_all(
+                confusable
+                    .zigens
+                    .iter()
+                    .map(|zigen| zigen.0.as_str())
+                    .collect(),
+            ),
+            SchemeZigen::Decomposition(_) => {}
+        }
+    }
+
+    pairs
+}
+
+/// 建议新的混淆集，按相似度由高至低排列。
+///
+/// 混淆集以全连接的方式合并：集内任意两个字根的相似度都须不低于 threshold，
+/// 这样才不会因为 A 像 B、B 像 C 而把毫不相像的 A 与 C 放在一起。
+pub fn suggest(
+    scheme: &LoadedScheme<ZigenConfusableUnpopulated>,
+    rendered: &Rendered,
+    options: &SuggestOptions,
+) -> Vec<Suggestion> {
+    let roots = &rendered.roots;
+    let existing = existing_pairs(scheme);
+
+    // 相似的字根对，包括编码相同的字根对（它们可以跟随其他字根一同加入混淆集）
+    let mut similar = HashMap::new();
+    // 编码不同的字根对，作为合并的依据
+    let mut candidates = Vec::new();
+
+    for i in 0..roots.len() {
+        for j in i + 1..roots.len() {
+            let (a, b) = (&roots[i], &roots[j]);
+            let score = a.3.similarity(&b.3);
+
+            if score < options.threshold {
+                continue;
+            }
+
+            similar.insert((i, j), score);
+
+            let same_code = a.1.eq_ignore_ascii_case(&b.1);
+            let together = a.2 == b.2 || existing.contains(&(a.0.clone(), b.0.clone()));
+            if !same_code && !together {
+                candidates.push((score, i, j));
+            }
+        }
+    }
+
+    candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let score_of = |i: usize, j: usize| similar.get(&(i.min(j), i.max(j))).copied();
+
+    // 字根 -> 所在的混淆集编号
+    let mut set_of = (0..roots.len()).collect::<Vec<_>>();
+    let mut sets = (0..roots.len()).map(|i| vec![i]).collect::<Vec<_>>();
+
+    for (_, i, j) in candidates {
+        let (a, b) = (set_of[i], set_of[j]);
+        if a == b || sets[a].len() + sets[b].len() > options.max_size {
+            continue;
+        }
+
+        let linked = sets[a]
+            .iter()
+            .all(|&x| sets[b].iter().all(|&y| score_of(x, y).is_some()));
+        if !linked {
+            continue;
+        }
+
+        let moved = std::mem::take(&mut sets[b]);
+        for &member in moved.iter() {
+            set_of[member] = a;
+        }
+        sets[a].extend(moved);
+    }
+
+    let mut suggestions = sets
+        .into_iter()
+        .filter(|set| set.len() >= 2)
+        .map(|mut set| {
+            set.sort_unstable();
+
+            let pairs = set
+                .iter()
+                .enumerate()
+                .flat_map(|(n, &x)| set[n + 1..].iter().map(move |&y| (x, y)))
+                .collect::<Vec<_>>();
+            let score = pairs
+                .iter()
+                .filter_map(|&(x, y)| score_of(x, y))
+                .sum::<f32>()
+                / pairs.len() as f32;
+
+            Suggestion {
+                zigens: set.iter().map(|&i| roots[i].0.clone()).collect(),
+                codes: set.iter().map(|&i| roots[i].1.clone()).collect(),
+                score,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    suggestions.sort_by(|a, b| b.score.total_cmp(&a.score));
+    suggestions
+}
@@ -0,0 +1,58 @@
+//! 检查字根字体是否含有字根集中所有私用区字根的字形。
+//!
+//! 私用区的字根只能以方案的字根字体显示，字体缺少字形时，用户只会看到方框（豆腐块）。
+//! 其他字根则可以由系统的中文字体显示，因此不在检查之列。
+
+use std::collections::HashSet;
+
+use crate::scheme::{self, LoadedScheme, ZigenConfusableUnpopulated};
+
+/// 一个缺少字形的字根。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MissingGlyph {
+    pub zigen: String,
+    /// 字根所在归并组的编码。
+    pub code: String,
+    /// 字体缺少的私用区字符。
+    pub missing: Vec<char>,
+}
+
+impl MissingGlyph {
+    /// 缺少的码位，比如「U+F0065 U+F0076」。
+    pub fn codepoints(&self) -> String {
+        self.missing
+            .iter()
+            .map(|c| format!("U+{:04X}", *c as u32))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// 列出字体（charset 为其含有的字符，见 font::charset）缺少字形的私用区字根。
+/// 同一个字根只会列出一次。
+pub fn missing_glyphs(
+    scheme: &LoadedScheme<ZigenConfusableUnpopulated>,
+    charset: &HashSet<char>,
+) -> Vec<MissingGlyph> {
+    let mut seen = HashSet::new();
+    let mut result = Vec::new();
+
+    for group in scheme.groups() {
+        for zigen in group.zigens.iter() {
+            let missing = zigen
+                .chars()
+                .filter(|c| scheme::is_pua(*c) && !charset.contains(c))
+                .collect::<Vec<_>>();
+
+            if !missing.is_empty() && seen.insert(zigen.0.as_str()) {
+                result.push(MissingGlyph {
+                    zigen: zigen.0.clone(),
+                    code: group.code.clone(),
+                    missing,
+                });
+            }
+        }
+    }
+
+    result
+}
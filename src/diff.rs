@@ -0,0 +1,259 @@
+//! 比较两个字根集（同一方案的两个版本，或者两个不同的方案）。
+//!
+//! 归并组以代表性字根（即第一个字根）配对：代表性字根相同的归并组视为同一组，
+//! 再逐项比较其编码、分类、成员与所在聚类。
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+use crate::scheme::{
+    LoadedScheme, SchemeZigen, ZigenClass, ZigenConfusableUnpopulated, ZigenGroup,
+};
+
+/// 两个字根集之间的单项差异。zigen 一律为归并组的代表性字根。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Change {
+    /// 新增的归并组。
+    Added { zigen: String, code: String },
+    /// 删除的归并组。
+    Removed { zigen: String, code: String },
+    /// 编码改变。
+    Recoded {
+        zigen: String,
+        from: String,
+        to: String,
+    },
+    /// 分类改变。
+    Reclassified {
+        zigen: String,
+        from: ZigenClass,
+        to: ZigenClass,
+    },
+    /// 归并组内的非代表性字根有所增减。
+    Members {
+        zigen: String,
+        added: Vec<String>,
+        removed: Vec<String>,
+    },
+    /// 所在聚类的其他成员改变（只考虑两边都存在的归并组）。
+    Regrouped {
+        zigen: String,
+        from: Vec<String>,
+        to: Vec<String>,
+    },
+}
+
+impl Change {
+    /// 本项差异涉及的代表性字根。
+    pub fn zigen(&self) -> &str {
+        match self {
+            Change::Added { zigen, .. }
+            | Change::Removed { zigen, .. }
+            | Change::Recoded { zigen, .. }
+            | Change::Reclassified { zigen, .. }
+            | Change::Members { zigen, .. }
+            | Change::Regrouped { zigen, .. } => zigen,
+        }
+    }
+
+    /// 差异的种类，面向用户。
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Change::Added { .. } => "新增",
+            Change::Removed { .. } => "删除",
+            Change::Recoded { .. } => "改码",
+            Change::Reclassified { .. } => "分类",
+            Change::Members { .. } => "归并",
+            Change::Regrouped { .. } => "聚类",
+        }
+    }
+
+    /// 差异的内容，面向用户。
+    pub fn detail(&self) -> String {
+        let list = |zigens: &[String]| {
+            if zigens.is_empty() {
+                String::from("（无）")
+            } else {
+                zigens.join("、")
+            }
+        };
+
+        match self {
+            Change::Added { code, .. } | Change::Removed { code, .. } => code.clone(),
+            Change::Recoded { from, to, .. } => {
+                let key = |code: &str| code.chars().next().map(|c| c.to_ascii_lowercase());
+                if key(from) != key(to) {
+                    format!("{from} → {to}（换键）")
+                } else {
+                    format!("{from} → {to}")
+                }
+            }
+            Change::Reclassified { from, to, .. } => {
+                format!("{} → {}", from.label(), to.label())
+            }
+            Change::Members { added, removed, .. } => {
+                let mut parts = Vec::new();
+                if !added.is_empty() {
+                    parts.push(format!("增加{}", added.join("、")));
+                }
+                if !removed.is_empty() {
+                    parts.push(format!("减少{}", removed.join("、")));
+                }
+                parts.join("，")
+            }
+            Change::Regrouped { from, to, .. } => {
+                format!("与{}同类 → 与{}同类", list(from), list(to))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = match self {
+            Change::Added { .. } => '+',
+            Change::Removed { .. } => '-',
+            _ => '~',
+        };
+
+        write!(
+            f,
+            "{sign} {} {}：{}",
+            self.zigen(),
+            self.kind(),
+            self.detail()
+        )
+    }
+}
+
+/// 各归并组的代表性字根 -> （归并组，所在聚类的其他代表性字根）。
+type GroupIndex<'a> = HashMap<&'a str, (&'a ZigenGroup, Vec<&'a str>)>;
+
+/// 为字根集建立索引，同时返回代表性字根在字根集中的顺序。
+fn index(scheme: &LoadedScheme<ZigenConfusableUnpopulated>) -> (Vec<&str>, GroupIndex<'_>) {
+    let mut order = Vec::new();
+    let mut groups = HashMap::new();
+
+    for entry in scheme.0.iter() {
+        let SchemeZigen::Cluster(cluster) = entry else {
+            continue;
+        };
+
+        let representatives = cluster
+            .groups
+            .iter()
+            .filter_map(|group| group.zigens.first())
+            .map(|zigen| zigen.0.as_str())
+            .collect::<Vec<_>>();
+
+        for group in cluster.groups.iter() {
+            let Some(first) = group.zigens.first() else {
+                continue;
+            };
+
+            let mates = representatives
+                .iter()
+                .copied()
+                .filter(|other| *other != first.0)
+                .collect();
+
+            // 同一代表性字根出现在多个归并组时，只取第一个
+            if !groups.contains_key(first.0.as_str()) {
+                order.push(first.0.as_str());
+                groups.insert(first.0.as_str(), (group, mates));
+            }
+        }
+    }
+
+    (order, groups)
+}
+
+/// 比较两个字根集，按 new 中的顺序列出差异，删除的归并组排在最后。
+pub fn diff(
+    old: &LoadedScheme<ZigenConfusableUnpopulated>,
+    new: &LoadedScheme<ZigenConfusableUnpopulated>,
+) -> Vec<Change> {
+    let (old_order, old_groups) = index(old);
+    let (new_order, new_groups) = index(new);
+
+    let mut changes = Vec::new();
+
+    for zigen in new_order.iter() {
+        let (new_group, new_mates) = &new_groups[zigen];
+
+        let Some((old_group, old_mates)) = old_groups.get(zigen) else {
+            changes.push(Change::Added {
+                zigen: zigen.to_string(),
+                code: new_group.code.clone(),
+            });
+            continue;
+        };
+
+        if old_group.code != new_group.code {
+            changes.push(Change::Recoded {
+                zigen: zigen.to_string(),
+                from: old_group.code.clone(),
+                to: new_group.code.clone(),
+            });
+        }
+
+        if old_group.classify != new_group.classify {
+            changes.push(Change::Reclassified {
+                zigen: zigen.to_string(),
+                from: old_group.classify.clone(),
+                to: new_group.classify.clone(),
+            });
+        }
+
+        let old_members = old_group.zigens[1..]
+            .iter()
+            .map(|z| z.0.as_str())
+            .collect::<BTreeSet<_>>();
+        let new_members = new_group.zigens[1..]
+            .iter()
+            .map(|z| z.0.as_str())
+            .collect::<BTreeSet<_>>();
+        if old_members != new_members {
+            changes.push(Change::Members {
+                zigen: zigen.to_string(),
+                added: new_members
+                    .difference(&old_members)
+                    .map(|z| z.to_string())
+                    .collect(),
+                removed: old_members
+                    .difference(&new_members)
+                    .map(|z| z.to_string())
+                    .collect(),
+            });
+        }
+
+        // 新增或删除的归并组已经另行列出，不应该让它们的同类也显示为聚类改变
+        let common = |mates: &[&str]| {
+            mates
+                .iter()
+                .filter(|mate| old_groups.contains_key(*mate) && new_groups.contains_key(*mate))
+                .map(|mate| mate.to_string())
+                .collect::<Vec<_>>()
+        };
+        let (from, to) = (common(old_mates), common(new_mates));
+        if from.iter().collect::<BTreeSet<_>>() != to.iter().collect::<BTreeSet<_>>() {
+            changes.push(Change::Regrouped {
+                zigen: zigen.to_string(),
+                from,
+                to,
+            });
+        }
+    }
+
+    for zigen in old_order
+        .iter()
+        .filter(|zigen| !new_groups.contains_key(*zigen))
+    {
+        changes.push(Change::Removed {
+            zigen: zigen.to_string(),
+            code: old_groups[zigen].0.code.clone(),
+        });
+    }
+
+    changes
+}
@@ -0,0 +1,104 @@
+//! 将字根集导出为 Anki 可以导入的卡组。
+//!
+//! 导出的格式为 Anki 的纯文本（TSV）格式，每张练习卡片对应一条笔记。Anki 导入纯文本时
+//! 无法设置复习进度，所有卡片都会成为新卡片，因此不导出练习器中的复习间隔。
+
+use crate::scheme::{LoadedScheme, SchemeZigen, ZigenConfusable};
+
+/// Anki 的纯文本格式以制表符分隔字段，字段内不可出现制表符与换行；
+/// 含有双引号的字段须以双引号包裹，并将内部的双引号写两次。
+fn escape_field(field: &str) -> String {
+    let field = field
+        .replace('\t', " ")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>");
+
+    if field.contains('"') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
+
+/// 将字根集（通常已经过 sort_to_options 处理）转换为 Anki 纯文本卡组。
+pub fn anki_tsv(scheme: &LoadedScheme<ZigenConfusable>, scheme_id: &str) -> String {
+    let columns = ["字根", "编码", "说明", "聚类说明", "标签"];
+
+    let mut output = String::new();
+    output.push_str("#separator:tab\n");
+    output.push_str("#html:true\n");
+    output.push_str(&format!("#columns:{}\n", columns.join("\t")));
+    output.push_str(&format!("#tags column:{}\n", columns.len()));
+
+    for zigen in &scheme.0 {
+        let (groups, description) = zigen.as_raw_parts();
+
+        // 拆分练习以整个字为正面，字根则与编码一同列在背面
+        let decomposition = match zigen {
+            SchemeZigen::Decomposition(dec) => Some(dec.character.as_str()),
+            _ => None,
+        };
+
+        let front = match decomposition {
+            Some(character) => character.to_owned(),
+            None => groups
+                .iter()
+                .map(|group| {
+                    group
+                        .zigens
+                        .iter()
+                        .map(|z| z.0.as_str())
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .collect::<Vec<_>>()
+                .join("<br>"),
+        };
+        let codes = groups
+            .iter()
+            .map(|group| {
+                let codes = group.codes().collect::<Vec<_>>().join(" / ");
+                match (decomposition, group.zigens.first()) {
+                    (Some(_), Some(root)) => format!("{} {codes}", root.0),
+                    _ => codes,
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("<br>");
+        let descriptions = groups
+            .iter()
+            .map(|group| group.description.as_str())
+            .collect::<Vec<_>>()
+            .join("<br>");
+
+        let tags = [
+            String::from("慧根"),
+            scheme_id.to_owned(),
+            String::from(match zigen {
+                SchemeZigen::Cluster(_) => "聚类",
+                SchemeZigen::Confusable(_) => "混淆",
+                SchemeZigen::Decomposition(_) => "拆分",
+            }),
+        ];
+
+        let mut fields = vec![
+            escape_field(&front),
+            escape_field(&codes),
+            escape_field(&descriptions),
+            escape_field(description),
+        ];
+
+        // Anki 的标签以空格分隔，因此标签内不可含有空格
+        fields.push(
+            tags.iter()
+                .map(|tag| tag.replace(' ', "_"))
+                .collect::<Vec<_>>()
+                .join(" "),
+        );
+
+        output.push_str(&fields.join("\t"));
+        output.push('\n');
+    }
+
+    output
+}
@@ -0,0 +1,144 @@
+//! 在字形中找出某个部件（字根）所在的位置，供练习器在例字中突出显示字根。
+//!
+//! 做法是把部件的字形缩放成不同的宽高，逐一放在字形的每个位置上，取相似度最高者。
+
+use super::Bitmap;
+
+/// 部件的宽高相对于位图大小的候选比例。字根在字中多数会被压扁或压窄，很少小于三成。
+const SCALES: [f32; 8] = [0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0];
+
+/// 最佳位置的相似度低于此值时，视为找不到部件。
+pub const MIN_SIMILARITY: f32 = 0.55;
+
+/// 部件在字形中的位置。
+#[derive(Clone, Debug, PartialEq)]
+pub struct Placement {
+    /// 部件所占的方框：(x, y, 宽, 高)，单位为像素
+    pub bounds: (usize, usize, usize, usize),
+    /// 部件与方框内字形的相似度，介于 0.0 与 1.0 之间
+    pub similarity: f32,
+    /// 与字形同样大小的遮罩，部件笔画附近的像素为 1.0，其余为 0.0
+    pub mask: Bitmap,
+}
+
+/// 在字形 glyph 中找出部件 part 的位置。两者须为同样大小的位图。
+///
+/// 任一位图为空白、大小不一致，或者最相似的位置仍低于 MIN_SIMILARITY 时返回 None。
+pub fn locate(glyph: &Bitmap, part: &Bitmap) -> Option<Placement> {
+    if glyph.size != part.size || glyph.is_blank() || part.is_blank() {
+        return None;
+    }
+
+    let size = glyph.size;
+    let glyph = glyph.blurred();
+    let part = crop(&part.blurred())?;
+
+    let mut best: Option<((usize, usize, usize, usize), f32)> = None;
+
+    for width in SCALES.map(|scale| ((size as f32 * scale) as usize).max(1)) {
+        for height in SCALES.map(|scale| ((size as f32 * scale) as usize).max(1)) {
+            let scaled = part.resized(width, height);
+            let scaled_norm = scaled.norm();
+
+            for y in 0..=size - height {
+                for x in 0..=size - width {
+                    let mut dot = 0.0;
+                    let mut glyph_norm = 0.0;
+
+                    for (row, scaled_row) in scaled.pixels.chunks(width).enumerate() {
+                        let glyph_row = &glyph.pixels[(y + row) * size + x..][..width];
+                        for (g, p) in glyph_row.iter().zip(scaled_row) {
+                            dot += g * p;
+                            glyph_norm += g * g;
+                        }
+                    }
+
+                    let norms = scaled_norm * glyph_norm.sqrt();
+                    let similarity = if norms == 0.0 { 0.0 } else { dot / norms };
+
+                    if best.is_none_or(|(_, best)| similarity > best) {
+                        best = Some(((x, y, width, height), similarity));
+                    }
+                }
+            }
+        }
+    }
+
+    let ((x, y, width, height), similarity) = best?;
+    if similarity < MIN_SIMILARITY {
+        return None;
+    }
+
+    let scaled = part.resized(width, height);
+    let mut mask = vec![0.0; size * size];
+    for (row, scaled_row) in scaled.pixels.chunks(width).enumerate() {
+        for (col, &p) in scaled_row.iter().enumerate() {
+            if p > 0.0 {
+                mask[(y + row) * size + x + col] = 1.0;
+            }
+        }
+    }
+
+    Some(Placement {
+        bounds: (x, y, width, height),
+        similarity,
+        mask: Bitmap { size, pixels: mask },
+    })
+}
+
+/// 长方形的灰度位图，只在本模块内用于缩放部件。
+struct Patch {
+    width: usize,
+    height: usize,
+    pixels: Vec<f32>,
+}
+
+impl Patch {
+    /// 以最近邻取样缩放至 width × height。
+    fn resized(&self, width: usize, height: usize) -> Patch {
+        let pixels = (0..height)
+            .flat_map(|y| {
+                let sy = (y * self.height / height).min(self.height - 1);
+                (0..width).map(move |x| {
+                    let sx = (x * self.width / width).min(self.width - 1);
+                    self.pixels[sy * self.width + sx]
+                })
+            })
+            .collect();
+
+        Patch {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    fn norm(&self) -> f32 {
+        self.pixels.iter().map(|p| p * p).sum::<f32>().sqrt()
+    }
+}
+
+/// 裁去位图四周的空白。位图完全空白时返回 None。
+fn crop(bitmap: &Bitmap) -> Option<Patch> {
+    let size = bitmap.size;
+    let inked = |x: usize, y: usize| bitmap.pixels[y * size + x] > 0.0;
+
+    let mut rows = (0..size).filter(|&y| (0..size).any(|x| inked(x, y)));
+    let mut cols = (0..size).filter(|&x| (0..size).any(|y| inked(x, y)));
+    let (top, left) = (rows.next()?, cols.next()?);
+    let (bottom, right) = (
+        rows.next_back().unwrap_or(top),
+        cols.next_back().unwrap_or(left),
+    );
+
+    let (width, height) = (right - left + 1, bottom - top + 1);
+    let pixels = (top..=bottom)
+        .flat_map(|y| bitmap.pixels[y * size + left..][..width].iter().copied())
+        .collect();
+
+    Some(Patch {
+        width,
+        height,
+        pixels,
+    })
+}
@@ -0,0 +1,599 @@
+//! 读取字体文件并渲染字形，供本地工具（如 zigen-confusables）使用；练习器也以此检查字根字体缺少的字形，
+//! 并在例字中找出字根的位置（见 locate）。
+//!
+//! 只支持 TrueType 轮廓（glyf 表）的 TTF、WOFF 与 WOFF2 字体。CFF 轮廓（多数 OTF）需要
+//! 先用其他工具转换为 TTF。
+
+mod locate;
+mod raster;
+mod woff2;
+
+use std::collections::{HashMap, HashSet};
+
+pub use locate::{MIN_SIMILARITY, Placement, locate};
+pub use raster::Bitmap;
+
+/// 复合字形的最大嵌套深度，防止损坏的字体造成无限递归。
+const MAX_COMPONENT_DEPTH: usize = 8;
+
+/// 字形轮廓中的一个点，单位为字体单位。
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// 一条闭合的轮廓，已经把二次贝塞尔曲线展开为折线。
+pub type Contour = Vec<Point>;
+
+/// 一个已解析的字体。
+pub struct Font {
+    tables: HashMap<[u8; 4], Vec<u8>>,
+    units_per_em: u16,
+    ascender: i16,
+    descender: i16,
+    long_loca: bool,
+    /// 码位 -> 字形编号
+    cmap: HashMap<u32, u16>,
+}
+
+/// 取出 data 中自 offset 起的 len 个字节。偏移量来自字体文件本身，因此须检查溢出。
+fn bytes(data: &[u8], offset: usize, len: usize) -> Result<&[u8], String> {
+    offset
+        .checked_add(len)
+        .and_then(|end| data.get(offset..end))
+        .ok_or_else(|| String::from("字体数据不完整"))
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, String> {
+    bytes(data, offset, 2).map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_i16(data: &[u8], offset: usize) -> Result<i16, String> {
+    read_u16(data, offset).map(|value| value as i16)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, String> {
+    bytes(data, offset, 4).map(|bytes| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn read_tag(data: &[u8], offset: usize) -> Result<[u8; 4], String> {
+    bytes(data, offset, 4).map(|bytes| [bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+impl Font {
+    /// 读取字体文件。
+    pub fn load(path: &str) -> Result<Self, String> {
+        let data = std::fs::read(path).map_err(|err| format!("无法读取「{path}」：{err}"))?;
+        Self::parse(&data).map_err(|err| format!("无法解析「{path}」：{err}"))
+    }
+
+    /// 解析 TTF、WOFF 或 WOFF2 格式的字体数据。
+    pub fn parse(data: &[u8]) -> Result<Self, String> {
+        if data.starts_with(b"OTTO") {
+            return Err(String::from("暂不支持CFF轮廓的字体，请先转换为TTF"));
+        }
+
+        let tables = font_tables(data)?;
+
+        let table = |tag: &[u8; 4]| {
+            tables
+                .get(tag)
+                .ok_or_else(|| format!("缺少 {} 表", String::from_utf8_lossy(tag)))
+        };
+
+        if !tables.contains_key(b"glyf") && tables.contains_key(b"CFF ") {
+            return Err(String::from("暂不支持CFF轮廓的字体，请先转换为TTF"));
+        }
+
+        let head = table(b"head")?;
+        let hhea = table(b"hhea")?;
+        table(b"glyf")?;
+        table(b"loca")?;
+
+        Ok(Self {
+            units_per_em: read_u16(head, 18)?.max(1),
+            long_loca: read_i16(head, 50)? != 0,
+            ascender: read_i16(hhea, 4)?,
+            descender: read_i16(hhea, 6)?,
+            cmap: parse_cmap(table(b"cmap")?)?,
+            tables,
+        })
+    }
+
+    /// 字符对应的字形编号。字体不含该字符时返回 None。
+    pub fn glyph_id(&self, c: char) -> Option<u16> {
+        self.cmap.get(&(c as u32)).copied().filter(|&id| id != 0)
+    }
+
+    /// 字形的轮廓。空白字形（如空格）没有任何轮廓。
+    pub fn outline(&self, glyph_id: u16) -> Result<Vec<Contour>, String> {
+        let mut contours = Vec::new();
+        self.append_outline(glyph_id, [1.0, 0.0, 0.0, 1.0, 0.0, 0.0], 0, &mut contours)?;
+        Ok(contours)
+    }
+
+    /// 在 size × size 的位图上渲染字符。字形按字体的上下行高缩放，水平方向置中。
+    pub fn render(&self, c: char, size: usize) -> Result<Option<Bitmap>, String> {
+        let Some(glyph_id) = self.glyph_id(c) else {
+            return Ok(None);
+        };
+
+        let contours = self.outline(glyph_id)?;
+        let height = (self.ascender as f32 - self.descender as f32).max(self.units_per_em as f32);
+        let scale = size as f32 / height;
+
+        // 以字形的实际宽度置中，而不是前进宽度，这样不同字体的字形比较容易对齐
+        let (min_x, max_x) = contours
+            .iter()
+            .flatten()
+            .fold((f32::MAX, f32::MIN), |(min, max), p| {
+                (min.min(p.x), max.max(p.x))
+            });
+        let center_x = if min_x <= max_x {
+            (min_x + max_x) / 2.0
+        } else {
+            0.0
+        };
+
+        let transformed = contours
+            .iter()
+            .map(|contour| {
+                contour
+                    .iter()
+                    .map(|p| Point {
+                        x: (p.x - center_x) * scale + size as f32 / 2.0,
+                        y: (self.ascender as f32 - p.y) * scale,
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        Ok(Some(raster::fill(&transformed, size)))
+    }
+
+    fn glyph_data(&self, glyph_id: u16) -> Result<&[u8], String> {
+        let loca = &self.tables[b"loca"];
+        let glyf = &self.tables[b"glyf"];
+        let id = glyph_id as usize;
+
+        let (start, end) = if self.long_loca {
+            (
+                read_u32(loca, id * 4)? as usize,
+                read_u32(loca, id * 4 + 4)? as usize,
+            )
+        } else {
+            (
+                read_u16(loca, id * 2)? as usize * 2,
+                read_u16(loca, id * 2 + 2)? as usize * 2,
+            )
+        };
+
+        if start > end {
+            return Err(format!("字形{glyph_id}的位置无效"));
+        }
+
+        glyf.get(start..end)
+            .ok_or_else(|| format!("字形{glyph_id}超出 glyf 表"))
+    }
+
+    /// 把字形的轮廓经 transform（[a, b, c, d, e, f]，即 x' = ax + cy + e，y' = bx + dy + f）
+    /// 变换后加入 contours。
+    fn append_outline(
+        &self,
+        glyph_id: u16,
+        transform: [f32; 6],
+        depth: usize,
+        contours: &mut Vec<Contour>,
+    ) -> Result<(), String> {
+        if depth > MAX_COMPONENT_DEPTH {
+            return Err(String::from("复合字形嵌套过深"));
+        }
+
+        let data = self.glyph_data(glyph_id)?;
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        let contour_count = read_i16(data, 0)?;
+        if contour_count >= 0 {
+            for contour in simple_glyph(data, contour_count as usize)? {
+                contours.push(contour.into_iter().map(|p| apply(&transform, p)).collect());
+            }
+            return Ok(());
+        }
+
+        // 复合字形
+        let mut offset = 10;
+        loop {
+            let flags = read_u16(data, offset)?;
+            let component = read_u16(data, offset + 2)?;
+            offset += 4;
+
+            let (dx, dy) = if flags & 0x0001 != 0 {
+                let args = (
+                    read_i16(data, offset)? as f32,
+                    read_i16(data, offset + 2)? as f32,
+                );
+                offset += 4;
+                args
+            } else {
+                let args = (
+                    data.get(offset).copied().ok_or("字体数据不完整")? as i8 as f32,
+                    data.get(offset + 1).copied().ok_or("字体数据不完整")? as i8 as f32,
+                );
+                offset += 2;
+                args
+            };
+
+            // 以锚点对齐的复合字形很少见，这里直接忽略其位移
+            let (dx, dy) = if flags & 0x0002 != 0 {
+                (dx, dy)
+            } else {
+                (0.0, 0.0)
+            };
+
+            let f2dot14 = |offset| read_i16(data, offset).map(|v| v as f32 / 16384.0);
+            let (a, b, c, d) = if flags & 0x0008 != 0 {
+                let scale = f2dot14(offset)?;
+                offset += 2;
+                (scale, 0.0, 0.0, scale)
+            } else if flags & 0x0040 != 0 {
+                let scales = (f2dot14(offset)?, f2dot14(offset + 2)?);
+                offset += 4;
+                (scales.0, 0.0, 0.0, scales.1)
+            } else if flags & 0x0080 != 0 {
+                let matrix = (
+                    f2dot14(offset)?,
+                    f2dot14(offset + 2)?,
+                    f2dot14(offset + 4)?,
+                    f2dot14(offset + 6)?,
+                );
+                offset += 8;
+                matrix
+            } else {
+                (1.0, 0.0, 0.0, 1.0)
+            };
+
+            let [ta, tb, tc, td, te, tf] = transform;
+            let combined = [
+                ta * a + tc * b,
+                tb * a + td * b,
+                ta * c + tc * d,
+                tb * c + td * d,
+                ta * dx + tc * dy + te,
+                tb * dx + td * dy + tf,
+            ];
+
+            self.append_outline(component, combined, depth + 1, contours)?;
+
+            if flags & 0x0020 == 0 {
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn apply(transform: &[f32; 6], p: Point) -> Point {
+    let [a, b, c, d, e, f] = *transform;
+    Point {
+        x: a * p.x + c * p.y + e,
+        y: b * p.x + d * p.y + f,
+    }
+}
+
+/// 解析简单字形，并把二次贝塞尔曲线展开为折线。
+fn simple_glyph(data: &[u8], contour_count: usize) -> Result<Vec<Contour>, String> {
+    let mut end_points = Vec::with_capacity(contour_count);
+    for i in 0..contour_count {
+        end_points.push(read_u16(data, 10 + i * 2)? as usize);
+    }
+
+    let point_count = end_points.last().map_or(0, |last| last + 1);
+    let instruction_len = read_u16(data, 10 + contour_count * 2)? as usize;
+    let mut offset = 12 + contour_count * 2 + instruction_len;
+
+    let byte = |offset: usize| data.get(offset).copied().ok_or("字体数据不完整");
+
+    let mut flags = Vec::with_capacity(point_count);
+    while flags.len() < point_count {
+        let flag = byte(offset)?;
+        offset += 1;
+        flags.push(flag);
+
+        if flag & 0x08 != 0 {
+            let repeat = byte(offset)?;
+            offset += 1;
+            flags.extend(std::iter::repeat_n(flag, repeat as usize));
+        }
+    }
+    flags.truncate(point_count);
+
+    // x 与 y 坐标的编码方式相同，只是标志位不同
+    let mut read_coordinates = |short_bit: u8, same_bit: u8| -> Result<Vec<f32>, String> {
+        let mut value = 0i32;
+        let mut coordinates = Vec::with_capacity(point_count);
+
+        for &flag in flags.iter() {
+            if flag & short_bit != 0 {
+                let delta = byte(offset)? as i32;
+                offset += 1;
+                value += if flag & same_bit != 0 { delta } else { -delta };
+            } else if flag & same_bit == 0 {
+                value += read_i16(data, offset)? as i32;
+                offset += 2;
+            }
+            coordinates.push(value as f32);
+        }
+
+        Ok(coordinates)
+    };
+
+    let xs = read_coordinates(0x02, 0x10)?;
+    let ys = read_coordinates(0x04, 0x20)?;
+
+    let mut contours = Vec::with_capacity(contour_count);
+    let mut start = 0;
+
+    for &end in end_points.iter() {
+        if end < start || end >= point_count {
+            return Err(String::from("字形轮廓的端点无效"));
+        }
+
+        let points = (start..=end)
+            .map(|i| (Point { x: xs[i], y: ys[i] }, flags[i] & 0x01 != 0))
+            .collect::<Vec<_>>();
+        contours.push(flatten_quadratic(&points));
+        start = end + 1;
+    }
+
+    Ok(contours)
+}
+
+/// 二次贝塞尔曲线每段展开成的线段数。
+const CURVE_STEPS: usize = 4;
+
+/// 把由在线点与离线点组成的 TrueType 轮廓展开为折线。
+fn flatten_quadratic(points: &[(Point, bool)]) -> Contour {
+    let midpoint = |a: Point, b: Point| Point {
+        x: (a.x + b.x) / 2.0,
+        y: (a.y + b.y) / 2.0,
+    };
+
+    let Some(first_on) = points.iter().position(|(_, on)| *on) else {
+        // 全部都是离线点：以相邻离线点的中点作为在线点
+        let mut contour = Vec::new();
+        for i in 0..points.len() {
+            let control = points[i].0;
+            let next = points[(i + 1) % points.len()].0;
+            let previous = points[(i + points.len() - 1) % points.len()].0;
+            push_curve(
+                &mut contour,
+                midpoint(previous, control),
+                control,
+                midpoint(control, next),
+            );
+        }
+        return contour;
+    };
+
+    let mut contour = vec![points[first_on].0];
+    let mut control: Option<Point> = None;
+
+    for i in 1..=points.len() {
+        let (point, on) = points[(first_on + i) % points.len()];
+        let current = *contour.last().unwrap();
+
+        match (on, control) {
+            (true, None) => contour.push(point),
+            (true, Some(c)) => {
+                push_curve(&mut contour, current, c, point);
+                control = None;
+            }
+            (false, None) => control = Some(point),
+            (false, Some(c)) => {
+                let mid = midpoint(c, point);
+                push_curve(&mut contour, current, c, mid);
+                control = Some(point);
+            }
+        }
+    }
+
+    contour
+}
+
+fn push_curve(contour: &mut Contour, from: Point, control: Point, to: Point) {
+    if contour.is_empty() {
+        contour.push(from);
+    }
+
+    for step in 1..=CURVE_STEPS {
+        let t = step as f32 / CURVE_STEPS as f32;
+        let u = 1.0 - t;
+        contour.push(Point {
+            x: u * u * from.x + 2.0 * u * t * control.x + t * t * to.x,
+            y: u * u * from.y + 2.0 * u * t * control.y + t * t * to.y,
+        });
+    }
+}
+
+/// 读取字体中的所有表。TTF、OTF、WOFF 与 WOFF2 的表格式相同，只是封装不同。
+fn font_tables(data: &[u8]) -> Result<HashMap<[u8; 4], Vec<u8>>, String> {
+    match data.get(..4) {
+        Some(b"wOFF") => woff_tables(data),
+        Some(b"wOF2") => woff2::woff2_tables(data),
+        Some([0, 1, 0, 0] | b"true" | b"OTTO") => sfnt_tables(data),
+        _ => Err(String::from("无法识别的字体格式")),
+    }
+}
+
+/// 字体含有字形的所有字符。
+///
+/// 只读取 cmap 表，不涉及轮廓，因此 CFF 轮廓的 OTF 字体也可以使用。
+pub fn charset(data: &[u8]) -> Result<HashSet<char>, String> {
+    let tables = font_tables(data)?;
+    let cmap = tables
+        .get(b"cmap")
+        .ok_or_else(|| String::from("缺少 cmap 表"))?;
+
+    Ok(parse_cmap(cmap)?
+        .into_iter()
+        .filter(|&(_, glyph)| glyph != 0)
+        .filter_map(|(code, _)| char::from_u32(code))
+        .collect())
+}
+
+fn sfnt_tables(data: &[u8]) -> Result<HashMap<[u8; 4], Vec<u8>>, String> {
+    let count = read_u16(data, 4)? as usize;
+    let mut tables = HashMap::new();
+
+    for i in 0..count {
+        let record = 12 + i * 16;
+        let tag = read_tag(data, record)?;
+        let offset = read_u32(data, record + 8)? as usize;
+        let len = read_u32(data, record + 12)? as usize;
+
+        let table = bytes(data, offset, len)
+            .map_err(|_| format!("{} 表超出文件范围", String::from_utf8_lossy(&tag)))?;
+        tables.insert(tag, table.to_vec());
+    }
+
+    Ok(tables)
+}
+
+fn woff_tables(data: &[u8]) -> Result<HashMap<[u8; 4], Vec<u8>>, String> {
+    let count = read_u16(data, 12)? as usize;
+    let mut tables = HashMap::new();
+
+    for i in 0..count {
+        let record = 44 + i * 20;
+        let tag = read_tag(data, record)?;
+        let offset = read_u32(data, record + 4)? as usize;
+        let compressed_len = read_u32(data, record + 8)? as usize;
+        let original_len = read_u32(data, record + 12)? as usize;
+
+        let table = bytes(data, offset, compressed_len)
+            .map_err(|_| format!("{} 表超出文件范围", String::from_utf8_lossy(&tag)))?;
+
+        let table = if compressed_len < original_len {
+            // 以表目录中的原始长度为上限，防止损坏的字体耗尽内存
+            let table =
+                miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(table, original_len)
+                    .map_err(|err| {
+                        format!("无法解压 {} 表：{err}", String::from_utf8_lossy(&tag))
+                    })?;
+            if table.len() != original_len {
+                return Err(format!(
+                    "{} 表解压后的长度与表目录不符",
+                    String::from_utf8_lossy(&tag)
+                ));
+            }
+            table
+        } else {
+            table.to_vec()
+        };
+
+        tables.insert(tag, table);
+    }
+
+    Ok(tables)
+}
+
+/// 解析 cmap 表。优先使用完整Unicode（格式12）的子表，其次为BMP（格式4）的子表。
+fn parse_cmap(cmap: &[u8]) -> Result<HashMap<u32, u16>, String> {
+    let count = read_u16(cmap, 2)? as usize;
+    let mut best: Option<(u16, usize)> = None;
+
+    for i in 0..count {
+        let record = 4 + i * 8;
+        let platform = read_u16(cmap, record)?;
+        let encoding = read_u16(cmap, record + 2)?;
+        let offset = read_u32(cmap, record + 4)? as usize;
+
+        let unicode = matches!((platform, encoding), (0, _) | (3, 1) | (3, 10));
+        if !unicode {
+            continue;
+        }
+
+        let format = read_u16(cmap, offset)?;
+        if matches!(format, 4 | 12) && best.is_none_or(|(best_format, _)| format > best_format) {
+            best = Some((format, offset));
+        }
+    }
+
+    let mut map = HashMap::new();
+
+    match best {
+        Some((12, offset)) => {
+            let groups = read_u32(cmap, offset.saturating_add(12))? as usize;
+            let first_group = offset.saturating_add(16);
+            // 组数不能多于表中实际容纳的数量，否则损坏的字体会让循环空转很久
+            if groups > cmap.len().saturating_sub(first_group) / 12 {
+                return Err(String::from("cmap 表的组数超出表的范围"));
+            }
+
+            let mut total = 0u32;
+            for i in 0..groups {
+                let group = first_group + i * 12;
+                let start = read_u32(cmap, group)?;
+                // 码位不会超过 U+10FFFF，字形编号也只有 16 位
+                let end = read_u32(cmap, group + 4)?.min(char::MAX as u32);
+                let glyph = read_u32(cmap, group + 8)?;
+
+                if start > end {
+                    continue;
+                }
+
+                total += end - start + 1;
+                if total > char::MAX as u32 + 1 {
+                    return Err(String::from("cmap 表的码位范围重叠过多"));
+                }
+
+                for (n, code) in (start..=end).enumerate() {
+                    match glyph.checked_add(n as u32).map(u16::try_from) {
+                        Some(Ok(glyph)) => map.insert(code, glyph),
+                        _ => break,
+                    };
+                }
+            }
+        }
+
+        Some((_, offset)) => {
+            let segments = read_u16(cmap, offset + 6)? as usize / 2;
+            let ends = offset + 14;
+            let starts = ends + segments * 2 + 2;
+            let deltas = starts + segments * 2;
+            let range_offsets = deltas + segments * 2;
+
+            for i in 0..segments {
+                let end = read_u16(cmap, ends + i * 2)?;
+                let start = read_u16(cmap, starts + i * 2)?;
+                let delta = read_u16(cmap, deltas + i * 2)?;
+                let range_offset = read_u16(cmap, range_offsets + i * 2)? as usize;
+
+                if start > end || start == 0xffff {
+                    continue;
+                }
+
+                for code in start..=end {
+                    let glyph = if range_offset == 0 {
+                        code.wrapping_add(delta)
+                    } else {
+                        let index =
+                            range_offsets + i * 2 + range_offset + (code - start) as usize * 2;
+                        match read_u16(cmap, index)? {
+                            0 => 0,
+                            glyph => glyph.wrapping_add(delta),
+                        }
+                    };
+
+                    map.insert(code as u32, glyph);
+                }
+            }
+        }
+
+        None => return Err(String::from("字体没有Unicode字符映射表")),
+    }
+
+    Ok(map)
+}
@@ -0,0 +1,132 @@
+//! 把展开后的轮廓填充为灰度位图，并比较位图之间的相似度。
+
+use super::{Contour, Point};
+
+/// 每个像素在垂直方向上的采样次数。水平方向则精确计算覆盖率。
+const SUBSAMPLES: usize = 4;
+
+/// 正方形的灰度位图，每个像素的值为 0.0（空白）至 1.0（全黑）。
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bitmap {
+    pub size: usize,
+    pub pixels: Vec<f32>,
+}
+
+impl Bitmap {
+    /// 位图是否完全空白。
+    pub fn is_blank(&self) -> bool {
+        self.pixels.iter().all(|&p| p == 0.0)
+    }
+
+    /// 以 3×3 的方框模糊位图，使相差一两个像素的笔画仍然被视为相似。
+    pub fn blurred(&self) -> Bitmap {
+        let size = self.size as isize;
+        let mut pixels = vec![0.0; self.pixels.len()];
+
+        for y in 0..size {
+            for x in 0..size {
+                let mut sum = 0.0;
+                for (dx, dy) in (-1..=1).flat_map(|dx| (-1..=1).map(move |dy| (dx, dy))) {
+                    let (sx, sy) = (x + dx, y + dy);
+                    if (0..size).contains(&sx) && (0..size).contains(&sy) {
+                        sum += self.pixels[(sy * size + sx) as usize];
+                    }
+                }
+                pixels[(y * size + x) as usize] = sum / 9.0;
+            }
+        }
+
+        Bitmap {
+            size: self.size,
+            pixels,
+        }
+    }
+
+    /// 两个同样大小的位图的余弦相似度，介于 0.0 与 1.0 之间。
+    pub fn similarity(&self, other: &Bitmap) -> f32 {
+        let dot = self
+            .pixels
+            .iter()
+            .zip(other.pixels.iter())
+            .map(|(a, b)| a * b)
+            .sum::<f32>();
+        let norm = |pixels: &[f32]| pixels.iter().map(|p| p * p).sum::<f32>().sqrt();
+        let norms = norm(&self.pixels) * norm(&other.pixels);
+
+        if norms == 0.0 { 0.0 } else { dot / norms }
+    }
+}
+
+/// 以非零环绕规则填充轮廓。轮廓的坐标以像素为单位，y 轴向下。
+pub fn fill(contours: &[Contour], size: usize) -> Bitmap {
+    let mut pixels = vec![0.0f32; size * size];
+
+    // (起点, 终点, 方向)，水平的线段不影响扫描线
+    let edges = contours
+        .iter()
+        .filter(|contour| contour.len() >= 2)
+        .flat_map(|contour| {
+            contour
+                .iter()
+                .zip(contour.iter().cycle().skip(1))
+                .take(contour.len())
+        })
+        .filter(|(a, b)| a.y != b.y)
+        .map(|(&a, &b)| if a.y < b.y { (a, b, 1) } else { (b, a, -1) })
+        .collect::<Vec<(Point, Point, i32)>>();
+
+    let mut crossings = Vec::new();
+
+    for row in 0..size * SUBSAMPLES {
+        let y = (row as f32 + 0.5) / SUBSAMPLES as f32;
+
+        crossings.clear();
+        crossings.extend(
+            edges
+                .iter()
+                .filter(|(top, bottom, _)| top.y <= y && y < bottom.y)
+                .map(|(top, bottom, winding)| {
+                    let t = (y - top.y) / (bottom.y - top.y);
+                    (top.x + t * (bottom.x - top.x), *winding)
+                }),
+        );
+        crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let pixel_row = &mut pixels[(row / SUBSAMPLES) * size..][..size];
+        let mut winding = 0;
+
+        for pair in crossings.windows(2) {
+            winding += pair[0].1;
+            if winding != 0 {
+                add_span(pixel_row, pair[0].0, pair[1].0);
+            }
+        }
+    }
+
+    for pixel in pixels.iter_mut() {
+        *pixel = (*pixel / SUBSAMPLES as f32).min(1.0);
+    }
+
+    Bitmap { size, pixels }
+}
+
+/// 把 [from, to) 的水平覆盖率累加到一行像素上。
+fn add_span(row: &mut [f32], from: f32, to: f32) {
+    let from = from.clamp(0.0, row.len() as f32);
+    let to = to.clamp(0.0, row.len() as f32);
+
+    if from >= to {
+        return;
+    }
+
+    let first = from.floor() as usize;
+    let last = (to.ceil() as usize).min(row.len());
+
+    for (x, pixel) in row.iter_mut().enumerate().take(last).skip(first) {
+        let left = from.max(x as f32);
+        let right = to.min(x as f32 + 1.0);
+        if right > left {
+            *pixel += right - left;
+        }
+    }
+}
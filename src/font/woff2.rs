@@ -0,0 +1,426 @@
+//! 读取 WOFF2 字体中的表。
+//!
+//! WOFF2 把所有表串接后以 Brotli 压缩，并且默认把 glyf 与 loca 表转换为更易压缩的格式。
+//! 这里把 glyf 与 loca 还原为 TrueType 的原始格式；其他经过转换的表（目前只有 hmtx）
+//! 用不上，直接略过。
+
+use std::collections::HashMap;
+use std::io::Read;
+
+use super::{read_i16, read_tag, read_u16, read_u32};
+
+/// 表目录中以 6 位索引表示的常用表名，见 WOFF2 规范 5.1 节。
+const KNOWN_TAGS: [&[u8; 4]; 63] = [
+    b"cmap", b"head", b"hhea", b"hmtx", b"maxp", b"name", b"OS/2", b"post", b"cvt ", b"fpgm",
+    b"glyf", b"loca", b"prep", b"CFF ", b"VORG", b"EBDT", b"EBLC", b"gasp", b"hdmx", b"kern",
+    b"LTSH", b"PCLT", b"VDMX", b"vhea", b"vmtx", b"BASE", b"GDEF", b"GPOS", b"GSUB", b"EBSC",
+    b"JSTF", b"MATH", b"CBDT", b"CBLC", b"COLR", b"CPAL", b"SVG ", b"sbix", b"acnt", b"avar",
+    b"bdat", b"bloc", b"bsln", b"cvar", b"fdsc", b"feat", b"fmtx", b"fvar", b"gvar", b"hsty",
+    b"just", b"lcar", b"mort", b"morx", b"opbd", b"prop", b"trak", b"Zapf", b"Silf", b"Glat",
+    b"Gloc", b"Feat", b"Sill",
+];
+
+/// 解压后所有表的总长度上限，防止恶意的字体耗尽内存。
+const MAX_DECOMPRESSED_LEN: usize = 64 << 20;
+
+/// 依序读取字节的游标。所有读取都会检查边界。
+struct Cursor<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .offset
+            .checked_add(len)
+            .filter(|&end| end <= self.data.len())
+            .ok_or_else(|| String::from("字体数据不完整"))?;
+        let bytes = &self.data[self.offset..end];
+        self.offset = end;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        let value = read_u16(self.data, self.offset)?;
+        self.offset += 2;
+        Ok(value)
+    }
+
+    fn i16(&mut self) -> Result<i16, String> {
+        let value = read_i16(self.data, self.offset)?;
+        self.offset += 2;
+        Ok(value)
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let value = read_u32(self.data, self.offset)?;
+        self.offset += 4;
+        Ok(value)
+    }
+
+    /// UIntBase128：每字节 7 位，最多 5 字节，不得有前导零。
+    fn base128(&mut self) -> Result<u32, String> {
+        let mut value = 0u32;
+
+        for i in 0..5 {
+            let byte = self.u8()?;
+            if i == 0 && byte == 0x80 {
+                return Err(String::from("UIntBase128 有前导零"));
+            }
+            if value & 0xFE00_0000 != 0 {
+                return Err(String::from("UIntBase128 溢出"));
+            }
+
+            value = (value << 7) | (byte & 0x7F) as u32;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+
+        Err(String::from("UIntBase128 过长"))
+    }
+
+    /// 255UInt16：以 253、254、255 为前缀的变长整数。
+    fn u255(&mut self) -> Result<u16, String> {
+        Ok(match self.u8()? {
+            253 => self.u16()?,
+            254 => 253 * 2 + self.u8()? as u16,
+            255 => 253 + self.u8()? as u16,
+            code => code as u16,
+        })
+    }
+}
+
+/// 表目录中的一项。
+struct TableEntry {
+    tag: [u8; 4],
+    /// 在解压后数据中的长度（经过转换的表为转换后的长度）
+    len: usize,
+    transformed: bool,
+}
+
+pub(super) fn woff2_tables(data: &[u8]) -> Result<HashMap<[u8; 4], Vec<u8>>, String> {
+    if read_tag(data, 4)? == *b"ttcf" {
+        return Err(String::from("暂不支持WOFF2字体集合"));
+    }
+
+    let count = read_u16(data, 12)? as usize;
+    let compressed_len = read_u32(data, 20)? as usize;
+
+    let mut cursor = Cursor::new(data);
+    cursor.offset = 48;
+
+    let mut entries = Vec::with_capacity(count);
+    for _ in 0..count {
+        let flags = cursor.u8()?;
+        let tag = match KNOWN_TAGS.get((flags & 0x3F) as usize) {
+            Some(tag) => **tag,
+            None => cursor.u32()?.to_be_bytes(),
+        };
+        let version = flags >> 6;
+        let original_len = cursor.base128()? as usize;
+
+        // glyf 与 loca 的转换版本 0 表示经过转换，3 表示没有；其他表则相反
+        let glyf_or_loca = matches!(&tag, b"glyf" | b"loca");
+        let has_transform = if glyf_or_loca {
+            version == 0
+        } else {
+            version != 0
+        };
+        let len = if has_transform {
+            cursor.base128()? as usize
+        } else {
+            original_len
+        };
+
+        entries.push(TableEntry {
+            tag,
+            len,
+            transformed: has_transform,
+        });
+    }
+
+    let total_len = entries
+        .iter()
+        .try_fold(0usize, |total, entry| total.checked_add(entry.len))
+        .filter(|&total| total <= MAX_DECOMPRESSED_LEN)
+        .ok_or_else(|| String::from("WOFF2字体的表过大"))?;
+
+    let compressed = cursor.bytes(compressed_len)?;
+    let mut decompressed = Vec::with_capacity(total_len);
+    brotli_decompressor::Decompressor::new(compressed, 4096)
+        .take(total_len as u64 + 1)
+        .read_to_end(&mut decompressed)
+        .map_err(|err| format!("无法解压WOFF2字体：{err}"))?;
+
+    if decompressed.len() != total_len {
+        return Err(String::from("WOFF2字体解压后的长度与表目录不符"));
+    }
+
+    let mut tables = HashMap::new();
+    let mut transformed_glyf = None;
+    let mut offset = 0;
+
+    for entry in entries.iter() {
+        let table = &decompressed[offset..offset + entry.len];
+        offset += entry.len;
+
+        match (&entry.tag, entry.transformed) {
+            (b"glyf", true) => transformed_glyf = Some(table),
+            // 转换后的 loca 表为空，由 glyf 还原；其他经过转换的表用不上
+            (_, true) => (),
+            (_, false) => {
+                tables.insert(entry.tag, table.to_vec());
+            }
+        }
+    }
+
+    if let Some(glyf) = transformed_glyf {
+        let (glyf, loca) = reconstruct_glyf(glyf)?;
+        tables.insert(*b"glyf", glyf);
+        tables.insert(*b"loca", loca);
+    }
+
+    Ok(tables)
+}
+
+/// 复合字形中各部件的标志位。
+const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+const WE_HAVE_A_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+const WE_HAVE_INSTRUCTIONS: u16 = 0x0100;
+
+/// 把转换后的 glyf 表还原为原始的 glyf 与 loca 表，见 WOFF2 规范 5.2 节。
+fn reconstruct_glyf(data: &[u8]) -> Result<(Vec<u8>, Vec<u8>), String> {
+    let mut header = Cursor::new(data);
+    // 保留字段与 optionFlags。optionFlags 只表示是否附有重叠轮廓的标志，不影响字形本身
+    header.u16()?;
+    header.u16()?;
+    let glyph_count = header.u16()? as usize;
+    let index_format = header.u16()?;
+
+    let mut streams = Vec::with_capacity(7);
+    for _ in 0..7 {
+        streams.push(header.u32()? as usize);
+    }
+
+    let mut body = Cursor::new(data);
+    body.offset = header.offset;
+    let mut stream = |len: usize| body.bytes(len).map(Cursor::new);
+
+    let mut contour_counts = stream(streams[0])?;
+    let mut point_counts = stream(streams[1])?;
+    let mut flag_stream = stream(streams[2])?;
+    let mut glyph_stream = stream(streams[3])?;
+    let mut composite_stream = stream(streams[4])?;
+    let mut bbox_stream = stream(streams[5])?;
+    let mut instruction_stream = stream(streams[6])?;
+
+    let bbox_bitmap = bbox_stream.bytes(glyph_count.div_ceil(32) * 4)?;
+    let has_bbox = |glyph: usize| bbox_bitmap[glyph / 8] & (0x80 >> (glyph % 8)) != 0;
+
+    let mut glyf = Vec::new();
+    let mut offsets = Vec::with_capacity(glyph_count + 1);
+
+    for glyph in 0..glyph_count {
+        offsets.push(glyf.len());
+        let contour_count = contour_counts.i16()?;
+
+        match contour_count {
+            0 => {
+                if has_bbox(glyph) {
+                    return Err(format!("空白字形{glyph}不应有边界框"));
+                }
+            }
+
+            -1 => {
+                if !has_bbox(glyph) {
+                    return Err(format!("复合字形{glyph}缺少边界框"));
+                }
+
+                glyf.extend_from_slice(&contour_count.to_be_bytes());
+                glyf.extend_from_slice(bbox_stream.bytes(8)?);
+
+                let start = composite_stream.offset;
+                let mut has_instructions = false;
+                loop {
+                    let flags = composite_stream.u16()?;
+                    composite_stream.u16()?;
+
+                    let mut len = if flags & ARG_1_AND_2_ARE_WORDS != 0 {
+                        4
+                    } else {
+                        2
+                    };
+                    if flags & WE_HAVE_A_SCALE != 0 {
+                        len += 2;
+                    } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+                        len += 4;
+                    } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+                        len += 8;
+                    }
+                    composite_stream.bytes(len)?;
+
+                    has_instructions |= flags & WE_HAVE_INSTRUCTIONS != 0;
+                    if flags & MORE_COMPONENTS == 0 {
+                        break;
+                    }
+                }
+                glyf.extend_from_slice(&composite_stream.data[start..composite_stream.offset]);
+
+                if has_instructions {
+                    let len = glyph_stream.u255()?;
+                    glyf.extend_from_slice(&len.to_be_bytes());
+                    glyf.extend_from_slice(instruction_stream.bytes(len as usize)?);
+                }
+            }
+
+            contour_count if contour_count > 0 => {
+                let mut end_points = Vec::with_capacity(contour_count as usize);
+                let mut point_count = 0usize;
+                for _ in 0..contour_count {
+                    point_count += point_counts.u255()? as usize;
+                    let end = point_count
+                        .checked_sub(1)
+                        .and_then(|end| u16::try_from(end).ok())
+                        .ok_or_else(|| format!("字形{glyph}的点数无效"))?;
+                    end_points.push(end);
+                }
+
+                let flags = flag_stream.bytes(point_count)?;
+                let points = decode_triplets(flags, &mut glyph_stream)?;
+                let instruction_len = glyph_stream.u255()?;
+                let instructions = instruction_stream.bytes(instruction_len as usize)?;
+
+                let bbox = if has_bbox(glyph) {
+                    let mut bbox = [0i16; 4];
+                    for value in bbox.iter_mut() {
+                        *value = bbox_stream.i16()?;
+                    }
+                    bbox
+                } else {
+                    points.iter().fold(
+                        [i16::MAX, i16::MAX, i16::MIN, i16::MIN],
+                        |[x0, y0, x1, y1], &(x, y, _)| [x0.min(x), y0.min(y), x1.max(x), y1.max(y)],
+                    )
+                };
+
+                glyf.extend_from_slice(&contour_count.to_be_bytes());
+                for value in bbox {
+                    glyf.extend_from_slice(&value.to_be_bytes());
+                }
+                for end in end_points {
+                    glyf.extend_from_slice(&end.to_be_bytes());
+                }
+                glyf.extend_from_slice(&instruction_len.to_be_bytes());
+                glyf.extend_from_slice(instructions);
+
+                // 坐标一律以 16 位的差值储存，标志位因此只需表示是否在线上
+                let mut previous = (0i16, 0i16);
+                glyf.extend(points.iter().map(|&(_, _, on_curve)| on_curve as u8));
+                for &(x, _, _) in points.iter() {
+                    glyf.extend_from_slice(&x.wrapping_sub(previous.0).to_be_bytes());
+                    previous.0 = x;
+                }
+                for &(_, y, _) in points.iter() {
+                    glyf.extend_from_slice(&y.wrapping_sub(previous.1).to_be_bytes());
+                    previous.1 = y;
+                }
+            }
+
+            _ => return Err(format!("字形{glyph}的轮廓数无效：{contour_count}")),
+        }
+
+        // loca 的短格式以 2 字节为单位，长格式则习惯以 4 字节对齐
+        glyf.resize(glyf.len().next_multiple_of(4), 0);
+    }
+    offsets.push(glyf.len());
+
+    let mut loca = Vec::new();
+    for offset in offsets {
+        if index_format == 0 {
+            let offset = u16::try_from(offset / 2)
+                .map_err(|_| String::from("glyf 表过大，无法以短格式的 loca 表示"))?;
+            loca.extend_from_slice(&offset.to_be_bytes());
+        } else {
+            let offset = u32::try_from(offset).map_err(|_| String::from("glyf 表过大"))?;
+            loca.extend_from_slice(&offset.to_be_bytes());
+        }
+    }
+
+    Ok((glyf, loca))
+}
+
+/// 解码简单字形的坐标。每个点的标志字节决定其坐标在 glyph_stream 中占几个字节、如何解读，
+/// 见 WOFF2 规范 5.2 节的三元组编码表。返回各点的绝对坐标及是否在线上。
+fn decode_triplets(
+    flags: &[u8],
+    glyph_stream: &mut Cursor,
+) -> Result<Vec<(i16, i16, bool)>, String> {
+    let with_sign = |flag: u8, base: i32| if flag & 1 != 0 { base } else { -base };
+
+    let mut x = 0i32;
+    let mut y = 0i32;
+    let mut points = Vec::with_capacity(flags.len());
+
+    for &flag in flags {
+        let on_curve = flag & 0x80 == 0;
+        let flag = flag & 0x7F;
+
+        let len = match flag {
+            0..84 => 1,
+            84..120 => 2,
+            120..124 => 3,
+            _ => 4,
+        };
+        let bytes = glyph_stream.bytes(len)?;
+        let byte = |i: usize| bytes[i] as i32;
+        let code = flag as i32;
+
+        let (dx, dy) = match flag {
+            0..10 => (0, with_sign(flag, ((code & 14) << 7) + byte(0))),
+            10..20 => (with_sign(flag, (((code - 10) & 14) << 7) + byte(0)), 0),
+            20..84 => {
+                let b0 = code - 20;
+                (
+                    with_sign(flag, 1 + (b0 & 0x30) + (byte(0) >> 4)),
+                    with_sign(flag >> 1, 1 + ((b0 & 0x0C) << 2) + (byte(0) & 0x0F)),
+                )
+            }
+            84..120 => {
+                let b0 = code - 84;
+                (
+                    with_sign(flag, 1 + ((b0 / 12) << 8) + byte(0)),
+                    with_sign(flag >> 1, 1 + (((b0 % 12) >> 2) << 8) + byte(1)),
+                )
+            }
+            120..124 => (
+                with_sign(flag, (byte(0) << 4) + (byte(1) >> 4)),
+                with_sign(flag >> 1, ((byte(1) & 0x0F) << 8) + byte(2)),
+            ),
+            _ => (
+                with_sign(flag, (byte(0) << 8) + byte(1)),
+                with_sign(flag >> 1, (byte(2) << 8) + byte(3)),
+            ),
+        };
+
+        x += dx;
+        y += dy;
+        let coordinate =
+            |value: i32| i16::try_from(value).map_err(|_| String::from("字形坐标超出范围"));
+        points.push((coordinate(x)?, coordinate(y)?, on_curve));
+    }
+
+    Ok(points)
+}
@@ -0,0 +1,318 @@
+//! 将常见的码表格式转换为字根集（LoadedScheme）的雏形。
+//!
+//! 转换结果只是一个起点：归并组与聚类是根据编码推测出来的，描述也都是空白，
+//! 仍需要维护者手动整理。
+
+use crate::scheme::{
+    LoadedScheme, SchemeZigen, Zigen, ZigenClass, ZigenCluster, ZigenConfusableUnpopulated,
+    ZigenGroup,
+};
+
+/// 一个聚类最多容纳多少个归并组。同一大码下的归并组过多时，会被拆分成数个聚类。
+const MAX_GROUPS_PER_CLUSTER: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// 每行一个字根，格式为「字根<TAB>编码」。
+    Tsv,
+    /// RIME 的 *.dict.yaml 码表。
+    Rime,
+    /// 含有分类列的CSV，列依次为字根、编码、分类，可以带有表头。
+    Csv,
+}
+
+impl ImportFormat {
+    /// 根据文件名猜测码表格式。
+    pub fn guess(file_name: &str) -> Self {
+        if file_name.ends_with(".yaml") || file_name.ends_with(".yml") {
+            ImportFormat::Rime
+        } else if file_name.ends_with(".csv") {
+            ImportFormat::Csv
+        } else {
+            ImportFormat::Tsv
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "tsv" | "txt" => Some(ImportFormat::Tsv),
+            "rime" | "yaml" => Some(ImportFormat::Rime),
+            "csv" => Some(ImportFormat::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// 码表中的一条记录。
+struct Entry {
+    zigen: String,
+    code: String,
+    classify: ZigenClass,
+}
+
+/// 将码表转换为字根集。
+pub fn import(
+    source: &str,
+    format: ImportFormat,
+) -> Result<LoadedScheme<ZigenConfusableUnpopulated>, String> {
+    let entries = match format {
+        ImportFormat::Tsv => parse_tsv(source)?,
+        ImportFormat::Rime => parse_rime(source)?,
+        ImportFormat::Csv => parse_csv(source)?,
+    };
+
+    if entries.is_empty() {
+        return Err(String::from("码表内没有任何字根"));
+    }
+
+    Ok(group_entries(entries))
+}
+
+fn parse_classify(label: &str) -> Option<ZigenClass> {
+    match label.trim().to_lowercase().as_str() {
+        "" | "通" | "common" => Some(ZigenClass::Common),
+        "简" | "simplified" => Some(ZigenClass::Simplified),
+        "繁" | "traditional" => Some(ZigenClass::Traditional),
+        "罕" | "uncommon" => Some(ZigenClass::Uncommon),
+        _ => None,
+    }
+}
+
+fn parse_tsv(source: &str) -> Result<Vec<Entry>, String> {
+    let mut entries = Vec::new();
+
+    for (i, line) in source.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut columns = line.split('\t');
+        let zigen = columns.next().unwrap_or_default().trim();
+        let code = columns.next().unwrap_or_default().trim();
+
+        if zigen.is_empty() || code.is_empty() {
+            return Err(format!("第{}行：应为「字根<TAB>编码」", i + 1));
+        }
+
+        entries.push(Entry {
+            zigen: zigen.to_owned(),
+            code: code.to_owned(),
+            classify: ZigenClass::Common,
+        });
+    }
+
+    Ok(entries)
+}
+
+fn parse_rime(source: &str) -> Result<Vec<Entry>, String> {
+    let mut lines = source.lines().enumerate().peekable();
+
+    // 默认的列顺序为 text、code、weight，但码表可以在YAML头部的 columns 中另行规定
+    let mut columns = vec![String::from("text"), String::from("code")];
+
+    // 一般的 *.dict.yaml 以「# Rime dictionary」等注释开头，YAML头部在其后
+    while lines
+        .peek()
+        .is_some_and(|(_, line)| line.trim().is_empty() || line.starts_with('#'))
+    {
+        lines.next();
+    }
+
+    if lines
+        .peek()
+        .is_some_and(|(_, line)| line.trim_end() == "---")
+    {
+        lines.next();
+        let mut header_columns = Vec::new();
+        let mut in_columns = false;
+
+        for (_, line) in lines.by_ref() {
+            let trimmed = line.trim();
+            if trimmed == "..." {
+                break;
+            }
+
+            if trimmed.starts_with("columns:") {
+                in_columns = true;
+            } else if in_columns && trimmed.starts_with('-') {
+                header_columns.push(trimmed.trim_start_matches('-').trim().to_owned());
+            } else if !line.starts_with(' ') {
+                in_columns = false;
+            }
+        }
+
+        if !header_columns.is_empty() {
+            columns = header_columns;
+        }
+    }
+
+    let text_idx = columns
+        .iter()
+        .position(|c| c == "text")
+        .ok_or("码表的 columns 中缺少 text")?;
+    let code_idx = columns
+        .iter()
+        .position(|c| c == "code")
+        .ok_or("码表的 columns 中缺少 code")?;
+
+    let mut entries = Vec::new();
+
+    for (i, line) in lines {
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fields = line.split('\t').collect::<Vec<_>>();
+        let zigen = fields.get(text_idx).map(|s| s.trim()).unwrap_or_default();
+        let code = fields.get(code_idx).map(|s| s.trim()).unwrap_or_default();
+
+        if zigen.is_empty() || code.is_empty() {
+            return Err(format!("第{}行：缺少字根或编码", i + 1));
+        }
+
+        entries.push(Entry {
+            zigen: zigen.to_owned(),
+            code: code.to_owned(),
+            classify: ZigenClass::Common,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// 将一行CSV拆分为数个字段，支持以双引号包裹的字段。
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+
+    fields.push(field);
+    fields
+}
+
+fn parse_csv(source: &str) -> Result<Vec<Entry>, String> {
+    let mut lines = source
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i, line.trim_end_matches('\r')))
+        .filter(|(_, line)| !line.trim().is_empty())
+        .peekable();
+
+    let (mut zigen_idx, mut code_idx, mut classify_idx) = (0, 1, Some(2));
+
+    // 第一行若含有「code」或「编码」，则视为表头
+    if let Some((_, header)) = lines.peek() {
+        let header = split_csv_line(header)
+            .into_iter()
+            .map(|field| field.trim().to_lowercase())
+            .collect::<Vec<_>>();
+        let find = |names: &[&str]| header.iter().position(|h| names.contains(&h.as_str()));
+
+        if let Some(idx) = find(&["code", "编码"]) {
+            code_idx = idx;
+            zigen_idx = find(&["root", "zigen", "字根"]).ok_or("表头中缺少字根列")?;
+            classify_idx = find(&["classify", "class", "分类"]);
+            lines.next();
+        }
+    }
+
+    let mut entries = Vec::new();
+
+    for (i, line) in lines {
+        let fields = split_csv_line(line);
+        let field = |idx: usize| fields.get(idx).map(|s| s.trim()).unwrap_or_default();
+
+        let zigen = field(zigen_idx);
+        let code = field(code_idx);
+        if zigen.is_empty() || code.is_empty() {
+            return Err(format!("第{}行：缺少字根或编码", i + 1));
+        }
+
+        let classify = match classify_idx {
+            Some(idx) => parse_classify(field(idx))
+                .ok_or_else(|| format!("第{}行：无法识别的分类「{}」", i + 1, field(idx)))?,
+            None => ZigenClass::Common,
+        };
+
+        entries.push(Entry {
+            zigen: zigen.to_owned(),
+            code: code.to_owned(),
+            classify,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// 根据编码推测归并组与聚类：编码与分类皆相同的字根归入同一归并组，
+/// 大码（编码首字母）相同的归并组按出现顺序归入聚类，每个聚类最多 MAX_GROUPS_PER_CLUSTER 组。
+fn group_entries(entries: Vec<Entry>) -> LoadedScheme<ZigenConfusableUnpopulated> {
+    let mut groups: Vec<ZigenGroup> = Vec::new();
+
+    for entry in entries {
+        let existing = groups
+            .iter_mut()
+            .find(|group| group.code == entry.code && group.classify == entry.classify);
+
+        match existing {
+            Some(group) => {
+                if !group.zigens.iter().any(|z| z.0 == entry.zigen) {
+                    group.zigens.push(Zigen(entry.zigen));
+                }
+            }
+            None => groups.push(ZigenGroup {
+                zigens: vec![Zigen(entry.zigen)],
+                code: entry.code,
+                alt_codes: Vec::new(),
+                classify: entry.classify,
+                description: String::new(),
+                examples: Vec::new(),
+                tags: Vec::new(),
+                id: String::new(),
+            }),
+        }
+    }
+
+    let major_key = |group: &ZigenGroup| group.code.chars().next().unwrap().to_ascii_lowercase();
+
+    let mut by_major = Vec::<(char, Vec<ZigenGroup>)>::new();
+    for group in groups {
+        let key = major_key(&group);
+        match by_major.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, list)) => list.push(group),
+            None => by_major.push((key, vec![group])),
+        }
+    }
+
+    let clusters = by_major
+        .into_iter()
+        .flat_map(|(_, groups)| {
+            groups
+                .chunks(MAX_GROUPS_PER_CLUSTER)
+                .map(|chunk| {
+                    SchemeZigen::Cluster(ZigenCluster {
+                        groups: chunk.to_vec(),
+                        description: String::new(),
+                    })
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    LoadedScheme(clusters)
+}
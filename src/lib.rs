@@ -0,0 +1,18 @@
+//! 字根练习器中与网页无关的部分，供练习器本体与本地工具（如 zigen-lint）共用。
+
+pub mod catalog;
+pub mod compact;
+pub mod confusable;
+pub mod coverage;
+pub mod diff;
+pub mod export;
+pub mod font;
+pub mod import;
+pub mod lint;
+pub mod overlay;
+pub mod progress;
+pub mod rich_text;
+pub mod scheduler;
+pub mod scheduler_v2;
+pub mod scheme;
+pub mod stats;
@@ -0,0 +1,656 @@
+mod source_map;
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::coverage;
+use crate::font;
+use crate::overlay::{self, SchemeFile};
+use crate::rich_text;
+pub use crate::scheme::DEFAULT_KEYS;
+use crate::scheme::{self, LoadedScheme, Scheme, SchemeZigen, ZigenConfusableUnpopulated};
+use source_map::SourceMap;
+
+/// 某种编码长度在方案内的占比低于此值时，视为可疑。
+const RARE_CODE_LENGTH_RATIO: f64 = 0.05;
+
+/// 每个归并组的例字多于此数时，卡片会显得拥挤。
+const MAX_EXAMPLES: usize = 5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// 可能是有意为之，但值得留意的问题。
+    Warning,
+    /// 会导致练习器无法正常工作的问题。
+    Error,
+}
+
+/// 检查器发现的单个问题。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub file: String,
+    /// 问题所在的行号，从1开始。无法定位时为 None。
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "警告",
+            Severity::Error => "错误",
+        };
+
+        match self.line {
+            Some(line) => write!(f, "{}:{}: {}: {}", self.file, line, severity, self.message),
+            None => write!(f, "{}: {}: {}", self.file, severity, self.message),
+        }
+    }
+}
+
+/// 收集某个文件的问题，并负责把JSON路径转换为行号。
+struct Reporter<'a> {
+    file: &'a str,
+    source_map: SourceMap,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Reporter<'a> {
+    fn new(file: &'a str, source: &str) -> Self {
+        Self {
+            file,
+            source_map: SourceMap::new(source),
+            diagnostics: Vec::new(),
+        }
+    }
+
+    fn report(&mut self, severity: Severity, path: &str, message: String) {
+        self.diagnostics.push(Diagnostic {
+            severity,
+            file: self.file.to_owned(),
+            line: self.source_map.line(path),
+            message,
+        });
+    }
+
+    fn error(&mut self, path: &str, message: String) {
+        self.report(Severity::Error, path, message);
+    }
+
+    /// 描述须为练习器支持的富文本（见 rich_text）。
+    fn description(&mut self, path: &str, description: &str) {
+        if let Err(err) = rich_text::parse(description) {
+            self.error(path, format!("描述的格式有误：{err}"));
+        }
+    }
+
+    fn warning(&mut self, path: &str, message: String) {
+        self.report(Severity::Warning, path, message);
+    }
+}
+
+/// 以便于阅读的方式显示字根。私用区的字根通常无法在终端里显示，因此附上码位。
+fn display_zigen(zigen: &str) -> String {
+    if zigen.chars().any(scheme::is_pua) {
+        let codepoints = zigen
+            .chars()
+            .map(|c| format!("U+{:04X}", c as u32))
+            .collect::<Vec<_>>()
+            .join(" ");
+        format!("「{zigen}」({codepoints})")
+    } else {
+        format!("「{zigen}」")
+    }
+}
+
+fn serde_error(file: &str, err: serde_json::Error) -> Diagnostic {
+    Diagnostic {
+        severity: Severity::Error,
+        file: file.to_owned(),
+        line: Some(err.line()).filter(|line| *line > 0),
+        message: format!("无法解析JSON：{err}"),
+    }
+}
+
+/// 检查一个字根集文件（格式见 LoadedScheme）。
+///
+/// keys 为方案允许使用的按键，比较时不区分大小写。
+pub fn lint_zigen_table(file: &str, source: &str, keys: &str) -> Vec<Diagnostic> {
+    let scheme = match serde_json::from_str::<LoadedScheme<ZigenConfusableUnpopulated>>(source) {
+        Ok(scheme) => scheme,
+        Err(err) => return vec![serde_error(file, err)],
+    };
+
+    let mut reporter = Reporter::new(file, source);
+    let keys = keys.to_lowercase();
+
+    // 字根 -> 出现过的所有路径
+    let mut seen_zigens = HashMap::<&str, Vec<String>>::new();
+    // 各归并组的代表性字根（即第一个字根），混淆集只能引用这些字根
+    let mut representatives = HashSet::<&str>::new();
+    // 编码长度 -> 使用该长度的编码路径
+    let mut code_lengths = HashMap::<usize, Vec<String>>::new();
+
+    for (i, entry) in scheme.0.iter().enumerate() {
+        let SchemeZigen::Cluster(cluster) = entry else {
+            continue;
+        };
+
+        if cluster.groups.is_empty() {
+            reporter.error(&format!("{i}"), String::from("聚类内没有任何归并组"));
+        }
+
+        reporter.description(&format!("{i}.description"), &cluster.description);
+
+        for (j, group) in cluster.groups.iter().enumerate() {
+            let group_path = format!("{i}.groups.{j}");
+
+            if let Some(first) = group.zigens.first() {
+                representatives.insert(first);
+            } else {
+                reporter.error(&group_path, String::from("归并组内没有任何字根"));
+            }
+
+            for (k, zigen) in group.zigens.iter().enumerate() {
+                let zigen_path = format!("{group_path}.zigens.{k}");
+
+                if zigen.is_empty() {
+                    reporter.error(&zigen_path, String::from("字根不能为空字符串"));
+                    continue;
+                }
+
+                // 有些方案以笔画字根依次拼出补码（如五笔98的「末笔为点，需补码」），
+                // 同一笔画可能出现两次，因此只作提醒
+                if group.zigens[..k].contains(zigen) {
+                    reporter.warning(
+                        &zigen_path,
+                        format!(
+                            "字根{}在同一归并组内重复出现（以笔画表示补码时可忽略）",
+                            display_zigen(zigen)
+                        ),
+                    );
+                } else {
+                    seen_zigens.entry(zigen).or_default().push(zigen_path);
+                }
+            }
+
+            if group.examples.len() > MAX_EXAMPLES {
+                reporter.warning(
+                    &format!("{group_path}.examples"),
+                    format!(
+                        "归并组有{}个例字，建议不超过{MAX_EXAMPLES}个",
+                        group.examples.len()
+                    ),
+                );
+            }
+
+            for (n, example) in group.examples.iter().enumerate() {
+                let example_path = format!("{group_path}.examples.{n}");
+
+                if example.character.chars().count() != 1 {
+                    reporter.error(
+                        &format!("{example_path}.char"),
+                        format!("例字「{}」必须是单个字符", example.character),
+                    );
+                } else if group.examples[..n]
+                    .iter()
+                    .any(|other| other.character == example.character)
+                {
+                    reporter.warning(
+                        &format!("{example_path}.char"),
+                        format!("例字「{}」重复", example.character),
+                    );
+                }
+
+                if example.split.iter().any(String::is_empty) {
+                    reporter.error(
+                        &format!("{example_path}.split"),
+                        String::from("例字的拆分不能含有空字符串"),
+                    );
+                } else if !example.split.is_empty()
+                    && !example
+                        .split
+                        .iter()
+                        .any(|part| group.zigens.iter().any(|zigen| zigen.0 == *part))
+                {
+                    reporter.error(
+                        &format!("{example_path}.split"),
+                        format!("例字「{}」的拆分中没有该归并组的字根", example.character),
+                    );
+                }
+            }
+
+            reporter.description(&format!("{group_path}.description"), &group.description);
+
+            for (n, tag) in group.tags.iter().enumerate() {
+                let tag_path = format!("{group_path}.tags.{n}");
+
+                if tag.trim().is_empty() {
+                    reporter.error(&tag_path, String::from("标签不能为空"));
+                } else if group.tags[..n].contains(tag) {
+                    reporter.warning(&tag_path, format!("标签「{tag}」重复"));
+                }
+            }
+
+            let code_path = format!("{group_path}.code");
+
+            if group.code.is_empty() {
+                reporter.error(&code_path, String::from("编码不能为空"));
+                continue;
+            }
+
+            let code_paths = std::iter::once(code_path.clone())
+                .chain((0..group.alt_codes.len()).map(|n| format!("{group_path}.alt_codes.{n}")));
+
+            for (n, (code, path)) in group.codes().zip(code_paths).enumerate() {
+                let invalid_keys = code
+                    .chars()
+                    .filter(|c| !keys.contains(c.to_lowercase().next().unwrap()))
+                    .collect::<String>();
+
+                if !invalid_keys.is_empty() {
+                    reporter.error(
+                        &path,
+                        format!("编码「{code}」含有方案键位以外的字符：{invalid_keys}"),
+                    );
+                }
+
+                if n == 0 {
+                    continue;
+                }
+
+                if code.chars().count() != group.code.chars().count() {
+                    reporter.error(
+                        &path,
+                        format!("备选编码「{code}」的长度与主编码「{}」不一致", group.code),
+                    );
+                }
+
+                if group
+                    .codes()
+                    .take(n)
+                    .any(|other| other.eq_ignore_ascii_case(code))
+                {
+                    reporter.error(&path, format!("编码「{code}」重复"));
+                }
+            }
+
+            code_lengths
+                .entry(group.code.chars().count())
+                .or_default()
+                .push(code_path);
+        }
+    }
+
+    for (zigen, paths) in seen_zigens.iter().filter(|(_, paths)| paths.len() > 1) {
+        let lines = paths
+            .iter()
+            .filter_map(|path| reporter.source_map.line(path))
+            .map(|line| line.to_string())
+            .collect::<Vec<_>>()
+            .join("、");
+        reporter.warning(
+            &paths[1],
+            format!(
+                "字根{}出现在{}个归并组内（第{lines}行）",
+                display_zigen(zigen),
+                paths.len()
+            ),
+        );
+    }
+
+    let total_codes = code_lengths.values().map(Vec::len).sum::<usize>();
+    let usual_length = code_lengths
+        .iter()
+        .max_by_key(|(_, paths)| paths.len())
+        .map(|(len, _)| *len);
+
+    for (len, paths) in code_lengths.iter() {
+        if (paths.len() as f64) < total_codes as f64 * RARE_CODE_LENGTH_RATIO {
+            for path in paths {
+                reporter.warning(
+                    path,
+                    format!(
+                        "编码长度为{len}，但本方案仅有{}个编码使用这个长度（多数编码长度为{}）",
+                        paths.len(),
+                        usual_length.unwrap_or_default()
+                    ),
+                );
+            }
+        }
+    }
+
+    for (i, entry) in scheme.0.iter().enumerate() {
+        let SchemeZigen::Confusable(confusable) = entry else {
+            continue;
+        };
+
+        if confusable.zigens.len() < 2 {
+            reporter.error(&format!("{i}"), String::from("混淆集至少需要两个字根"));
+        }
+
+        reporter.description(&format!("{i}.description"), &confusable.description);
+
+        for (k, zigen) in confusable.zigens.iter().enumerate() {
+            let zigen_path = format!("{i}.zigens.{k}");
+
+            if confusable.zigens[..k].contains(zigen) {
+                reporter.error(
+                    &zigen_path,
+                    format!("字根{}在同一混淆集内重复出现", display_zigen(zigen)),
+                );
+                continue;
+            }
+
+            if representatives.contains(&**zigen) {
+                continue;
+            }
+
+            match seen_zigens.get(&**zigen) {
+                Some(paths) => {
+                    let line = reporter
+                        .source_map
+                        .line(&paths[0])
+                        .map(|line| format!("（见第{line}行）"))
+                        .unwrap_or_default();
+                    reporter.error(
+                        &zigen_path,
+                        format!(
+                            "混淆集引用的字根{}不是其归并组的代表性字根{line}",
+                            display_zigen(zigen)
+                        ),
+                    );
+                }
+                None => reporter.error(
+                    &zigen_path,
+                    format!("混淆集引用的字根{}不在字根码表内", display_zigen(zigen)),
+                ),
+            }
+        }
+    }
+
+    let mut decomposed = HashMap::new();
+    for (i, entry) in scheme.0.iter().enumerate() {
+        let SchemeZigen::Decomposition(decomposition) = entry else {
+            continue;
+        };
+
+        if decomposition.character.chars().count() != 1 {
+            reporter.error(
+                &format!("{i}.char"),
+                format!("拆分的对象“{}”应为单个汉字", decomposition.character),
+            );
+        } else if let Some(first) = decomposition.character.chars().next()
+            && let Some(previous) = decomposed.insert(first, i)
+        {
+            let line = reporter
+                .source_map
+                .line(&format!("{previous}"))
+                .map(|line| format!("（见第{line}行）"))
+                .unwrap_or_default();
+            reporter.error(
+                &format!("{i}.char"),
+                format!("字{first}的拆分重复出现{line}"),
+            );
+        }
+
+        if decomposition.split.is_empty() {
+            reporter.error(&format!("{i}.split"), String::from("拆分至少需要一个字根"));
+        }
+
+        reporter.description(&format!("{i}.description"), &decomposition.description);
+
+        for (k, zigen) in decomposition.split.iter().enumerate() {
+            if !seen_zigens.contains_key(&**zigen) {
+                reporter.error(
+                    &format!("{i}.split.{k}"),
+                    format!("拆分使用的字根{}不在字根码表内", display_zigen(zigen)),
+                );
+            }
+        }
+    }
+
+    reporter
+        .diagnostics
+        .sort_by_key(|diagnostic| diagnostic.line);
+    reporter.diagnostics
+}
+
+/// 检查一个本地的字根集文件，该文件可以是叠加文件。
+///
+/// 叠加文件本身只检查其中的修改能否套用；展开后的字根集则另行检查，
+/// 其行号对应 `zigen-lint --flatten` 的输出。
+pub fn lint_zigen_file(path: &Path, keys: &str) -> Vec<Diagnostic> {
+    let file = path.display().to_string();
+
+    let source = match std::fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(err) => {
+            return vec![Diagnostic {
+                severity: Severity::Error,
+                file,
+                line: None,
+                message: format!("无法读取文件：{err}"),
+            }];
+        }
+    };
+
+    let overlay = match SchemeFile::parse(&source) {
+        Ok(SchemeFile::Overlay(overlay)) => overlay,
+        // 完整的字根集（或无法解析的文件）交给 lint_zigen_table 处理
+        _ => return lint_zigen_table(&file, &source, keys),
+    };
+
+    let mut reporter = Reporter::new(&file, &source);
+
+    let base_path = overlay::resolve_relative(&file, &overlay.base);
+    let mut scheme = match overlay::load_file(&base_path) {
+        Ok(scheme) => scheme,
+        Err(err) => {
+            reporter.error("base", format!("无法加载基础字根集：{err}"));
+            return reporter.diagnostics;
+        }
+    };
+
+    for (n, item) in overlay.overrides.iter().enumerate() {
+        if let Err(err) = item.apply(&mut scheme) {
+            reporter.error(&format!("overrides.{n}"), err);
+        }
+    }
+
+    let flattened = serde_json::to_string_pretty(&scheme).unwrap();
+    let mut diagnostics = reporter.diagnostics;
+    diagnostics.extend(lint_zigen_table(
+        &format!("{file}（展开后）"),
+        &flattened,
+        keys,
+    ));
+    diagnostics
+}
+
+/// 检查 schemes.json，以及其中列出的所有字根集文件。
+///
+/// 相对路径以 schemes.json 的所在目录为根目录；绝对URL无法在本地检查，因此会被跳过。
+pub fn lint_catalog(path: &Path) -> Vec<Diagnostic> {
+    let file = path.display().to_string();
+
+    let source = match std::fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(err) => {
+            return vec![Diagnostic {
+                severity: Severity::Error,
+                file,
+                line: None,
+                message: format!("无法读取文件：{err}"),
+            }];
+        }
+    };
+
+    let schemes = match serde_json::from_str::<Vec<Scheme>>(&source) {
+        Ok(schemes) => schemes,
+        Err(err) => return vec![serde_error(&file, err)],
+    };
+
+    let base_dir = path.parent().unwrap_or(Path::new("."));
+    let mut reporter = Reporter::new(&file, &source);
+    let mut zigen_files = Vec::new();
+
+    for (i, scheme) in schemes.iter().enumerate() {
+        if schemes[..i].iter().any(|other| other.id == scheme.id) {
+            reporter.error(&format!("{i}.id"), format!("方案ID「{}」重复", scheme.id));
+        }
+
+        if scheme
+            .code_structure
+            .is_some_and(|structure| structure.major_len == 0)
+        {
+            reporter.error(
+                &format!("{i}.code_structure.major_len"),
+                String::from("大码长度不可为0"),
+            );
+        }
+
+        for problem in scheme.keyboard.problems() {
+            reporter.error(&format!("{i}.keyboard"), problem);
+        }
+
+        if let Some(limit_keys) = &scheme.default_options.limit_keys {
+            let invalid = limit_keys
+                .iter()
+                .filter(|key| scheme.keyboard.normalize(**key).is_none())
+                .collect::<String>();
+
+            if !invalid.is_empty() {
+                reporter.error(
+                    &format!("{i}.default_options.limit_keys"),
+                    format!("推荐设置的键面含有方案键位以外的字符：{invalid}"),
+                );
+            }
+        }
+
+        if scheme.zigen_url.is_empty() {
+            reporter.error(&format!("{i}.zigen_url"), String::from("未指定字根集URL"));
+        }
+
+        let urls = std::iter::once(("zigen_url", &scheme.zigen_url))
+            .chain(scheme.zigen_font.iter().map(|font| ("zigen_font", font)));
+
+        for (key, url) in urls {
+            if url.is_empty() {
+                continue;
+            }
+
+            if url.contains("://") || url.starts_with('/') || url.starts_with("data:") {
+                continue;
+            }
+
+            let resolved = base_dir.join(url.trim_start_matches("./"));
+            if !resolved.is_file() {
+                reporter.error(
+                    &format!("{i}.{key}"),
+                    format!("无法找到「{url}」（{}）", resolved.display()),
+                );
+            } else if key == "zigen_url" {
+                // 同一个字根集可能被多个按键不同的方案共用
+                let entry = (resolved, scheme.keyboard.keys.clone());
+                if !zigen_files.contains(&entry) {
+                    zigen_files.push(entry);
+                }
+            }
+        }
+    }
+
+    let mut checked_fonts = Vec::new();
+
+    for (i, scheme) in schemes.iter().enumerate() {
+        let local = |url: &str| {
+            let remote = url.is_empty()
+                || url.contains("://")
+                || url.starts_with('/')
+                || url.starts_with("data:");
+            Some(base_dir.join(url.trim_start_matches("./")))
+                .filter(|path| !remote && path.is_file())
+        };
+
+        let Some(zigen_file) = local(&scheme.zigen_url) else {
+            continue;
+        };
+
+        // 含有远程字体时无法检查，但未指定字体时同样需要检查
+        let Some(font_files) = scheme
+            .zigen_font
+            .iter()
+            .map(|font| local(font))
+            .collect::<Option<Vec<_>>>()
+        else {
+            continue;
+        };
+
+        let entry = (zigen_file, font_files);
+        if checked_fonts.contains(&entry) {
+            continue;
+        }
+
+        check_font_coverage(&mut reporter, i, &entry.0, &entry.1);
+        checked_fonts.push(entry);
+    }
+
+    let mut diagnostics = reporter.diagnostics;
+
+    for (zigen_file, keys) in zigen_files {
+        diagnostics.extend(lint_zigen_file(&zigen_file, &keys));
+    }
+
+    diagnostics
+}
+
+/// 检查字根字体是否含有字根集中所有私用区字根的字形。fonts 为方案的所有字根字体，
+/// 只要其中一个字体含有字形即可；fonts 为空表示方案未指定字根字体。
+fn check_font_coverage(reporter: &mut Reporter, i: usize, zigen_file: &Path, fonts: &[PathBuf]) {
+    // 字根集本身的错误由 lint_zigen_file 报告
+    let Ok(scheme) = overlay::load_file(&zigen_file.display().to_string()) else {
+        return;
+    };
+
+    let mut charset = HashSet::new();
+    for font in fonts {
+        match std::fs::read(font)
+            .map_err(|err| err.to_string())
+            .and_then(|data| font::charset(&data))
+        {
+            Ok(chars) => charset.extend(chars),
+            Err(err) => {
+                reporter.warning(
+                    &format!("{i}.zigen_font"),
+                    format!("无法检查字根字体「{}」：{err}", font.display()),
+                );
+                return;
+            }
+        }
+    }
+
+    let missing = coverage::missing_glyphs(&scheme, &charset);
+
+    if fonts.is_empty() {
+        if !missing.is_empty() {
+            reporter.error(
+                &format!("{i}.zigen_font"),
+                format!("字根集含有{}个私用区字根，但未指定字根字体", missing.len()),
+            );
+        }
+        return;
+    }
+
+    for glyph in missing {
+        reporter.error(
+            &format!("{i}.zigen_font"),
+            format!(
+                "所有字根字体都缺少字根{}（{}）的字形",
+                display_zigen(&glyph.zigen),
+                glyph.code
+            ),
+        );
+    }
+}
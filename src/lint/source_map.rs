@@ -0,0 +1,162 @@
+/// 一个极简的JSON扫描器，只用来找出JSON内各个值所在的行号。
+///
+/// serde_json 反序列化后无法得知数据在文件中的位置，因此检查器另外扫描一遍原文，
+/// 以便在报告问题时指出行号。本扫描器假定JSON本身是合法的，若解析失败则放弃定位。
+#[derive(Clone, Debug)]
+pub(super) struct SourceMap {
+    root: Option<Node>,
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    /// 值的起始行号，从1开始。
+    line: usize,
+    kind: NodeKind,
+}
+
+#[derive(Clone, Debug)]
+enum NodeKind {
+    Object(Vec<(String, Node)>),
+    Array(Vec<Node>),
+    Scalar,
+}
+
+impl SourceMap {
+    pub fn new(source: &str) -> Self {
+        let mut scanner = Scanner {
+            chars: source.chars().peekable(),
+            line: 1,
+        };
+
+        Self {
+            root: scanner.value(),
+        }
+    }
+
+    /// 查找某个值的行号。path 以 '.' 分隔，数字表示数组下标，其余表示对象的键，
+    /// 例如 `3.groups.0.code`。空字符串表示根节点。
+    pub fn line(&self, path: &str) -> Option<usize> {
+        let mut node = self.root.as_ref()?;
+
+        for segment in path.split('.').filter(|s| !s.is_empty()) {
+            node = match (&node.kind, segment.parse::<usize>()) {
+                (NodeKind::Array(items), Ok(idx)) => items.get(idx)?,
+                (NodeKind::Object(entries), _) => entries
+                    .iter()
+                    .find(|(key, _)| key == segment)
+                    .map(|(_, v)| v)?,
+                _ => return None,
+            };
+        }
+
+        Some(node.line)
+    }
+}
+
+struct Scanner<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+}
+
+impl Scanner<'_> {
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+        }
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.peek().is_some_and(|c| c.is_whitespace()) {
+            self.bump();
+        }
+    }
+
+    fn value(&mut self) -> Option<Node> {
+        self.skip_whitespace();
+        let line = self.line;
+
+        let kind = match *self.chars.peek()? {
+            '{' => {
+                self.bump();
+                let mut entries = Vec::new();
+
+                loop {
+                    self.skip_whitespace();
+                    match self.bump()? {
+                        '}' => break,
+                        ',' => continue,
+                        '"' => {
+                            let key = self.string()?;
+                            self.skip_whitespace();
+                            if self.bump()? != ':' {
+                                return None;
+                            }
+                            entries.push((key, self.value()?));
+                        }
+                        _ => return None,
+                    }
+                }
+
+                NodeKind::Object(entries)
+            }
+
+            '[' => {
+                self.bump();
+                let mut items = Vec::new();
+
+                loop {
+                    self.skip_whitespace();
+                    match *self.chars.peek()? {
+                        ']' => {
+                            self.bump();
+                            break;
+                        }
+                        ',' => {
+                            self.bump();
+                        }
+                        _ => items.push(self.value()?),
+                    }
+                }
+
+                NodeKind::Array(items)
+            }
+
+            '"' => {
+                self.bump();
+                self.string()?;
+                NodeKind::Scalar
+            }
+
+            _ => {
+                // 数字、true、false、null
+                while self
+                    .chars
+                    .peek()
+                    .is_some_and(|c| !c.is_whitespace() && !matches!(c, ',' | ']' | '}'))
+                {
+                    self.bump();
+                }
+                NodeKind::Scalar
+            }
+        };
+
+        Some(Node { line, kind })
+    }
+
+    /// 读取字符串的剩余部分（开头的引号已被读取）。转义序列只做粗略处理，足以比对键名。
+    fn string(&mut self) -> Option<String> {
+        let mut result = String::new();
+
+        loop {
+            match self.bump()? {
+                '"' => break,
+                '\\' => result.push(self.bump()?),
+                c => result.push(c),
+            }
+        }
+
+        Some(result)
+    }
+}
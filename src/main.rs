@@ -1,11 +1,9 @@
 mod component;
-mod scheduler;
-mod scheduler_v2;
-mod scheme;
 mod user_state;
 mod view;
 
 use dioxus::prelude::*;
+use zigen_trainer::scheme;
 
 use crate::view::Trainer;
 
@@ -0,0 +1,317 @@
+//! 叠加式字根集：以另一个字根集为基础，只记录与之不同的地方。
+//!
+//! 叠加文件的格式如下（base 为基础字根集的路径，以叠加文件的所在目录为根目录）：
+//!
+//! ```json
+//! {
+//!     "base": "./yuhao_star.json",
+//!     "overrides": [
+//!         { "op": "改码", "zigen": "日", "code": "Jr" },
+//!         { "op": "添加", "cluster": "日", "group": { "zigens": ["曰"], "code": "Jy", ... } },
+//!         { "op": "删除", "zigen": "目" },
+//!         { "op": "移动", "zigen": "月", "to": "日" }
+//!     ]
+//! }
+//! ```
+//!
+//! 归并组一律以其代表性字根（即第一个字根）指定。基础字根集本身也可以是叠加文件。
+
+use serde::{Deserialize, Serialize};
+
+use crate::catalog;
+use crate::scheme::{
+    LoadedScheme, SchemeZigen, ZigenCluster, ZigenConfusableUnpopulated, ZigenGroup,
+};
+
+/// 叠加文件最多可以嵌套几层，用以防止循环引用。
+pub const MAX_DEPTH: usize = 8;
+
+/// 叠加文件的结构。
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SchemeOverlay {
+    /// 基础字根集的路径。
+    pub base: String,
+    /// 依次套用在基础字根集上的修改。
+    #[serde(default)]
+    pub overrides: Vec<Override>,
+}
+
+/// 对基础字根集的单项修改。
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op")]
+pub enum Override {
+    /// 修改归并组的编码。若提供 alt_codes，备选编码也会一并替换。
+    #[serde(rename = "改码")]
+    Recode {
+        zigen: String,
+        code: String,
+        #[serde(default)]
+        alt_codes: Option<Vec<String>>,
+    },
+
+    /// 添加一个归并组。若指定 cluster，则放入该字根所在的聚类，否则自成一个新聚类。
+    #[serde(rename = "添加")]
+    Add {
+        #[serde(default)]
+        cluster: Option<String>,
+        group: ZigenGroup,
+    },
+
+    /// 删除一个归并组。混淆集内的该字根也会被一并删除，用到该字根的拆分则整条删除。
+    #[serde(rename = "删除")]
+    Remove { zigen: String },
+
+    /// 将一个归并组移动到另一字根所在的聚类。若不指定 to，则自成一个新聚类。
+    #[serde(rename = "移动")]
+    Move {
+        zigen: String,
+        #[serde(default)]
+        to: Option<String>,
+    },
+}
+
+/// 字根集文件：完整的字根集，或者叠加文件。
+#[derive(Clone, Debug, PartialEq)]
+pub enum SchemeFile {
+    Full(LoadedScheme<ZigenConfusableUnpopulated>),
+    Overlay(SchemeOverlay),
+}
+
+impl SchemeFile {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        // 完整的字根集的根节点为数组，叠加文件的根节点则为对象
+        if source.trim_start().starts_with('{') {
+            serde_json::from_str::<SchemeOverlay>(source)
+                .map(SchemeFile::Overlay)
+                .map_err(|err| err.to_string())
+        } else {
+            serde_json::from_str::<LoadedScheme<ZigenConfusableUnpopulated>>(source)
+                .map(SchemeFile::Full)
+                .map_err(|err| err.to_string())
+        }
+    }
+}
+
+/// 找出代表性字根为 zigen 的归并组，返回其所在聚类与归并组的位置。
+fn find_group(
+    scheme: &LoadedScheme<ZigenConfusableUnpopulated>,
+    zigen: &str,
+) -> Option<(usize, usize)> {
+    scheme
+        .0
+        .iter()
+        .enumerate()
+        .find_map(|(i, entry)| match entry {
+            SchemeZigen::Cluster(cluster) => cluster
+                .groups
+                .iter()
+                .position(|group| group.zigens.first().is_some_and(|first| first.0 == zigen))
+                .map(|j| (i, j)),
+            SchemeZigen::Confusable(_) | SchemeZigen::Decomposition(_) => None,
+        })
+}
+
+fn cluster_mut(
+    scheme: &mut LoadedScheme<ZigenConfusableUnpopulated>,
+    i: usize,
+) -> &mut ZigenCluster {
+    match &mut scheme.0[i] {
+        SchemeZigen::Cluster(cluster) => cluster,
+        SchemeZigen::Confusable(_) | SchemeZigen::Decomposition(_) => unreachable!(),
+    }
+}
+
+/// 从聚类中取出一个归并组。聚类因此变空时，会被一并删除。
+fn take_group(
+    scheme: &mut LoadedScheme<ZigenConfusableUnpopulated>,
+    (i, j): (usize, usize),
+) -> ZigenGroup {
+    let cluster = cluster_mut(scheme, i);
+    let group = cluster.groups.remove(j);
+
+    if cluster.groups.is_empty() {
+        scheme.0.remove(i);
+    }
+
+    group
+}
+
+impl Override {
+    /// 将本项修改套用在字根集上。
+    pub fn apply(
+        &self,
+        scheme: &mut LoadedScheme<ZigenConfusableUnpopulated>,
+    ) -> Result<(), String> {
+        let locate = |scheme: &LoadedScheme<ZigenConfusableUnpopulated>, zigen: &str| {
+            find_group(scheme, zigen)
+                .ok_or_else(|| format!("基础字根集内没有以「{zigen}」为代表的归并组"))
+        };
+
+        match self {
+            Override::Recode {
+                zigen,
+                code,
+                alt_codes,
+            } => {
+                let (i, j) = locate(scheme, zigen)?;
+                let group = &mut cluster_mut(scheme, i).groups[j];
+                group.code = code.clone();
+                if let Some(alt_codes) = alt_codes {
+                    group.alt_codes = alt_codes.clone();
+                }
+            }
+
+            Override::Add { cluster, group } => {
+                let Some(first) = group.zigens.first() else {
+                    return Err(String::from("添加的归并组内没有任何字根"));
+                };
+
+                if find_group(scheme, first).is_some() {
+                    return Err(format!("以「{}」为代表的归并组已经存在", first.0));
+                }
+
+                match cluster {
+                    Some(target) => {
+                        let (i, _) = locate(scheme, target)?;
+                        cluster_mut(scheme, i).groups.push(group.clone());
+                    }
+                    None => push_cluster(scheme, group.clone()),
+                }
+            }
+
+            Override::Remove { zigen } => {
+                let position = locate(scheme, zigen)?;
+                take_group(scheme, position);
+
+                scheme.0.retain_mut(|entry| match entry {
+                    SchemeZigen::Confusable(confusable) => {
+                        confusable.zigens.retain(|z| z.0 != *zigen);
+                        confusable.zigens.len() >= 2
+                    }
+                    // 拆分无法缺少字根，因此含有该字根的拆分会被整个删除
+                    SchemeZigen::Decomposition(dec) => !dec.split.iter().any(|z| z.0 == *zigen),
+                    SchemeZigen::Cluster(_) => true,
+                });
+            }
+
+            Override::Move { zigen, to } => {
+                // 先检查所有字根，出错时字根集保持原样
+                if to.as_ref() == Some(zigen) {
+                    return Err(format!("不能把以「{zigen}」为代表的归并组移动到其自身"));
+                }
+                if let Some(target) = to {
+                    locate(scheme, target)?;
+                }
+
+                let position = locate(scheme, zigen)?;
+                let group = take_group(scheme, position);
+
+                match to {
+                    // 取出归并组后，聚类的位置可能有所变动，因此需要重新查找
+                    Some(target) => {
+                        let (i, _) = locate(scheme, target)?;
+                        cluster_mut(scheme, i).groups.push(group);
+                    }
+                    None => push_cluster(scheme, group),
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// 在混淆集之前插入一个只含单个归并组的聚类，以保持「聚类在前、混淆集在后」的习惯。
+fn push_cluster(scheme: &mut LoadedScheme<ZigenConfusableUnpopulated>, group: ZigenGroup) {
+    let position = scheme
+        .0
+        .iter()
+        .rposition(|entry| matches!(entry, SchemeZigen::Cluster(_)))
+        .map_or(0, |i| i + 1);
+
+    scheme.0.insert(
+        position,
+        SchemeZigen::Cluster(ZigenCluster {
+            groups: vec![group],
+            description: String::new(),
+        }),
+    );
+}
+
+/// 将一连串叠加文件套用在基础字根集上。overlays 的顺序为由外至内，
+/// 即第一个元素是最终的叠加文件，最后一个元素直接以 base 为基础。
+pub fn flatten(
+    mut base: LoadedScheme<ZigenConfusableUnpopulated>,
+    overlays: &[SchemeOverlay],
+) -> Result<LoadedScheme<ZigenConfusableUnpopulated>, String> {
+    for overlay in overlays.iter().rev() {
+        for (n, item) in overlay.overrides.iter().enumerate() {
+            item.apply(&mut base).map_err(|err| {
+                format!("叠加文件（基础：{}）第{}项修改：{err}", overlay.base, n + 1)
+            })?;
+        }
+    }
+
+    Ok(base)
+}
+
+/// 以 file 的所在目录为根目录，解析相对路径 relative。支持「./」与「../」。
+///
+/// 本函数同时适用于URL与（以「/」分隔的）本地路径。file 为绝对URL（比如第三方方案列表中的
+/// 字根集）时交由 catalog::resolve_url 处理：以「/」开头的路径相对于 file 的来源，「../」也不会越过
+/// 主机名。否则绝对URL与绝对路径会被原样返回。
+pub fn resolve_relative(file: &str, relative: &str) -> String {
+    if file.contains("://") {
+        return catalog::resolve_url(file, relative);
+    }
+
+    if relative.contains("://") || relative.starts_with('/') {
+        return relative.to_owned();
+    }
+
+    let mut segments = match file.rfind('/') {
+        Some(idx) => file[..idx].split('/').collect::<Vec<_>>(),
+        None => Vec::new(),
+    };
+
+    for segment in relative.split('/') {
+        match segment {
+            "" | "." => (),
+            ".." if segments
+                .last()
+                .is_some_and(|last| !matches!(*last, "" | "." | "..")) =>
+            {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+
+    segments.join("/")
+}
+
+/// 读取本地的字根集文件。若为叠加文件，则顺着 base 一路加载，并展开为完整的字根集。
+pub fn load_file(path: &str) -> Result<LoadedScheme<ZigenConfusableUnpopulated>, String> {
+    let mut path = path.to_owned();
+    let mut overlays = Vec::new();
+
+    let base = loop {
+        let source =
+            std::fs::read_to_string(&path).map_err(|err| format!("无法读取「{path}」：{err}"))?;
+
+        match SchemeFile::parse(&source).map_err(|err| format!("无法解析「{path}」：{err}"))?
+        {
+            SchemeFile::Full(loaded) => break loaded,
+            SchemeFile::Overlay(overlay) => {
+                if overlays.len() >= MAX_DEPTH {
+                    return Err(String::from("叠加文件嵌套过深，可能存在循环引用"));
+                }
+
+                path = resolve_relative(&path, &overlay.base);
+                overlays.push(overlay);
+            }
+        }
+    };
+
+    flatten(base, &overlays)
+}
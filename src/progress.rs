@@ -0,0 +1,434 @@
+//! 单个方案的练习进度：调度器的状态，以及根据字根集找回的卡片内容。
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use chrono::{DateTime, Utc};
+use dioxus_logger::tracing;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+use crate::scheduler::{
+    Rating, ScheduleParamsAdept, ScheduleParamsNovice, Scheduler, SchedulerCard, ZigenCard,
+    weakest_cards,
+};
+use crate::scheduler_v2::{SchedulerV2, SchedulerV2Card};
+use crate::scheme::{
+    LoadedScheme, Scheme, SchemeOptions, SchemeZigen, ZigenConfusableUnpopulated, card_ids,
+};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TrainProgress {
+    start_time: DateTime<Utc>,
+    scheduler: UsedScheduler,
+    /// 正在进行的弱项加练。
+    #[serde(default)]
+    extra_practice: Option<ExtraPractice>,
+    /// 建立本进度时使用的设置。字根集更新后，须以同样的设置重新整理卡片。
+    ///
+    /// 旧版的进度没有保存设置，因此不会跟随字根集的更新增删卡片。
+    #[serde(default)]
+    options: Option<SchemeOptions>,
+    /// 卡片ID -> 卡片内容。卡片内容不会被保存，而是在加载字根集后根据ID找回。
+    #[serde(skip)]
+    contents: HashMap<String, SchemeZigen>,
+    /// 建立（或上次找回卡片）时字根集的版本。旧版的进度没有记录版本。
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    scheme_version: String,
+    /// 本次启动后找回卡片时，发现的字根集更新。用户阅读后即可清除。
+    #[serde(skip)]
+    update: Option<SchemeUpdate>,
+}
+
+/// 用户继续旧进度时，字根集相对于上次练习的变动。
+#[derive(Clone, Debug, PartialEq)]
+pub struct SchemeUpdate {
+    /// 上次练习时的版本。旧版的进度没有记录版本，此时为空。
+    pub from_version: String,
+    pub to_version: String,
+    /// 方案提供的更新说明，仅在版本有所改变时提供。
+    pub changelog: String,
+    /// 新增卡片的ID
+    pub added: Vec<String>,
+    /// 删除卡片的ID
+    pub removed: Vec<String>,
+}
+
+/// 当前应练习的卡片。
+#[derive(Clone, Debug, PartialEq)]
+pub struct CurrentCard {
+    pub zigen: SchemeZigen,
+    pub is_new: bool,
+}
+
+/// 弱项加练：完成所有到期卡片后，专门练习调度状态最差的若干张卡片。
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ExtraPractice {
+    /// 待练习的卡片在调度器中的下标。加练期间调度器不会调整卡片的位置，因此下标始终有效。
+    queue: VecDeque<usize>,
+    /// 加练的结果是否计入调度。
+    update_schedule: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+enum UsedScheduler {
+    Novice(Scheduler<ScheduleParamsNovice>),
+    Adept(Scheduler<ScheduleParamsAdept>),
+    V2(SchedulerV2),
+}
+
+impl UsedScheduler {
+    fn cards(&self) -> Box<dyn Iterator<Item = &dyn ZigenCard> + '_> {
+        match self {
+            UsedScheduler::Novice(scheduler) => Box::new(scheduler.cards().map(|c| c as _)),
+            UsedScheduler::Adept(scheduler) => Box::new(scheduler.cards().map(|c| c as _)),
+            UsedScheduler::V2(scheduler) => Box::new(scheduler.cards().map(|c| c as _)),
+        }
+    }
+
+    fn cards_mut(&mut self) -> Box<dyn Iterator<Item = &mut dyn ZigenCard> + '_> {
+        match self {
+            UsedScheduler::Novice(scheduler) => Box::new(scheduler.cards_mut().map(|c| c as _)),
+            UsedScheduler::Adept(scheduler) => Box::new(scheduler.cards_mut().map(|c| c as _)),
+            UsedScheduler::V2(scheduler) => Box::new(scheduler.cards_mut().map(|c| c as _)),
+        }
+    }
+
+    fn retain_cards(&mut self, mut keep: impl FnMut(&str) -> bool) {
+        match self {
+            UsedScheduler::Novice(scheduler) => scheduler.retain_cards(|card| keep(card.id())),
+            UsedScheduler::Adept(scheduler) => scheduler.retain_cards(|card| keep(card.id())),
+            UsedScheduler::V2(scheduler) => scheduler.retain_cards(|card| keep(card.id())),
+        }
+    }
+
+    fn add_new_cards(&mut self, ids: Vec<String>) {
+        match self {
+            UsedScheduler::Novice(scheduler) => {
+                scheduler.add_new_cards(ids.into_iter().map(SchedulerCard::new).collect())
+            }
+            UsedScheduler::Adept(scheduler) => {
+                scheduler.add_new_cards(ids.into_iter().map(SchedulerCard::new).collect())
+            }
+            UsedScheduler::V2(scheduler) => {
+                scheduler.add_new_cards(ids.into_iter().map(SchedulerV2Card::new).collect())
+            }
+        }
+    }
+
+    fn card_at(&mut self, idx: usize) -> Option<&mut dyn ZigenCard> {
+        match self {
+            UsedScheduler::Novice(scheduler) => scheduler.card_at(idx).map(|c| c as _),
+            UsedScheduler::Adept(scheduler) => scheduler.card_at(idx).map(|c| c as _),
+            UsedScheduler::V2(scheduler) => scheduler.card_at(idx).map(|c| c as _),
+        }
+    }
+}
+
+impl TrainProgress {
+    pub fn new(zigens: Vec<SchemeZigen>, options: SchemeOptions) -> Self {
+        let ids = card_ids(&zigens);
+
+        if ids.is_empty() {
+            tracing::error!("pending_cards 不能为空！");
+            panic!("pending_cards is empty");
+        }
+
+        let scheduler = if options.v2_sched {
+            let pending_cards = ids.iter().cloned().map(SchedulerV2Card::new).collect();
+            UsedScheduler::V2(SchedulerV2::new(pending_cards, options.adept))
+        } else {
+            let pending_cards = ids
+                .iter()
+                .cloned()
+                .map(SchedulerCard::new)
+                .collect::<Vec<_>>();
+
+            if options.adept {
+                UsedScheduler::Adept(Scheduler::new(pending_cards))
+            } else {
+                UsedScheduler::Novice(Scheduler::new(pending_cards))
+            }
+        };
+
+        Self {
+            start_time: Utc::now(),
+            scheduler,
+            extra_practice: None,
+            options: Some(options),
+            contents: ids.into_iter().zip(zigens).collect(),
+            scheme_version: String::new(),
+            update: None,
+        }
+    }
+
+    /// 本次启动后，是否已经找回了卡片内容。
+    pub fn is_resolved(&self) -> bool {
+        !self.contents.is_empty()
+    }
+
+    /// 清除已找回的卡片内容。字根集或标签有所改变时，须重新找回卡片。
+    pub fn unresolve(&mut self) {
+        self.contents.clear();
+    }
+
+    /// 建立（或上次找回卡片）时字根集的版本。旧版的进度没有记录版本，此时为空。
+    pub fn scheme_version(&self) -> &str {
+        &self.scheme_version
+    }
+
+    pub fn set_scheme_version(&mut self, version: String) {
+        self.scheme_version = version;
+    }
+
+    /// 根据字根集找回卡片内容。
+    ///
+    /// 若本进度保存了建立时的设置，卡片会跟随字根集的更新：字根集中已不存在的卡片会被删除，
+    /// 新增的卡片则排在所有新卡片之后。旧版的进度则会先根据其保存的卡片内容补上ID，
+    /// 找不到的卡片继续使用旧的内容。
+    ///
+    /// 字根集的版本有所改变，或者卡片有所增删时，变动会记录在 update 中。
+    pub fn resolve(
+        &mut self,
+        scheme: &LoadedScheme<ZigenConfusableUnpopulated>,
+        options: SchemeOptions,
+        meta: &Scheme,
+    ) -> Result<(), String> {
+        let follows_updates = self.options.is_some();
+        let options = self.options.clone().unwrap_or(options);
+
+        let mut cards = scheme.clone().populate_confusables()?;
+        cards.sort_to_options(&options);
+
+        let ids = card_ids(&cards.0);
+        let mut contents = ids.iter().cloned().zip(cards.0).collect::<HashMap<_, _>>();
+        let mut unresolved = 0;
+
+        for card in self.scheduler.cards_mut() {
+            if card.id().is_empty()
+                && let Some(id) = card.legacy_zigen_mut().as_ref().map(SchemeZigen::card_id)
+            {
+                *card.id_mut() = id;
+            }
+
+            if contents.contains_key(card.id()) {
+                *card.legacy_zigen_mut() = None;
+            } else if let Some(legacy) = card.legacy_zigen_mut().clone() {
+                contents.insert(card.id().to_owned(), legacy);
+                unresolved += 1;
+            }
+        }
+
+        let known = self
+            .scheduler
+            .cards()
+            .map(|card| card.id().to_owned())
+            .collect::<HashSet<_>>();
+        let removed = known
+            .iter()
+            .filter(|id| !contents.contains_key(*id))
+            .cloned()
+            .collect::<Vec<_>>();
+        let added = ids
+            .into_iter()
+            .filter(|id| !known.contains(id))
+            .collect::<Vec<_>>();
+        // 旧版的进度不会新增卡片
+        let added = if follows_updates { added } else { Vec::new() };
+
+        if !removed.is_empty() || !added.is_empty() {
+            tracing::info!(
+                "scheme updated: {} cards removed, {} added",
+                removed.len(),
+                added.len()
+            );
+
+            // 卡片的增删会打乱加练队列中的下标
+            self.extra_practice = None;
+            self.scheduler.retain_cards(|id| contents.contains_key(id));
+            self.scheduler.add_new_cards(added.clone());
+        }
+
+        let version_changed = !self.scheme_version.is_empty()
+            && !meta.version.is_empty()
+            && self.scheme_version != meta.version;
+
+        if version_changed || !removed.is_empty() || !added.is_empty() {
+            self.update = Some(SchemeUpdate {
+                from_version: self.scheme_version.clone(),
+                to_version: meta.version.clone(),
+                changelog: if version_changed {
+                    meta.changelog.clone()
+                } else {
+                    String::new()
+                },
+                added,
+                removed,
+            });
+        }
+
+        if !meta.version.is_empty() {
+            self.scheme_version = meta.version.clone();
+        }
+
+        // 旧版的进度若已全部找回，便可以开始跟随字根集的更新
+        if !follows_updates && unresolved == 0 {
+            self.options = Some(options);
+        }
+
+        if self.scheduler.cards().next().is_none() {
+            return Err(String::from("无练习卡片！"));
+        }
+
+        self.contents = contents;
+        Ok(())
+    }
+
+    /// 本次启动后发现的字根集更新（如有）。
+    pub fn update(&self) -> Option<&SchemeUpdate> {
+        self.update.as_ref()
+    }
+
+    /// 用户已阅读更新提示。
+    pub fn dismiss_update(&mut self) {
+        self.update = None;
+    }
+
+    pub fn is_adept(&self) -> bool {
+        match &self.scheduler {
+            UsedScheduler::Novice(scheduler) => scheduler.is_adept(),
+            UsedScheduler::Adept(scheduler) => scheduler.is_adept(),
+            UsedScheduler::V2(scheduler) => scheduler.is_adept(),
+        }
+    }
+
+    /// 为当前卡片评分。latency 为用户作答的耗时，以秒为单位。
+    pub fn rate_card(&mut self, rating: Rating, latency: f64) {
+        if let Some(extra) = &mut self.extra_practice {
+            let idx = extra.queue.pop_front().unwrap();
+            let update_schedule = extra.update_schedule;
+
+            if extra.queue.is_empty() {
+                self.extra_practice = None;
+            }
+
+            if update_schedule {
+                self.scheduler
+                    .card_at(idx)
+                    .unwrap()
+                    .stats_mut()
+                    .record(&rating, latency);
+
+                match &mut self.scheduler {
+                    UsedScheduler::Novice(scheduler) => scheduler.rate_card_at(idx, rating),
+                    UsedScheduler::Adept(scheduler) => scheduler.rate_card_at(idx, rating),
+                    UsedScheduler::V2(scheduler) => {
+                        scheduler.rate_card_at(idx, rating, &mut rand::rng())
+                    }
+                }
+            }
+
+            return;
+        }
+
+        self.current_scheduler_card()
+            .stats_mut()
+            .record(&rating, latency);
+
+        match &mut self.scheduler {
+            UsedScheduler::Novice(scheduler) => scheduler.rate_card(rating),
+            UsedScheduler::Adept(scheduler) => scheduler.rate_card(rating),
+            UsedScheduler::V2(scheduler) => scheduler.rate_card(rating, &mut rand::rng()),
+        }
+    }
+
+    pub fn get_card(&mut self) -> CurrentCard {
+        let card = self.current_scheduler_card();
+        let is_new = card.is_new_card();
+        let id = card.id().to_owned();
+
+        let zigen = self.contents.get(&id).cloned().unwrap_or_else(|| {
+            tracing::error!("card {id} is not resolved");
+            SchemeZigen::default()
+        });
+
+        CurrentCard { zigen, is_new }
+    }
+
+    /// 将当前卡片内的归并组的顺序打乱。打乱后的顺序只在本次启动期间有效。
+    pub fn shuffle_current_card(&mut self) {
+        let id = self.current_scheduler_card().id().to_owned();
+
+        if let Some(zigen) = self.contents.get_mut(&id) {
+            zigen.as_raw_parts_mut().0.shuffle(&mut rand::rng());
+        }
+    }
+
+    fn current_scheduler_card(&mut self) -> &mut dyn ZigenCard {
+        if let Some(extra) = &self.extra_practice {
+            let idx = *extra.queue.front().unwrap();
+            return self.scheduler.card_at(idx).unwrap();
+        }
+
+        match &mut self.scheduler {
+            UsedScheduler::Novice(scheduler) => scheduler.get_card(),
+            UsedScheduler::Adept(scheduler) => scheduler.get_card(),
+            UsedScheduler::V2(scheduler) => scheduler.get_card(),
+        }
+    }
+
+    /// 所有到期的练习是否都已完成。完成后，用户可以开始弱项加练。
+    pub fn is_due_work_done(&self) -> bool {
+        match &self.scheduler {
+            UsedScheduler::Novice(scheduler) => scheduler.is_due_work_done(),
+            UsedScheduler::Adept(scheduler) => scheduler.is_due_work_done(),
+            UsedScheduler::V2(scheduler) => scheduler.is_due_work_done(),
+        }
+    }
+
+    /// 开始弱项加练，练习最弱的 count 张卡片。若 update_schedule 为假，加练结果不会影响调度。
+    ///
+    /// 若没有可以加练的卡片，返回 false。
+    pub fn start_extra_practice(&mut self, count: usize, update_schedule: bool) -> bool {
+        let queue = match &self.scheduler {
+            UsedScheduler::Novice(scheduler) => weakest_cards(scheduler.cards(), count),
+            UsedScheduler::Adept(scheduler) => weakest_cards(scheduler.cards(), count),
+            UsedScheduler::V2(scheduler) => weakest_cards(scheduler.cards(), count),
+        };
+
+        if queue.is_empty() {
+            return false;
+        }
+
+        self.extra_practice = Some(ExtraPractice {
+            queue: queue.into(),
+            update_schedule,
+        });
+
+        true
+    }
+
+    pub fn stop_extra_practice(&mut self) {
+        self.extra_practice = None;
+    }
+
+    /// 弱项加练中尚未练习的卡片数量。若不在加练中，返回 None。
+    pub fn extra_practice_remaining(&self) -> Option<usize> {
+        self.extra_practice.as_ref().map(|extra| extra.queue.len())
+    }
+
+    pub fn reviewed_cards(&self) -> usize {
+        match &self.scheduler {
+            UsedScheduler::Novice(scheduler) => scheduler.reviewed_cards(),
+            UsedScheduler::Adept(scheduler) => scheduler.reviewed_cards(),
+            UsedScheduler::V2(scheduler) => scheduler.reviewed_cards(),
+        }
+    }
+
+    pub fn total_cards(&self) -> usize {
+        match &self.scheduler {
+            UsedScheduler::Novice(scheduler) => scheduler.total_cards(),
+            UsedScheduler::Adept(scheduler) => scheduler.total_cards(),
+            UsedScheduler::V2(scheduler) => scheduler.total_cards(),
+        }
+    }
+}
@@ -0,0 +1,220 @@
+//! 字根集描述所用的富文本格式：受限的HTML子集。
+//!
+//! 描述可能来自用户载入的字根集或第三方方案，因此不能直接作为HTML插入页面，
+//! 而是先在这里解析为 Inline 节点，再由练习器渲染为 Dioxus 元素。支持的标记：
+//!
+//! - `<b>`、`<strong>`：粗体
+//! - `<i>`、`<em>`：斜体
+//! - `<br>`：换行（文本中的换行符亦然）
+//! - `<a href="https://...">`：链接，只接受 http 与 https 地址
+//! - `<zigen>`：以字根字体显示的字根，内部只能是纯文本
+//! - `&lt;`、`&gt;`、`&amp;`、`&quot;`、`&#39;`：转义字符
+//!
+//! 其他标签、属性以及未闭合的标签都会被视为错误。
+
+/// 富文本中的一个节点。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Inline {
+    Text(String),
+    Bold(Vec<Inline>),
+    Italic(Vec<Inline>),
+    Link { href: String, children: Vec<Inline> },
+    Zigen(String),
+    Break,
+}
+
+/// 尚未闭合的标签。
+enum Open {
+    Bold,
+    Italic,
+    Link(String),
+    Zigen,
+}
+
+impl Open {
+    fn name(&self) -> &'static str {
+        match self {
+            Open::Bold => "b",
+            Open::Italic => "i",
+            Open::Link(_) => "a",
+            Open::Zigen => "zigen",
+        }
+    }
+
+    fn close(self, children: Vec<Inline>) -> Result<Inline, String> {
+        Ok(match self {
+            Open::Bold => Inline::Bold(children),
+            Open::Italic => Inline::Italic(children),
+            Open::Link(href) => Inline::Link { href, children },
+            Open::Zigen => match children.as_slice() {
+                [] => Inline::Zigen(String::new()),
+                [Inline::Text(text)] => Inline::Zigen(text.clone()),
+                _ => return Err(String::from("<zigen> 标签内只能是纯文本")),
+            },
+        })
+    }
+}
+
+/// 把文本加入节点列表，与前一个文本节点合并。
+fn push_text(nodes: &mut Vec<Inline>, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+
+    match nodes.last_mut() {
+        Some(Inline::Text(last)) => last.push_str(text),
+        _ => nodes.push(Inline::Text(text.to_owned())),
+    }
+}
+
+/// 解析 `&...;` 转义字符，返回字符及其长度。无法识别时视为普通的「&」。
+fn parse_entity(rest: &str) -> (char, usize) {
+    const ENTITIES: [(&str, char); 5] = [
+        ("&lt;", '<'),
+        ("&gt;", '>'),
+        ("&amp;", '&'),
+        ("&quot;", '"'),
+        ("&#39;", '\''),
+    ];
+
+    ENTITIES
+        .iter()
+        .find(|(entity, _)| rest.starts_with(entity))
+        .map(|(entity, c)| (*c, entity.len()))
+        .unwrap_or(('&', 1))
+}
+
+/// 解析文本中的所有转义字符。
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(pos) = rest.find('&') {
+        decoded.push_str(&rest[..pos]);
+        let (c, len) = parse_entity(&rest[pos..]);
+        decoded.push(c);
+        rest = &rest[pos + len..];
+    }
+
+    decoded.push_str(rest);
+    decoded
+}
+
+/// 解析 `<a>` 标签的属性，只接受一个 href。
+fn parse_link(attributes: &str) -> Result<String, String> {
+    let value = attributes
+        .trim()
+        .strip_prefix("href")
+        .map(str::trim_start)
+        .and_then(|rest| rest.strip_prefix('='))
+        .map(str::trim_start)
+        .ok_or_else(|| String::from("<a> 标签须有 href 属性，且不能有其他属性"))?;
+
+    let quote = value
+        .chars()
+        .next()
+        .filter(|c| *c == '"' || *c == '\'')
+        .ok_or_else(|| String::from("href 属性的值须以引号括起"))?;
+
+    let (href, rest) = value[1..]
+        .split_once(quote)
+        .ok_or_else(|| String::from("href 属性的引号未闭合"))?;
+
+    if !rest.trim().is_empty() {
+        return Err(String::from("<a> 标签只能有 href 一个属性"));
+    }
+
+    let href = decode_entities(href);
+    let lower = href.to_ascii_lowercase();
+    if !lower.starts_with("https://") && !lower.starts_with("http://") {
+        return Err(format!("链接「{href}」须以 http:// 或 https:// 开头"));
+    }
+
+    Ok(href)
+}
+
+/// 解析富文本。遇到不支持的标记时返回错误。
+pub fn parse(source: &str) -> Result<Vec<Inline>, String> {
+    // 每一层：(尚未闭合的标签, 已解析的子节点)，最外层没有标签
+    let mut stack: Vec<(Option<Open>, Vec<Inline>)> = vec![(None, Vec::new())];
+    let mut rest = source;
+
+    while let Some(pos) = rest.find(['<', '&', '\n']) {
+        let nodes = &mut stack.last_mut().unwrap().1;
+        push_text(nodes, &rest[..pos]);
+        rest = &rest[pos..];
+
+        if rest.starts_with('\n') {
+            nodes.push(Inline::Break);
+            rest = &rest[1..];
+            continue;
+        }
+
+        if rest.starts_with('&') {
+            let (c, len) = parse_entity(rest);
+            push_text(nodes, c.encode_utf8(&mut [0; 4]));
+            rest = &rest[len..];
+            continue;
+        }
+
+        let end = rest
+            .find('>')
+            .ok_or_else(|| String::from("「<」未闭合；若要显示「<」本身，请写作 &lt;"))?;
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        let (name, attributes) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+        let name = name.to_ascii_lowercase();
+
+        if let Some(name) = name.strip_prefix('/') {
+            let expected = match name {
+                "strong" => "b",
+                "em" => "i",
+                other => other,
+            };
+
+            let (open, children) = stack
+                .pop()
+                .filter(|(open, _)| open.as_ref().is_some_and(|open| open.name() == expected))
+                .ok_or_else(|| format!("</{name}> 没有对应的开始标签"))?;
+
+            let node = open.unwrap().close(children)?;
+            stack.last_mut().unwrap().1.push(node);
+            continue;
+        }
+
+        let name = name.trim_end_matches('/');
+        let attributes = attributes.trim().trim_end_matches('/');
+
+        if name != "a" && !attributes.is_empty() {
+            return Err(format!("<{name}> 标签不能有属性"));
+        }
+
+        let open = match name {
+            "br" => {
+                stack.last_mut().unwrap().1.push(Inline::Break);
+                continue;
+            }
+            "b" | "strong" => Open::Bold,
+            "i" | "em" => Open::Italic,
+            "a" => Open::Link(parse_link(attributes)?),
+            "zigen" => Open::Zigen,
+            _ => return Err(format!("不支持 <{name}> 标签")),
+        };
+
+        stack.push((Some(open), Vec::new()));
+    }
+
+    if let Some(open) = stack.last().and_then(|(open, _)| open.as_ref()) {
+        return Err(format!("<{}> 标签未闭合", open.name()));
+    }
+
+    let mut nodes = stack.pop().unwrap().1;
+    push_text(&mut nodes, rest);
+    Ok(nodes)
+}
+
+/// 解析富文本；格式有误时，把整段文本当作纯文本显示。
+pub fn parse_or_plain(source: &str) -> Vec<Inline> {
+    parse(source).unwrap_or_else(|_| vec![Inline::Text(source.to_owned())])
+}
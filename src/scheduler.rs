@@ -3,43 +3,156 @@ use std::marker::PhantomData;
 
 use chrono::{DateTime, Duration, Utc};
 use dioxus_logger::tracing;
-use rand::seq::SliceRandom;
 use serde_derive::{Deserialize, Serialize};
 
-use crate::scheme::SchemeZigen;
+use crate::scheme::{CodeStructure, SchemeZigen};
 
 pub trait ZigenCard {
-    fn zigen(&self) -> &SchemeZigen;
+    /// 卡片的ID（见 scheme::card_ids）。卡片内容须在加载字根集后根据ID找回。
+    fn id(&self) -> &str;
 
-    fn zigen_mut(&mut self) -> &mut SchemeZigen;
+    fn id_mut(&mut self) -> &mut String;
+
+    /// 旧版的练习进度直接保存了卡片的完整内容。迁移后，只有无法在字根集内找回的卡片
+    /// 才会保留这份内容。
+    fn legacy_zigen_mut(&mut self) -> &mut Option<SchemeZigen>;
 
     fn is_new_card(&self) -> bool;
 
-    fn shuffle(&mut self);
+    fn stats(&self) -> &CardStats;
+
+    fn stats_mut(&mut self) -> &mut CardStats;
+
+    /// 卡片的容易系数。尚未进入复习阶段的卡片没有容易系数。
+    fn easiness_factor(&self) -> Option<f64>;
+
+    /// 卡片当前的复习间隔（上一次作答至到期的时间）。尚未进入复习阶段的卡片没有复习间隔。
+    fn interval(&self) -> Option<Duration>;
+}
+
+/// 卡片的作答记录，用于挑选弱项卡片。
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct CardStats {
+    /// 答错（Again）的次数。
+    pub lapses: usize,
+    /// 最近数次作答的耗时，以秒为单位。
+    pub latencies: VecDeque<f64>,
+}
+
+impl CardStats {
+    /// 最多保留多少次作答耗时。
+    const MAX_LATENCIES: usize = 8;
+
+    pub fn record(&mut self, rating: &Rating, latency: f64) {
+        if *rating == Rating::Again {
+            self.lapses += 1;
+        }
+
+        if self.latencies.len() >= Self::MAX_LATENCIES {
+            self.latencies.pop_front();
+        }
+        self.latencies.push_back(latency);
+    }
+
+    pub fn mean_latency(&self) -> f64 {
+        if self.latencies.is_empty() {
+            0.0
+        } else {
+            self.latencies.iter().sum::<f64>() / self.latencies.len() as f64
+        }
+    }
+}
+
+/// 按调度状态挑出最弱的 n 张卡片，返回这些卡片在 cards 中的下标，最弱者在前。
+///
+/// 排序依据依次为：容易系数（低者优先）、答错次数（多者优先）、平均作答耗时（长者优先）。
+/// 未曾学习过的卡片不参与排序。
+pub fn weakest_cards<'a, C: ZigenCard + 'a>(
+    cards: impl Iterator<Item = &'a C>,
+    n: usize,
+) -> Vec<usize> {
+    let mut ranked = cards
+        .enumerate()
+        .filter(|(_, card)| !card.is_new_card())
+        .map(|(i, card)| {
+            // 学习阶段的卡片还没有容易系数，视为初始值
+            let easiness_factor = card.easiness_factor().unwrap_or(2.5);
+            let stats = card.stats();
+            (i, easiness_factor, stats.lapses, stats.mean_latency())
+        })
+        .collect::<Vec<_>>();
+
+    ranked.sort_by(|(_, ef1, lapses1, latency1), (_, ef2, lapses2, latency2)| {
+        ef1.total_cmp(ef2)
+            .then(lapses2.cmp(lapses1))
+            .then(latency2.total_cmp(latency1))
+    });
+
+    ranked.into_iter().take(n).map(|(i, ..)| i).collect()
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, Default)]
 pub struct SchedulerCard {
-    zigen: SchemeZigen,
+    #[serde(default)]
+    id: String,
+    #[serde(default, rename = "zigen", skip_serializing_if = "Option::is_none")]
+    legacy_zigen: Option<SchemeZigen>,
     card: Card,
+    #[serde(default)]
+    stats: CardStats,
+}
+
+impl SchedulerCard {
+    pub fn new(id: String) -> Self {
+        Self {
+            id,
+            ..Default::default()
+        }
+    }
 }
 
 impl ZigenCard for SchedulerCard {
-    fn zigen(&self) -> &SchemeZigen {
-        &self.zigen
+    fn id(&self) -> &str {
+        &self.id
     }
 
-    fn zigen_mut(&mut self) -> &mut SchemeZigen {
-        &mut self.zigen
+    fn id_mut(&mut self) -> &mut String {
+        &mut self.id
     }
 
-    fn shuffle(&mut self) {
-        self.zigen.as_raw_parts_mut().0.shuffle(&mut rand::rng());
+    fn legacy_zigen_mut(&mut self) -> &mut Option<SchemeZigen> {
+        &mut self.legacy_zigen
     }
 
     fn is_new_card(&self) -> bool {
         self.card == Card::New
     }
+
+    fn stats(&self) -> &CardStats {
+        &self.stats
+    }
+
+    fn stats_mut(&mut self) -> &mut CardStats {
+        &mut self.stats
+    }
+
+    fn easiness_factor(&self) -> Option<f64> {
+        match self.card {
+            Card::Review {
+                easiness_factor, ..
+            } => Some(easiness_factor),
+            _ => None,
+        }
+    }
+
+    fn interval(&self) -> Option<Duration> {
+        match self.card {
+            Card::Review {
+                last_reviewed, due, ..
+            } => Some(due - last_reviewed),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -61,6 +174,54 @@ impl Rating {
     }
 }
 
+/// 作答时所犯的错误。大码错了意味着连字根所在的键位都没记住，比小码错误严重得多。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Mistake {
+    /// 大码正确，但小码错误。
+    Minor,
+    /// 大码错误，或该方案不区分大小码。
+    Major,
+    /// 用户主动查看了答案。
+    Revealed,
+}
+
+impl Mistake {
+    /// 犯错后最终答对时，卡片应得的评分。只有小码错误的卡片不必从头学起。
+    pub fn rating(self) -> Rating {
+        match self {
+            Mistake::Minor => Rating::Hard,
+            Mistake::Major | Mistake::Revealed => Rating::Again,
+        }
+    }
+}
+
+/// 判断作答犯了哪种错误，答对时返回 None。
+///
+/// answers 与 accepted_answers 皆为作答时的形式（见 ZigenGroup::accepted_codes），一组一项。
+///
+/// 若方案区分大小码，只要某个可接受编码的大码与作答相同，该归并组便只算小码错误。
+pub fn find_mistake(
+    answers: &[String],
+    accepted_answers: &[Vec<String>],
+    code_structure: Option<&CodeStructure>,
+) -> Option<Mistake> {
+    answers
+        .iter()
+        .zip(accepted_answers)
+        .filter(|(answer, codes)| !codes.contains(answer))
+        .map(|(answer, codes)| match code_structure {
+            Some(structure)
+                if codes
+                    .iter()
+                    .any(|code| structure.split(code).0 == structure.split(answer).0) =>
+            {
+                Mistake::Minor
+            }
+            _ => Mistake::Major,
+        })
+        .max()
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
 enum Card {
     /// 全新的，未曾学习过的卡片。
@@ -96,6 +257,31 @@ enum Card {
     }, // todo: 在这个阶段的卡片使用due排序，「每天」复习已due的卡片
 }
 
+impl Card {
+    /// 在不调整卡片位置的情况下更新复习阶段卡片的状态，用于弱项加练。
+    fn rate_in_place(&mut self, rating: &Rating) {
+        if let Card::Review {
+            repetition,
+            easiness_factor,
+            last_reviewed,
+            ..
+        } = self
+        {
+            if *rating == Rating::Again {
+                *repetition = 0;
+            } else {
+                *repetition += 1;
+            }
+
+            let difficulty = rating.difficulty();
+            *easiness_factor += 0.1 - difficulty * (0.08 + difficulty * 0.2);
+            *easiness_factor = easiness_factor.max(1.3);
+
+            *last_reviewed = Utc::now();
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Scheduler<P: ScheduleParam> {
     new_cards: Vec<SchedulerCard>,
@@ -451,4 +637,54 @@ impl<Param: ScheduleParam> Scheduler<Param> {
     pub fn total_cards(&self) -> usize {
         self.new_cards.len() + self.learning_cards.len() + self.reviewing_cards.len()
     }
+
+    /// 新卡与学习阶段的卡片是否都已经清空。
+    pub fn is_due_work_done(&self) -> bool {
+        self.new_cards.is_empty() && self.learning_cards.is_empty()
+    }
+
+    pub fn cards(&self) -> impl Iterator<Item = &SchedulerCard> {
+        self.new_cards
+            .iter()
+            .chain(self.learning_cards.iter())
+            .chain(self.reviewing_cards.iter())
+    }
+
+    pub fn cards_mut(&mut self) -> impl Iterator<Item = &mut SchedulerCard> {
+        self.new_cards
+            .iter_mut()
+            .chain(self.learning_cards.iter_mut())
+            .chain(self.reviewing_cards.iter_mut())
+    }
+
+    /// 删除不符合条件的卡片，用于跟随字根集的更新。
+    pub fn retain_cards(&mut self, mut keep: impl FnMut(&SchedulerCard) -> bool) {
+        self.new_cards.retain(&mut keep);
+        self.learning_cards.retain(&mut keep);
+        self.reviewing_cards.retain(&mut keep);
+        self.populate_learning_cards();
+    }
+
+    /// 加入新卡片。新卡片排在其他所有新卡片之后。
+    pub fn add_new_cards(&mut self, cards: Vec<SchedulerCard>) {
+        // new_cards 是倒序的，末尾的卡片最先被学习
+        self.new_cards.splice(0..0, cards.into_iter().rev());
+        self.populate_learning_cards();
+    }
+
+    /// 按 self.cards() 的顺序取得第 idx 张卡片。
+    pub fn card_at(&mut self, idx: usize) -> Option<&mut SchedulerCard> {
+        self.new_cards
+            .iter_mut()
+            .chain(self.learning_cards.iter_mut())
+            .chain(self.reviewing_cards.iter_mut())
+            .nth(idx)
+    }
+
+    /// 为第 idx 张卡片评分，但不改变其在队列中的位置。
+    pub fn rate_card_at(&mut self, idx: usize, rating: Rating) {
+        if let Some(card) = self.card_at(idx) {
+            card.card.rate_in_place(&rating);
+        }
+    }
 }
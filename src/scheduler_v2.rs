@@ -1,36 +1,73 @@
 use chrono::{DateTime, Duration, Utc};
 use dioxus_logger::tracing;
-use rand::seq::SliceRandom;
+use rand::Rng;
 use serde_derive::{Deserialize, Serialize};
 
-use crate::scheduler::{Rating, ZigenCard};
+use crate::scheduler::{CardStats, Rating, ZigenCard};
 use crate::scheme::SchemeZigen;
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize, Default)]
 pub struct SchedulerV2Card {
-    zigen: SchemeZigen,
+    #[serde(default)]
+    id: String,
+    #[serde(default, rename = "zigen", skip_serializing_if = "Option::is_none")]
+    legacy_zigen: Option<SchemeZigen>,
     card: Card,
+    #[serde(default)]
+    stats: CardStats,
+}
+
+impl SchedulerV2Card {
+    pub fn new(id: String) -> Self {
+        Self {
+            id,
+            ..Default::default()
+        }
+    }
 }
 
 impl ZigenCard for SchedulerV2Card {
-    fn zigen(&self) -> &SchemeZigen {
-        &self.zigen
+    fn id(&self) -> &str {
+        &self.id
     }
 
-    fn zigen_mut(&mut self) -> &mut SchemeZigen {
-        &mut self.zigen
+    fn id_mut(&mut self) -> &mut String {
+        &mut self.id
     }
 
-    fn shuffle(&mut self) {
-        self.zigen_mut()
-            .as_raw_parts_mut()
-            .0
-            .shuffle(&mut rand::rng());
+    fn legacy_zigen_mut(&mut self) -> &mut Option<SchemeZigen> {
+        &mut self.legacy_zigen
     }
 
     fn is_new_card(&self) -> bool {
         self.card == Card::New
     }
+
+    fn stats(&self) -> &CardStats {
+        &self.stats
+    }
+
+    fn stats_mut(&mut self) -> &mut CardStats {
+        &mut self.stats
+    }
+
+    fn easiness_factor(&self) -> Option<f64> {
+        match self.card {
+            Card::Review {
+                easiness_factor, ..
+            } => Some(easiness_factor),
+            _ => None,
+        }
+    }
+
+    fn interval(&self) -> Option<Duration> {
+        match self.card {
+            Card::Review {
+                last_reviewed, due, ..
+            } => Some(due - last_reviewed),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
@@ -183,6 +220,45 @@ impl ScheduleParam {
     }
 }
 
+/// 复习间隔的随机扰动幅度，以间隔的比例表示。
+pub const FUZZ_FACTOR: f64 = 0.15;
+
+/// 负载均衡时，在扰动范围内抽取多少个候选到期时间。
+pub const LOAD_BALANCE_CANDIDATES: usize = 8;
+
+/// 与候选时间相距多远的卡片算作“附近”，以整个扰动范围的比例表示。
+///
+/// 范围过大时，靠近扰动范围两端的候选只会计入一侧的卡片，负载总是偏低，卡片反而会挤在两端。
+pub const LOAD_BALANCE_WINDOW: f64 = 0.05;
+
+/// 在 last_reviewed + interval 前后 FUZZ_FACTOR 的范围内随机抽取 LOAD_BALANCE_CANDIDATES 个
+/// 候选到期时间，返回附近到期的其他卡片（other_dues）最少者；负载相同时取先抽到者。
+///
+/// “附近”指与候选时间相距不到 LOAD_BALANCE_WINDOW 个扰动范围的卡片，但至少为一秒。
+pub fn balanced_due(
+    last_reviewed: DateTime<Utc>,
+    interval: Duration,
+    other_dues: &[DateTime<Utc>],
+    rng: &mut impl Rng,
+) -> DateTime<Utc> {
+    let interval = interval.as_seconds_f64();
+    let fuzz = interval * FUZZ_FACTOR;
+    let window = (2.0 * fuzz * LOAD_BALANCE_WINDOW).max(1.0);
+
+    (0..LOAD_BALANCE_CANDIDATES)
+        .map(|_| {
+            let offset = interval + rng.random_range(-fuzz..=fuzz);
+            last_reviewed + Duration::milliseconds((offset * 1000.0) as i64)
+        })
+        .min_by_key(|candidate| {
+            other_dues
+                .iter()
+                .filter(|due| (**due - *candidate).as_seconds_f64().abs() < window)
+                .count()
+        })
+        .unwrap()
+}
+
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize, Default)]
 pub struct SchedulerV2 {
     new_cards: Vec<SchedulerV2Card>,
@@ -238,7 +314,9 @@ impl SchedulerV2 {
             }
         }
 
-        let first = self.learning_cards.first().unwrap().zigen.clone();
+        let Some(first) = self.learning_cards.first().map(|card| card.id.clone()) else {
+            return;
+        };
 
         // 排序优先度：
         // - 最先：已过期复习阶段卡片，越早过期的越优先
@@ -292,7 +370,7 @@ impl SchedulerV2 {
             }
         });
 
-        if self.learning_cards.first().unwrap().zigen == first && self.learning_cards.len() > 1 {
+        if self.learning_cards.first().unwrap().id == first && self.learning_cards.len() > 1 {
             self.learning_cards.swap(0, 1);
         }
     }
@@ -302,17 +380,44 @@ impl SchedulerV2 {
         self.learning_cards.first_mut().unwrap()
     }
 
-    pub fn rate_card(&mut self, rating: Rating) {
-        let card = self
+    pub fn rate_card(&mut self, rating: Rating, rng: &mut impl Rng) {
+        let mut card = self
             .learning_cards
             .first()
             .unwrap()
             .card
             .rate_card(&self.sched_param, rating);
 
+        self.balance_due(self.new_cards.len(), &mut card, rng);
+
         self.learning_cards.first_mut().unwrap().card = card;
     }
 
+    /// 对复习阶段卡片的到期时间进行随机扰动，并在扰动范围内挑选到期卡片最少的时间点，
+    /// 避免同时学会的卡片全部挤在同一时间到期。
+    ///
+    /// exclude 为该卡片在 self.cards() 中的下标，计算负载时不把卡片自身算进去。
+    fn balance_due(&self, exclude: usize, card: &mut Card, rng: &mut impl Rng) {
+        let Card::Review {
+            last_reviewed, due, ..
+        } = card
+        else {
+            return;
+        };
+
+        let other_dues = self
+            .cards()
+            .enumerate()
+            .filter(|(i, _)| *i != exclude)
+            .filter_map(|(_, card)| match card.card {
+                Card::Review { due, .. } => Some(due),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        *due = balanced_due(*last_reviewed, *due - *last_reviewed, &other_dues, rng);
+    }
+
     pub fn reviewed_cards(&self) -> usize {
         let not_yet_learned = self
             .learning_cards
@@ -327,4 +432,58 @@ impl SchedulerV2 {
     pub fn total_cards(&self) -> usize {
         self.new_cards.len() + self.learning_cards.len()
     }
+
+    /// 新卡是否已经用完，并且没有需要学习或已经过期的卡片。与 V1 调度器一样，
+    /// 新卡与学习阶段的卡片都算作尚未完成的练习。
+    pub fn is_due_work_done(&self) -> bool {
+        let now = Utc::now();
+
+        self.new_cards.is_empty()
+            && self
+                .learning_cards
+                .iter()
+                .all(|card| !card.card.needs_learning(now))
+    }
+
+    pub fn cards(&self) -> impl Iterator<Item = &SchedulerV2Card> {
+        self.new_cards.iter().chain(self.learning_cards.iter())
+    }
+
+    pub fn cards_mut(&mut self) -> impl Iterator<Item = &mut SchedulerV2Card> {
+        self.new_cards
+            .iter_mut()
+            .chain(self.learning_cards.iter_mut())
+    }
+
+    /// 删除不符合条件的卡片，用于跟随字根集的更新。
+    pub fn retain_cards(&mut self, mut keep: impl FnMut(&SchedulerV2Card) -> bool) {
+        self.new_cards.retain(&mut keep);
+        self.learning_cards.retain(&mut keep);
+    }
+
+    /// 加入新卡片。新卡片排在其他所有新卡片之后。
+    pub fn add_new_cards(&mut self, cards: Vec<SchedulerV2Card>) {
+        // new_cards 是倒序的，末尾的卡片最先被学习
+        self.new_cards.splice(0..0, cards.into_iter().rev());
+    }
+
+    /// 按 self.cards() 的顺序取得第 idx 张卡片。
+    pub fn card_at(&mut self, idx: usize) -> Option<&mut SchedulerV2Card> {
+        self.new_cards
+            .iter_mut()
+            .chain(self.learning_cards.iter_mut())
+            .nth(idx)
+    }
+
+    /// 为第 idx 张卡片评分。卡片的位置会在下一次 populate_learning_cards 时重新排序。
+    pub fn rate_card_at(&mut self, idx: usize, rating: Rating, rng: &mut impl Rng) {
+        let Some(card) = self.cards().nth(idx) else {
+            return;
+        };
+
+        let mut card = card.card.rate_card(&self.sched_param, rating);
+        self.balance_due(idx, &mut card, rng);
+
+        self.card_at(idx).unwrap().card = card;
+    }
 }
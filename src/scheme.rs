@@ -1,12 +1,12 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::ops::Deref;
 
 use rand::seq::SliceRandom;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
 /// scheme.json的结构。这个JSON文件将列出练习器实例所支持的所有方案。
 /// 练习器开始加载时，这将会是练习器第一个下载的文件。
-#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct Scheme {
     /// 方案ID，必须是独一无二的。
     pub id: String,
@@ -25,13 +25,157 @@ pub struct Scheme {
     /// 方案字根集的URL，字根集的格式详情请参考 LoadedScheme 与 ZigenCluster 。
     /// 如果不是绝对地址，则默认根目录为 scheme.json 的所在目录。
     pub zigen_url: String,
-    /// 方案字根集所需字体的URL。
-    pub zigen_font: String,
+    /// 方案字根集所需字体的URL，规则与 zigen_url 相同。可以是单个URL，也可以是按优先次序
+    /// 排列的数组：前面的字体缺少的字形，由后面的字体补上。空字符串会被忽略。
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub zigen_font: Vec<String>,
+    /// 字根集的版本（可选），比如 "2025-06-01" 或 "3.1"。字根集有所改动时应更新此值，
+    /// 练习器会据此提醒正在练习该方案的用户。
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub version: String,
+    /// 本版本的更新说明（可选），会在用户继续旧版本的进度时显示。
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub changelog: String,
+    /// 方案的编码结构。若不提供，练习器会将整个编码视为一体。
+    #[serde(default)]
+    pub code_structure: Option<CodeStructure>,
+    /// 方案的按键定义。若不提供，则为26个字母。
+    #[serde(default)]
+    pub keyboard: KeySet,
+    /// 推荐的练习设置，用于预先填写设置页面，以及「继续上次练习」。未提供的项目沿用
+    /// SchemeOptions::recommended 的值。
+    #[serde(default = "SchemeOptions::recommended")]
+    pub default_options: SchemeOptions,
+}
+
+/// 读取单个字符串或字符串数组，并去掉其中的空字符串。
+fn one_or_many<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    let list = match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(one) => vec![one],
+        OneOrMany::Many(many) => many,
+    };
+
+    Ok(list.into_iter().filter(|item| !item.is_empty()).collect())
+}
+
+/// 编码的结构。许多方案（比如宇浩系列）的字根编码由大码与小码组成，
+/// 练习器会将两者分开显示，并分别判断对错。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CodeStructure {
+    /// 大码的长度（以字母计），其后的部分皆为小码。
+    pub major_len: usize,
+}
+
+impl CodeStructure {
+    /// 将编码分为大码与小码两部分。编码不长于大码时，小码为空。
+    pub fn split<'a>(&self, code: &'a str) -> (&'a str, &'a str) {
+        let mid = code
+            .char_indices()
+            .nth(self.major_len)
+            .map(|(i, _)| i)
+            .unwrap_or(code.len());
+
+        code.split_at(mid)
+    }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Default)]
+/// 未声明按键时，方案编码允许使用的按键。
+pub const DEFAULT_KEYS: &str = "abcdefghijklmnopqrstuvwxyz";
+
+/// 方案的按键定义。字母不区分大小写，其他按键则须完全相同。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeySet {
+    /// 方案使用的所有按键。空格被练习器用于显示答案，不能作为按键。
+    pub keys: String,
+    /// 按键别名：输入别名等同于输入对应的按键，比如全角的「；」之于「;」。
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<char, char>,
+}
+
+impl Default for KeySet {
+    fn default() -> Self {
+        Self {
+            keys: String::from(DEFAULT_KEYS),
+            aliases: BTreeMap::new(),
+        }
+    }
+}
+
+impl KeySet {
+    /// 将输入的字符转换为方案的按键。字符不属于方案时返回 None。
+    pub fn normalize(&self, input: char) -> Option<char> {
+        let input = self.aliases.get(&input).copied().unwrap_or(input);
+
+        self.keys
+            .chars()
+            .find(|key| key.to_lowercase().eq(input.to_lowercase()))
+    }
+
+    /// 将编码转换为作答时的形式（即逐个按键 normalize）。不属于方案的字符保持不变。
+    pub fn normalize_code(&self, code: &str) -> String {
+        code.chars()
+            .map(|c| self.normalize(c).unwrap_or(c))
+            .collect()
+    }
+
+    /// 编码中不属于方案的字符。
+    pub fn invalid_keys(&self, code: &str) -> String {
+        code.chars()
+            .filter(|c| self.normalize(*c).is_none())
+            .collect()
+    }
+
+    /// 检查按键定义本身的问题，例如重复的按键或指向未知按键的别名。
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        if self.keys.is_empty() {
+            problems.push(String::from("按键定义不能为空"));
+        }
+
+        if self.keys.chars().any(char::is_whitespace) {
+            problems.push(String::from("空格被用于显示答案，不能作为按键"));
+        }
+
+        let keys = self.keys.chars().collect::<Vec<_>>();
+        for (i, key) in keys.iter().enumerate() {
+            if keys[..i]
+                .iter()
+                .any(|other| other.to_lowercase().eq(key.to_lowercase()))
+            {
+                problems.push(format!("按键「{key}」重复"));
+            }
+        }
+
+        for (alias, key) in self.aliases.iter() {
+            if !keys.contains(key) {
+                problems.push(format!("别名「{alias}」指向的「{key}」不是方案的按键"));
+            }
+            if keys.contains(alias) {
+                problems.push(format!("别名「{alias}」本身就是方案的按键"));
+            }
+        }
+
+        problems
+    }
+}
+
+/// 练习设置。
+///
+/// 反序列化时，未提供的项目取 SchemeOptions::recommended 的值（即乱序模式默认开启），
+/// 这与 Default 不同：Default 关闭所有选项，以便按原顺序整理卡片。
+#[derive(Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SchemeOptions {
     /// 乱序模式
+    #[serde(default = "default_shuffle")]
     pub shuffle: bool,
     /// 简繁通练
     pub combined_training: bool,
@@ -41,13 +185,55 @@ pub struct SchemeOptions {
     pub adept: bool,
     /// 字根合并模式
     pub combine_mode: CombineMode,
-    /// 仅训练键面
+    /// 仅训练键面。按键须为方案的按键定义中的按键，比较时不区分大小写
     pub limit_keys: Option<Vec<char>>,
     /// 使用V2调度器
     pub v2_sched: bool,
+    /// 作答前显示例字作为提示
+    pub examples_as_prompt: bool,
+    /// 只训练带有其中任一标签的字根。留空以训练所有字根
+    pub include_tags: Vec<String>,
+    /// 不训练带有其中任一标签的字根
+    pub exclude_tags: Vec<String>,
+}
+
+fn default_shuffle() -> bool {
+    true
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Default)]
+impl SchemeOptions {
+    /// 方案未提供推荐设置时使用的设置，即设置页面的默认值。
+    pub fn recommended() -> Self {
+        Self {
+            shuffle: true,
+            ..Default::default()
+        }
+    }
+
+    /// 编码的第一个按键（即键面）是否在 limit_keys 之内。
+    pub fn allows_code(&self, code: &str) -> bool {
+        let Some(limit_keys) = &self.limit_keys else {
+            return true;
+        };
+
+        code.chars().next().is_some_and(|first| {
+            limit_keys
+                .iter()
+                .any(|key| key.to_lowercase().eq(first.to_lowercase()))
+        })
+    }
+
+    /// 归并组是否符合 limit_keys 与标签的筛选条件。
+    pub fn allows_group(&self, group: &ZigenGroup) -> bool {
+        let has_any = |tags: &[String]| tags.iter().any(|tag| group.tags.contains(tag));
+
+        self.allows_code(&group.code)
+            && (self.include_tags.is_empty() || has_any(&self.include_tags))
+            && !has_any(&self.exclude_tags)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum CombineMode {
     #[default]
     Cluster,
@@ -60,7 +246,142 @@ pub enum CombineMode {
 pub struct LoadedScheme<Z>(pub Vec<SchemeZigen<Z>>);
 
 impl LoadedScheme<ZigenConfusableUnpopulated> {
-    pub fn populate_confusables(self) -> Result<LoadedScheme<ZigenConfusable>, String> {
+    /// 字根集内所有的归并组。
+    pub fn groups(&self) -> impl Iterator<Item = &ZigenGroup> {
+        self.0
+            .iter()
+            .filter_map(|entry| match entry {
+                SchemeZigen::Cluster(cluster) => Some(cluster.groups.iter()),
+                SchemeZigen::Confusable(_) | SchemeZigen::Decomposition(_) => None,
+            })
+            .flatten()
+    }
+
+    /// 字根集内用到的所有标签。
+    pub fn tags(&self) -> BTreeSet<&str> {
+        self.groups()
+            .flat_map(|group| group.tags.iter().map(String::as_str))
+            .collect()
+    }
+
+    /// 为归并组加上用户自定义的标签。tags 以归并组的代表性字根为键，已有的标签不会重复添加。
+    pub fn apply_tags(&mut self, tags: &BTreeMap<String, Vec<String>>) {
+        let groups = self.0.iter_mut().filter_map(|entry| match entry {
+            SchemeZigen::Cluster(cluster) => Some(cluster.groups.iter_mut()),
+            SchemeZigen::Confusable(_) | SchemeZigen::Decomposition(_) => None,
+        });
+
+        for group in groups.flatten() {
+            let Some(extra) = group.zigens.first().and_then(|root| tags.get(&root.0)) else {
+                continue;
+            };
+
+            for tag in extra {
+                if !group.tags.contains(tag) {
+                    group.tags.push(tag.clone());
+                }
+            }
+        }
+    }
+
+    /// 确认所有编码都只使用方案的按键，否则用户将无法作答。
+    pub fn check_keys(&self, keyboard: &KeySet) -> Result<(), String> {
+        for group in self.groups() {
+            for code in group.codes() {
+                let invalid = keyboard.invalid_keys(code);
+
+                if !invalid.is_empty() {
+                    return Err(format!(
+                        "字根「{}」的编码「{code}」含有方案键位以外的字符：{invalid}",
+                        group
+                            .zigens
+                            .first()
+                            .map(|z| z.0.as_str())
+                            .unwrap_or_default()
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 检查展开混淆集与拆分前必须满足的结构。本地文件与第三方方案不一定经过 zigen-lint 检查，
+    /// 这些错误须在此报告，而不是在展开时 panic 或产生无法作答的卡片。
+    fn check_structure(&self) -> Result<(), String> {
+        let empty_groups = self
+            .groups()
+            .filter(|group| group.zigens.is_empty())
+            .map(|group| group.code.clone())
+            .collect::<Vec<_>>();
+        if !empty_groups.is_empty() {
+            return Err(format!(
+                "以下编码的归并组没有字根：{}",
+                empty_groups.join("、")
+            ));
+        }
+
+        let mut seen = HashSet::new();
+        let mut repeated = Vec::new();
+        for zigen in self.0.iter().flat_map(|zigen| match zigen {
+            SchemeZigen::Confusable(con) => con.zigens.as_slice(),
+            _ => &[],
+        }) {
+            if !seen.insert(zigen) && !repeated.contains(&zigen.0) {
+                repeated.push(zigen.0.clone());
+            }
+        }
+        if !repeated.is_empty() {
+            return Err(format!(
+                "以下字根出现在多个混淆集中：{}",
+                repeated.join("、")
+            ));
+        }
+
+        let incomplete = self
+            .0
+            .iter()
+            .filter_map(|zigen| match zigen {
+                SchemeZigen::Decomposition(dec)
+                    if dec.character.is_empty() || dec.split.is_empty() =>
+                {
+                    Some(if dec.character.is_empty() {
+                        String::from("（空）")
+                    } else {
+                        dec.character.clone()
+                    })
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if !incomplete.is_empty() {
+            return Err(format!("拆分缺少被拆的字或字根：{}", incomplete.join("、")));
+        }
+
+        Ok(())
+    }
+
+    pub fn populate_confusables(mut self) -> Result<LoadedScheme<ZigenConfusable>, String> {
+        self.check_structure()?;
+
+        let mut occurrences = HashMap::<Zigen, usize>::new();
+        for group in self.0.iter_mut().flat_map(|zigen| match zigen {
+            SchemeZigen::Cluster(cat) => cat.groups.iter_mut(),
+            SchemeZigen::Confusable(_) | SchemeZigen::Decomposition(_) => [].iter_mut(),
+        }) {
+            let Some(root) = group.zigens.first() else {
+                continue;
+            };
+
+            let count = occurrences.entry(root.clone()).or_default();
+            *count += 1;
+            group.id = if *count == 1 {
+                root.0.clone()
+            } else {
+                format!("{}#{count}", root.0)
+            };
+        }
+
         let mut populated_confusables = self
             .0
             .iter()
@@ -87,6 +408,40 @@ impl LoadedScheme<ZigenConfusableUnpopulated> {
                 }
             });
 
+        // 拆分中的字根可以是归并组内的任何字根
+        let mut all_groups = HashMap::new();
+        for group in self.groups() {
+            for zigen in group.zigens.iter() {
+                all_groups.entry(zigen.clone()).or_insert(group);
+            }
+        }
+
+        let mut error_decompositions = Vec::new();
+        let decomposition_groups = self
+            .0
+            .iter()
+            .filter_map(|zigen| match zigen {
+                SchemeZigen::Decomposition(dec) => Some(dec),
+                _ => None,
+            })
+            .map(|dec| {
+                dec.split
+                    .iter()
+                    .filter_map(|zigen| match all_groups.get(zigen) {
+                        Some(group) => Some(ZigenGroup {
+                            zigens: vec![zigen.clone()],
+                            ..(*group).clone()
+                        }),
+                        None => {
+                            error_decompositions.push(format!("{}（{}）", dec.character, zigen.0));
+                            None
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let mut decomposition_groups = decomposition_groups.into_iter();
+
         let mut error_groups = Vec::new();
         let new_scheme = self
             .0
@@ -104,13 +459,15 @@ impl LoadedScheme<ZigenConfusableUnpopulated> {
                         }
                     }
 
-                    
-
                     ZigenConfusable {
                         groups,
                         description: con.description.to_owned(),
                     }
                 }),
+                SchemeZigen::Decomposition(dec) => SchemeZigen::Decomposition(ZigenDecomposition {
+                    groups: decomposition_groups.next().unwrap_or_default(),
+                    ..dec
+                }),
             })
             .collect::<Vec<_>>();
 
@@ -120,6 +477,13 @@ impl LoadedScheme<ZigenConfusableUnpopulated> {
             return Err(err_msg);
         }
 
+        if !error_decompositions.is_empty() {
+            return Err(format!(
+                "拆分使用的字根不在字根码表内：{}",
+                error_decompositions.join("、")
+            ));
+        }
+
         Ok(LoadedScheme(new_scheme))
     }
 }
@@ -134,25 +498,31 @@ impl LoadedScheme<ZigenConfusable> {
             .0
             .iter()
             .filter(|zigens| match zigens {
-                SchemeZigen::Cluster(_) => false,
+                SchemeZigen::Cluster(_) | SchemeZigen::Decomposition(_) => false,
                 SchemeZigen::Confusable(con) => {
                     let ZigenConfusable { groups, .. } = con;
-                    if let Some(limit_keys) = &options.limit_keys {
-                        groups.iter().all(|group| {
-                            limit_keys
-                                .contains(&group.code.chars().nth(0).unwrap().to_ascii_uppercase())
-                        })
-                    } else {
-                        true
-                    }
+                    groups.iter().all(|group| options.allows_group(group))
+                }
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        // 拆分练习排在最后：先熟悉字根，再练习拆字
+        let mut decompositions = self
+            .0
+            .iter()
+            .filter(|zigens| match zigens {
+                SchemeZigen::Decomposition(dec) => {
+                    dec.groups.iter().all(|group| options.allows_group(group))
                 }
+                _ => false,
             })
             .cloned()
             .collect::<Vec<_>>();
 
         let categories = self.0.iter().filter_map(|zigens| match zigens {
             SchemeZigen::Cluster(cat) => Some((&cat.groups, &cat.description)),
-            SchemeZigen::Confusable(_con) => None,
+            SchemeZigen::Confusable(_) | SchemeZigen::Decomposition(_) => None,
         });
 
         for (cat, cat_desc) in categories.clone() {
@@ -165,14 +535,7 @@ impl LoadedScheme<ZigenConfusable> {
                                 && group.classify == ZigenClass::Traditional))
                             || (group.classify == ZigenClass::Common)
                     })
-                    .filter(|group| {
-                        if let Some(limit_keys) = &options.limit_keys {
-                            limit_keys
-                                .contains(&group.code.chars().nth(0).unwrap().to_ascii_uppercase())
-                        } else {
-                            true
-                        }
-                    })
+                    .filter(|group| options.allows_group(group))
                     .cloned()
                     .collect::<Vec<_>>(),
                 description: cat_desc.to_owned(),
@@ -185,14 +548,7 @@ impl LoadedScheme<ZigenConfusable> {
                             || (!options.prioritize_trad
                                 && group.classify == ZigenClass::Traditional)
                     })
-                    .filter(|group| {
-                        if let Some(limit_keys) = &options.limit_keys {
-                            limit_keys
-                                .contains(&group.code.chars().nth(0).unwrap().to_ascii_uppercase())
-                        } else {
-                            true
-                        }
-                    })
+                    .filter(|group| options.allows_group(group))
                     .cloned()
                     .collect::<Vec<_>>(),
                 description: cat_desc.to_owned(),
@@ -201,14 +557,7 @@ impl LoadedScheme<ZigenConfusable> {
                 groups: cat
                     .iter()
                     .filter(|group| group.classify == ZigenClass::Uncommon)
-                    .filter(|group| {
-                        if let Some(limit_keys) = &options.limit_keys {
-                            limit_keys
-                                .contains(&group.code.chars().nth(0).unwrap().to_ascii_uppercase())
-                        } else {
-                            true
-                        }
-                    })
+                    .filter(|group| options.allows_group(group))
                     .cloned()
                     .collect::<Vec<_>>(),
                 description: cat_desc.to_owned(),
@@ -229,17 +578,29 @@ impl LoadedScheme<ZigenConfusable> {
                         let ZigenGroup {
                             zigens,
                             code,
+                            alt_codes,
                             classify,
                             description,
+                            examples,
+                            tags,
+                            id,
                         } = group;
 
-                        zigens.into_iter().map(move |zigen| {
+                        zigens.into_iter().enumerate().map(move |(n, zigen)| {
                             SchemeZigen::Cluster(ZigenCluster {
                                 groups: vec![ZigenGroup {
                                     zigens: vec![zigen],
                                     code: code.clone(),
+                                    alt_codes: alt_codes.clone(),
                                     classify: classify.clone(),
                                     description: description.clone(),
+                                    examples: examples.clone(),
+                                    tags: tags.clone(),
+                                    id: if n == 0 {
+                                        id.clone()
+                                    } else {
+                                        format!("{id}/{n}")
+                                    },
                                 }],
                                 description: String::new(),
                             })
@@ -266,11 +627,13 @@ impl LoadedScheme<ZigenConfusable> {
             commons.shuffle(&mut rand::rng());
             outliers.shuffle(&mut rand::rng());
             uncommons.shuffle(&mut rand::rng());
+            decompositions.shuffle(&mut rand::rng());
         }
 
         commons.extend_from_slice(&outliers);
         commons.extend_from_slice(&uncommons);
         commons.extend_from_slice(&confusables);
+        commons.extend_from_slice(&decompositions);
         self.0 = commons;
     }
 }
@@ -286,6 +649,10 @@ pub enum SchemeZigen<Z = ZigenConfusable> {
     /// 容易被混淆或记错的几个字根。
     #[serde(rename = "混")]
     Confusable(Z),
+
+    /// 拆分练习：把一个汉字拆为字根，并依次输入各字根的编码。
+    #[serde(rename = "拆")]
+    Decomposition(ZigenDecomposition),
 }
 
 impl<Z> Default for SchemeZigen<Z> {
@@ -303,31 +670,87 @@ impl SchemeZigen<ZigenConfusable> {
             SchemeZigen::Cluster(cat) => (&cat.groups, &cat.description),
 
             SchemeZigen::Confusable(con) => (&con.groups, &con.description),
+
+            SchemeZigen::Decomposition(dec) => (&dec.groups, &dec.description),
         };
 
         (zigen_groups, description)
     }
 
+    /// 卡片的ID：卡片类型加上各归并组的ID（见 ZigenGroup::id，已排序）。拆分练习的卡片则以被拆的字为ID。
+    ///
+    /// 练习进度只保存卡片的ID，卡片内容则在加载字根集后再根据ID找回，因此ID必须在
+    /// 字根集更新后保持不变。归并组的顺序会被打乱，所以归并组的ID需要排序。尚未填入ID的
+    /// 归并组（比如旧版进度保存的卡片内容）以代表性字根代替。
+    pub fn card_id(&self) -> String {
+        let kind = match self {
+            SchemeZigen::Cluster(_) => "类",
+            SchemeZigen::Confusable(_) => "混",
+            SchemeZigen::Decomposition(dec) => return format!("拆:{}", dec.character),
+        };
+
+        let mut ids = self
+            .as_raw_parts()
+            .0
+            .iter()
+            .filter_map(|group| {
+                if group.id.is_empty() {
+                    group.zigens.first().map(|zigen| zigen.0.as_str())
+                } else {
+                    Some(group.id.as_str())
+                }
+            })
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+
+        format!("{kind}:{}", ids.join(","))
+    }
+
     pub fn as_raw_parts_mut(&mut self) -> (&mut Vec<ZigenGroup>, &mut String) {
         let (zigen_groups, description) = match self {
             SchemeZigen::Cluster(cat) => (&mut cat.groups, &mut cat.description),
 
             SchemeZigen::Confusable(con) => (&mut con.groups, &mut con.description),
+
+            SchemeZigen::Decomposition(dec) => (&mut dec.groups, &mut dec.description),
         };
 
         (zigen_groups, description)
     }
 }
 
+/// 为一组卡片分配ID（见 SchemeZigen::card_id）。
+///
+/// 归并组的ID在字根集内是唯一的，因此只有内容重复的混淆集或拆分练习才会得到相同的ID。
+/// 这些卡片按出现的次序，第二张起附上「~序号」。
+pub fn card_ids(cards: &[SchemeZigen]) -> Vec<String> {
+    let mut counts = HashMap::<String, usize>::new();
+
+    cards
+        .iter()
+        .map(|card| {
+            let id = card.card_id();
+            let count = counts.entry(id.clone()).or_default();
+            *count += 1;
+
+            if *count == 1 {
+                id
+            } else {
+                format!("{id}~{count}")
+            }
+        })
+        .collect()
+}
+
 /// 容易被混淆或记错的几个字根。
 ///
 /// 这些字根未必属于同一个聚类，但是可能因为发音、外形、字源相似等因素，经常被
 /// 学习者搞混。练习器会特意加强这类字根的学习强度。
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ZigenConfusable {
-    groups: Vec<ZigenGroup>,
+    pub(crate) groups: Vec<ZigenGroup>,
     #[serde(default)]
-    description: String,
+    pub(crate) description: String,
 }
 
 /// 容易被混淆或记错的几个字根。
@@ -336,9 +759,26 @@ pub struct ZigenConfusable {
 /// 学习者搞混。练习器会特意加强这类字根的学习强度。
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ZigenConfusableUnpopulated {
-    zigens: Vec<Zigen>,
+    pub(crate) zigens: Vec<Zigen>,
+    #[serde(default)]
+    pub(crate) description: String,
+}
+
+/// 拆分练习：一个汉字，及其按书写顺序排列的字根。
+///
+/// 练习时只显示该字，用户须依次输入各字根的编码；提示则会显示拆分。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ZigenDecomposition {
+    /// 被拆的字，必须是单个字符。
+    #[serde(rename = "char")]
+    pub character: String,
+    /// 按顺序排列的字根。字根可以是归并组内的任何字根，不必是代表性字根。
+    pub split: Vec<Zigen>,
     #[serde(default)]
-    description: String,
+    pub description: String,
+    /// 各字根所在的归并组（只保留该字根本身），与 split 一一对应。由 populate_confusables 填入。
+    #[serde(skip)]
+    pub(crate) groups: Vec<ZigenGroup>,
 }
 
 /// 属于同一聚类的字根。这些字根的编码不一定相同（比如：宇浩星陈码中的Jm目和Jr日）。
@@ -359,8 +799,55 @@ pub struct ZigenCluster {
 pub struct ZigenGroup {
     pub zigens: Vec<Zigen>,
     pub code: String,
+    /// 其他同样可以接受的编码，比如在简繁不同语境下的变体编码。长度必须与 code 一致。
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alt_codes: Vec<String>,
     pub classify: ZigenClass,
     pub description: String,
+    /// 含有该归并组字根的例字，通常两三个即可。
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub examples: Vec<ZigenExample>,
+    /// 自定义标签（可选），比如「笔画」或「v3新增」。用户可在练习设置中按标签筛选字根。
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// 归并组在字根集内的ID，用于组成卡片ID（见 SchemeZigen::card_id），由 populate_confusables 填入。
+    ///
+    /// 即代表性字根；若有数个归并组以同一字根为代表，则按在字根集内出现的次序，
+    /// 第二个起附上「#序号」。不拆分归并组练习时，组内第 n 个字根（由 0 起算）另附上「/n」。
+    #[serde(skip)]
+    pub(crate) id: String,
+}
+
+/// 例字：一个含有某字根的汉字。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ZigenExample {
+    /// 例字本身，必须是单个字符。
+    #[serde(rename = "char")]
+    pub character: String,
+    /// 例字的拆分（可选）。若提供，练习器会以字根字体显示拆分，并突出显示归并组内的字根。
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub split: Vec<String>,
+}
+
+impl ZigenGroup {
+    /// 所有可以接受的编码，主编码在前。
+    pub fn codes(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.code.as_str()).chain(self.alt_codes.iter().map(String::as_str))
+    }
+
+    /// 所有可以接受的编码，已转换为作答时的形式（见 KeySet::normalize_code），主编码在前。
+    pub fn accepted_codes(&self, keyboard: &KeySet) -> Vec<String> {
+        self.codes()
+            .map(|code| keyboard.normalize_code(code))
+            .collect()
+    }
+
+    /// 作答是否为该组可接受的编码之一。比较时不区分大小写，按键别名等同于对应的按键。
+    pub fn accepts(&self, keyboard: &KeySet, answer: &str) -> bool {
+        let answer = keyboard.normalize_code(answer);
+        self.codes()
+            .any(|code| keyboard.normalize_code(code) == answer)
+    }
 }
 
 /// 单个字根。
@@ -368,6 +855,18 @@ pub struct ZigenGroup {
 #[serde(transparent)]
 pub struct Zigen(pub String);
 
+impl Zigen {
+    /// 字根是否含有私用区字符。这类字根只能以方案的字根字体显示。
+    pub fn is_pua(&self) -> bool {
+        self.0.chars().any(is_pua)
+    }
+}
+
+/// 字符是否位于 Unicode 私用区（含补充私用区）。
+pub fn is_pua(c: char) -> bool {
+    matches!(c as u32, 0xE000..=0xF8FF | 0xF0000..=0xFFFFD | 0x100000..=0x10FFFD)
+}
+
 impl Deref for Zigen {
     type Target = str;
 
@@ -395,3 +894,15 @@ pub enum ZigenClass {
     #[serde(rename = "罕")]
     Uncommon,
 }
+
+impl ZigenClass {
+    /// 分类在字根集中的写法，比如「通」。
+    pub fn label(&self) -> &'static str {
+        match self {
+            ZigenClass::Common => "通",
+            ZigenClass::Simplified => "简",
+            ZigenClass::Traditional => "繁",
+            ZigenClass::Uncommon => "罕",
+        }
+    }
+}
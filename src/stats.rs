@@ -0,0 +1,193 @@
+//! 字根集的统计数据：各键的字根数、聚类大小、分类与编码长度的分布等。
+//!
+//! 练习器的方案详情页与 zigen-stats 命令行工具都以 sections 生成的表格显示统计数据，
+//! 传入多个方案时各占一列，便于并排比较。
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::scheme::{LoadedScheme, SchemeZigen, ZigenClass, ZigenConfusableUnpopulated};
+
+/// 统计时依次列出的字根分类。
+const CLASSES: [ZigenClass; 4] = [
+    ZigenClass::Common,
+    ZigenClass::Simplified,
+    ZigenClass::Traditional,
+    ZigenClass::Uncommon,
+];
+
+/// 一个字根集的统计数据。
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SchemeStats {
+    /// 聚类数。
+    pub clusters: usize,
+    /// 归并组数。
+    pub groups: usize,
+    /// 字根数（归并组内的所有字根）。
+    pub roots: usize,
+    /// 各键的字根数，以主编码的首个按键计算。字母按小写计。
+    pub roots_per_key: BTreeMap<char, usize>,
+    /// 聚类大小的分布：含有若干个归并组的聚类数。
+    pub groups_per_cluster: BTreeMap<usize, usize>,
+    /// 各分类的归并组数，顺序同 CLASSES（通、简、繁、罕）。
+    pub classes: [usize; 4],
+    /// 主编码长度的分布：编码长度为若干的归并组数。
+    pub code_lengths: BTreeMap<usize, usize>,
+    /// 混淆集数。
+    pub confusables: usize,
+    /// 拆分练习的字数。
+    pub decompositions: usize,
+    /// 含有私用区字符的字根数。这些字根须以方案的字根字体才能显示。
+    pub pua_roots: usize,
+}
+
+impl SchemeStats {
+    pub fn compute(scheme: &LoadedScheme<ZigenConfusableUnpopulated>) -> Self {
+        let mut stats = Self::default();
+
+        for entry in scheme.0.iter() {
+            match entry {
+                SchemeZigen::Cluster(cluster) => {
+                    stats.clusters += 1;
+                    *stats
+                        .groups_per_cluster
+                        .entry(cluster.groups.len())
+                        .or_default() += 1;
+                }
+                SchemeZigen::Confusable(_) => stats.confusables += 1,
+                SchemeZigen::Decomposition(_) => stats.decompositions += 1,
+            }
+        }
+
+        for group in scheme.groups() {
+            stats.groups += 1;
+            stats.roots += group.zigens.len();
+            stats.pua_roots += group.zigens.iter().filter(|z| z.is_pua()).count();
+
+            if let Some(key) = group.code.chars().next() {
+                *stats
+                    .roots_per_key
+                    .entry(key.to_ascii_lowercase())
+                    .or_default() += group.zigens.len();
+            }
+
+            let class = CLASSES.iter().position(|c| *c == group.classify).unwrap();
+            stats.classes[class] += 1;
+
+            *stats
+                .code_lengths
+                .entry(group.code.chars().count())
+                .or_default() += 1;
+        }
+
+        stats
+    }
+
+    /// 私用区字根占所有字根的比例。
+    pub fn pua_share(&self) -> f64 {
+        ratio(self.pua_roots, self.roots)
+    }
+}
+
+fn ratio(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64
+    }
+}
+
+/// 数量及其所占百分比，比如「120（45.0%）」。
+fn with_share(part: usize, total: usize) -> String {
+    format!("{part}（{:.1}%）", ratio(part, total) * 100.0)
+}
+
+/// 统计表格的一节。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Section {
+    pub title: &'static str,
+    /// (项目, 各方案的数值)
+    pub rows: Vec<(String, Vec<String>)>,
+}
+
+impl Section {
+    fn new<K: Ord + Copy>(
+        title: &'static str,
+        stats: &[&SchemeStats],
+        map: impl Fn(&SchemeStats) -> &BTreeMap<K, usize>,
+        label: impl Fn(K) -> String,
+        total: impl Fn(&SchemeStats) -> usize,
+    ) -> Self {
+        let keys = stats
+            .iter()
+            .flat_map(|s| map(s).keys().copied())
+            .collect::<BTreeSet<_>>();
+
+        let rows = keys
+            .into_iter()
+            .map(|key| {
+                let values = stats
+                    .iter()
+                    .map(|s| with_share(map(s).get(&key).copied().unwrap_or(0), total(s)))
+                    .collect();
+                (label(key), values)
+            })
+            .collect();
+
+        Self { title, rows }
+    }
+}
+
+/// 把一个或多个方案的统计数据整理为表格。各方案的数值按传入顺序排列；
+/// 某方案没有的项目（比如某键没有字根）记为零。
+pub fn sections(stats: &[&SchemeStats]) -> Vec<Section> {
+    let row = |label: &str, value: &dyn Fn(&SchemeStats) -> String| {
+        (label.to_owned(), stats.iter().map(|s| value(s)).collect())
+    };
+
+    let overview = Section {
+        title: "总览",
+        rows: vec![
+            row("聚类", &|s| s.clusters.to_string()),
+            row("归并组", &|s| s.groups.to_string()),
+            row("字根", &|s| s.roots.to_string()),
+            row("混淆集", &|s| s.confusables.to_string()),
+            row("拆分", &|s| s.decompositions.to_string()),
+            row("私用区字根", &|s| with_share(s.pua_roots, s.roots)),
+        ],
+    };
+
+    let classes = Section {
+        title: "分类（归并组数）",
+        rows: CLASSES
+            .iter()
+            .enumerate()
+            .map(|(i, class)| row(class.label(), &|s| with_share(s.classes[i], s.groups)))
+            .collect(),
+    };
+
+    vec![
+        overview,
+        classes,
+        Section::new(
+            "编码长度（归并组数）",
+            stats,
+            |s| &s.code_lengths,
+            |len| format!("{len}码"),
+            |s| s.groups,
+        ),
+        Section::new(
+            "聚类大小（聚类数）",
+            stats,
+            |s| &s.groups_per_cluster,
+            |len| format!("{len}组"),
+            |s| s.clusters,
+        ),
+        Section::new(
+            "各键字根数",
+            stats,
+            |s| &s.roots_per_key,
+            |key| key.to_string(),
+            |s| s.roots,
+        ),
+    ]
+}
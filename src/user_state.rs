@@ -1,20 +1,30 @@
 use std::collections::BTreeMap;
 
-use chrono::{DateTime, Utc};
 use dioxus_logger::tracing;
 use gloo_storage::{LocalStorage, Storage};
 use serde::{Deserialize, Serialize};
 
-use crate::scheduler::{
-    Rating, ScheduleParamsAdept, ScheduleParamsNovice, Scheduler, SchedulerCard, ZigenCard,
-};
-use crate::scheduler_v2::{SchedulerV2, SchedulerV2Card};
-use crate::scheme::{LoadedScheme, SchemeOptions, SchemeZigen, ZigenConfusableUnpopulated};
+use zigen_trainer::progress::TrainProgress;
+
+use crate::scheme::{LoadedScheme, Scheme, SchemeOptions, ZigenConfusableUnpopulated};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct UserState {
     current_scheme: String,
     progresses: BTreeMap<String, TrainProgress>,
+    /// 用户从本地文件载入的方案，以方案ID为键。
+    user_schemes: BTreeMap<String, UserScheme>,
+    /// 用户添加的第三方方案列表的地址。
+    catalogs: Vec<String>,
+    /// 用户自定义的标签：方案ID -> 代表性字根 -> 标签。
+    user_tags: BTreeMap<String, BTreeMap<String, Vec<String>>>,
+}
+
+/// 用户从本地文件载入的方案。字根集已经展开，字体（如有）以 data URL 的形式保存在 scheme.zigen_font 中。
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UserScheme {
+    pub scheme: Scheme,
+    pub zigen: LoadedScheme<ZigenConfusableUnpopulated>,
 }
 
 impl UserState {
@@ -24,9 +34,21 @@ impl UserState {
         let progresses =
             LocalStorage::get::<BTreeMap<String, TrainProgress>>("progresses").unwrap_or_default();
 
+        let user_schemes =
+            LocalStorage::get::<BTreeMap<String, UserScheme>>("userSchemes").unwrap_or_default();
+
+        let catalogs = LocalStorage::get::<Vec<String>>("catalogs").unwrap_or_default();
+
+        let user_tags =
+            LocalStorage::get::<BTreeMap<String, BTreeMap<String, Vec<String>>>>("userTags")
+                .unwrap_or_default();
+
         Self {
             current_scheme,
             progresses,
+            user_schemes,
+            catalogs,
+            user_tags,
         }
     }
 
@@ -45,166 +67,232 @@ impl UserState {
             .inspect_err(|e| tracing::error!("unable to write to localStorage due to {e}"));
     }
 
-    pub fn try_initialize_scheme(
-        &mut self,
-        scheme_id: &str,
-        scheme: &LoadedScheme<ZigenConfusableUnpopulated>,
-        options: SchemeOptions,
-    ) -> Result<(), String> {
-        self.current_scheme = scheme_id.to_owned();
+    /// 所有用户方案的方案信息。
+    pub fn user_schemes(&self) -> impl Iterator<Item = &Scheme> {
+        self.user_schemes
+            .values()
+            .map(|user_scheme| &user_scheme.scheme)
+    }
 
-        if !self.progresses.contains_key(scheme_id) {
-            let mut scheme = scheme.clone().populate_confusables()?;
-            scheme.sort_to_options(&options);
-            tracing::debug!("{:?}", &scheme);
+    /// 若 scheme_id 为用户方案，返回其字根集。
+    pub fn user_scheme_zigen(
+        &self,
+        scheme_id: &str,
+    ) -> Option<&LoadedScheme<ZigenConfusableUnpopulated>> {
+        self.user_schemes
+            .get(scheme_id)
+            .map(|user_scheme| &user_scheme.zigen)
+    }
 
-            if scheme.0.is_empty() {
-                return Err(String::from("无练习卡片！"));
-            }
+    /// 登记一个用户方案并立即保存。字根集须能通过 populate_confusables 的检查。
+    ///
+    /// 若已有同一ID的方案，则以新的字根集取而代之，原有的进度会在下次练习时跟随新的字根集更新。
+    pub fn add_user_scheme(
+        &mut self,
+        scheme: Scheme,
+        zigen: LoadedScheme<ZigenConfusableUnpopulated>,
+    ) -> Result<(), String> {
+        zigen.clone().populate_confusables()?;
+
+        let previous = self.user_schemes.insert(
+            scheme.id.clone(),
+            UserScheme {
+                scheme: scheme.clone(),
+                zigen,
+            },
+        );
+
+        if let Err(err) = self.write_user_schemes() {
+            match previous {
+                Some(previous) => self.user_schemes.insert(scheme.id.clone(), previous),
+                None => self.user_schemes.remove(&scheme.id),
+            };
+            return Err(err);
+        }
 
-            self.progresses.insert(
-                scheme_id.to_owned(),
-                TrainProgress::new(scheme.0, options.adept, options.v2_sched),
-            );
+        if let Some(progress) = self.progresses.get_mut(&scheme.id) {
+            progress.unresolve();
         }
 
         Ok(())
     }
 
-    pub fn current_scheme(&self) -> &str {
-        &self.current_scheme
+    /// 删除一个用户方案及其进度，并立即保存。
+    pub fn remove_user_scheme(&mut self, scheme_id: &str) {
+        self.user_schemes.remove(scheme_id);
+        self.progresses.remove(scheme_id);
+
+        let _ = self
+            .write_user_schemes()
+            .inspect_err(|e| tracing::error!("{e}"));
+        self.write_to_local_storage();
     }
 
-    pub fn has_progress(&self, scheme_name: &str) -> bool {
-        self.progresses.contains_key(scheme_name)
+    /// 用户方案可能包含整个字体文件，因此只在有所改变时才保存。
+    fn write_user_schemes(&self) -> Result<(), String> {
+        LocalStorage::set("userSchemes", &self.user_schemes)
+            .map_err(|e| format!("无法保存方案，浏览器的存储空间可能已满：{e}"))
     }
 
-    pub fn reset_progress(&mut self, scheme_name: &str) {
-        if self.progresses.contains_key(scheme_name) {
-            self.progresses.remove(scheme_name);
+    /// 用户添加的第三方方案列表。
+    pub fn catalogs(&self) -> &[String] {
+        &self.catalogs
+    }
+
+    /// 添加一个第三方方案列表并立即保存。
+    pub fn add_catalog(&mut self, url: &str) -> Result<(), String> {
+        let url = url.trim();
+
+        if !url.contains("://") {
+            return Err(String::from(
+                "请输入完整的URL，例如 https://example.com/schemes.json",
+            ));
+        }
+
+        if self.catalogs.iter().any(|catalog| catalog == url) {
+            return Err(String::from("该方案列表已经添加过了"));
         }
+
+        self.catalogs.push(url.to_owned());
+        self.write_catalogs();
+        Ok(())
     }
 
-    pub fn current_progress(&self) -> &TrainProgress {
-        &self.progresses[&self.current_scheme]
+    /// 删除一个第三方方案列表并立即保存。该方案列表中的方案的进度会被保留。
+    pub fn remove_catalog(&mut self, url: &str) {
+        self.catalogs.retain(|catalog| catalog != url);
+        self.write_catalogs();
     }
 
-    pub fn current_progress_mut(&mut self) -> &mut TrainProgress {
-        self.progresses.get_mut(&self.current_scheme).unwrap()
+    fn write_catalogs(&self) {
+        let _ = LocalStorage::set("catalogs", &self.catalogs)
+            .inspect_err(|e| tracing::error!("unable to write to localStorage due to {e}"));
     }
 
-    pub fn reset_current_progress(&mut self) {
-        if self.progresses.contains_key(&self.current_scheme) {
-            self.progresses.remove(&self.current_scheme);
-        }
+    /// 用户为某个方案添加的标签，以代表性字根为键。
+    pub fn user_tags(&self, scheme_id: &str) -> BTreeMap<String, Vec<String>> {
+        self.user_tags.get(scheme_id).cloned().unwrap_or_default()
     }
-}
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct TrainProgress {
-    start_time: DateTime<Utc>,
-    scheduler: UsedScheduler,
-}
+    /// 为代表性字根为 root 的归并组添加一个标签，并立即保存。
+    pub fn add_user_tag(&mut self, scheme_id: &str, root: &str, tag: &str) -> Result<(), String> {
+        let tag = tag.trim();
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-enum UsedScheduler {
-    Novice(Scheduler<ScheduleParamsNovice>),
-    Adept(Scheduler<ScheduleParamsAdept>),
-    V2(SchedulerV2),
-}
+        if tag.is_empty() {
+            return Err(String::from("标签不能为空"));
+        }
 
-impl TrainProgress {
-    pub fn new(zigens: Vec<SchemeZigen>, adept: bool, v2: bool) -> Self {
-        if v2 {
-            let pending_cards = zigens
-                .into_iter()
-                .map(|zigen| {
-                    let mut card = SchedulerV2Card::default();
-                    *card.zigen_mut() = zigen.clone();
-                    card
-                })
-                .collect::<Vec<SchedulerV2Card>>();
-
-            if pending_cards.is_empty() {
-                tracing::error!("pending_cards 不能为空！");
-                panic!("pending_cards is empty");
-            }
+        let tags = self
+            .user_tags
+            .entry(scheme_id.to_owned())
+            .or_default()
+            .entry(root.to_owned())
+            .or_default();
 
-            Self {
-                start_time: Utc::now(),
-                scheduler: UsedScheduler::V2(SchedulerV2::new(pending_cards, adept)),
-            }
-        } else {
-            let pending_cards = zigens
-                .into_iter()
-                .map(|zigen| {
-                    let mut card = SchedulerCard::default();
-                    *card.zigen_mut() = zigen.clone();
-                    card
-                })
-                .collect::<Vec<SchedulerCard>>();
-
-            if pending_cards.is_empty() {
-                tracing::error!("pending_cards 不能为空！");
-                panic!("pending_cards is empty");
-            }
+        if tags.iter().any(|existing| existing == tag) {
+            return Err(format!("已经有「{tag}」标签了"));
+        }
 
-            Self {
-                start_time: Utc::now(),
-                scheduler: if adept {
-                    UsedScheduler::Adept(Scheduler::new(pending_cards))
-                } else {
-                    UsedScheduler::Novice(Scheduler::new(pending_cards))
-                },
+        tags.push(tag.to_owned());
+        self.user_tags_changed(scheme_id);
+        Ok(())
+    }
+
+    /// 删除代表性字根为 root 的归并组的一个标签，并立即保存。
+    pub fn remove_user_tag(&mut self, scheme_id: &str, root: &str, tag: &str) {
+        let Some(scheme_tags) = self.user_tags.get_mut(scheme_id) else {
+            return;
+        };
+
+        if let Some(tags) = scheme_tags.get_mut(root) {
+            tags.retain(|existing| existing != tag);
+            if tags.is_empty() {
+                scheme_tags.remove(root);
             }
         }
-    }
 
-    pub fn is_adept(&self) -> bool {
-        match &self.scheduler {
-            UsedScheduler::Novice(scheduler) => scheduler.is_adept(),
-            UsedScheduler::Adept(scheduler) => scheduler.is_adept(),
-            UsedScheduler::V2(scheduler) => scheduler.is_adept(),
+        if scheme_tags.is_empty() {
+            self.user_tags.remove(scheme_id);
         }
+
+        self.user_tags_changed(scheme_id);
     }
 
-    pub fn rate_card(&mut self, rating: Rating) {
-        match &mut self.scheduler {
-            UsedScheduler::Novice(scheduler) => scheduler.rate_card(rating),
-            UsedScheduler::Adept(scheduler) => scheduler.rate_card(rating),
-            UsedScheduler::V2(scheduler) => scheduler.rate_card(rating),
+    /// 保存标签。按标签筛选的进度须根据新的标签重新找回卡片，以便增删卡片。
+    fn user_tags_changed(&mut self, scheme_id: &str) {
+        let _ = LocalStorage::set("userTags", &self.user_tags)
+            .inspect_err(|e| tracing::error!("unable to write to localStorage due to {e}"));
+
+        if let Some(progress) = self.progresses.get_mut(scheme_id) {
+            progress.unresolve();
         }
     }
 
-    pub fn get_card(&mut self) -> Box<dyn ZigenCard> {
-        match &mut self.scheduler {
-            UsedScheduler::Novice(scheduler) => Box::new(scheduler.get_card().clone()),
-            UsedScheduler::Adept(scheduler) => Box::new(scheduler.get_card().clone()),
-            UsedScheduler::V2(scheduler) => Box::new(scheduler.get_card().clone()),
+    pub fn try_initialize_scheme(
+        &mut self,
+        meta: &Scheme,
+        scheme: &LoadedScheme<ZigenConfusableUnpopulated>,
+        options: SchemeOptions,
+    ) -> Result<(), String> {
+        let scheme_id = meta.id.as_str();
+        self.current_scheme = scheme_id.to_owned();
+
+        match self.progresses.get_mut(scheme_id) {
+            // 每次启动练习器后，只需根据字根集找回一次卡片内容
+            Some(progress) if progress.is_resolved() => Ok(()),
+            Some(progress) => progress.resolve(scheme, options, meta),
+
+            None => {
+                let mut cards = scheme.clone().populate_confusables()?;
+                cards.sort_to_options(&options);
+                tracing::debug!("{:?}", &cards);
+
+                if cards.0.is_empty() {
+                    return Err(String::from("无练习卡片！"));
+                }
+
+                let mut progress = TrainProgress::new(cards.0, options);
+                progress.set_scheme_version(meta.version.clone());
+                self.progresses.insert(scheme_id.to_owned(), progress);
+                Ok(())
+            }
         }
     }
 
-    pub fn get_card_mut(&mut self) -> &mut dyn ZigenCard {
-        match &mut self.scheduler {
-            UsedScheduler::Novice(scheduler) => scheduler.get_card(),
-            UsedScheduler::Adept(scheduler) => scheduler.get_card(),
-            UsedScheduler::V2(scheduler) => scheduler.get_card(),
-        }
+    pub fn current_scheme(&self) -> &str {
+        &self.current_scheme
+    }
+
+    /// 用户开始练习该方案后，方案的字根集是否更新过。未记录版本的进度不算在内。
+    pub fn is_progress_outdated(&self, scheme: &Scheme) -> bool {
+        self.progresses.get(&scheme.id).is_some_and(|progress| {
+            !progress.scheme_version().is_empty()
+                && !scheme.version.is_empty()
+                && progress.scheme_version() != scheme.version
+        })
     }
 
-    pub fn reviewed_cards(&self) -> usize {
-        match &self.scheduler {
-            UsedScheduler::Novice(scheduler) => scheduler.reviewed_cards(),
-            UsedScheduler::Adept(scheduler) => scheduler.reviewed_cards(),
-            UsedScheduler::V2(scheduler) => scheduler.reviewed_cards(),
+    pub fn has_progress(&self, scheme_name: &str) -> bool {
+        self.progresses.contains_key(scheme_name)
+    }
+
+    pub fn reset_progress(&mut self, scheme_name: &str) {
+        if self.progresses.contains_key(scheme_name) {
+            self.progresses.remove(scheme_name);
         }
     }
 
-    pub fn total_cards(&self) -> usize {
-        match &self.scheduler {
-            UsedScheduler::Novice(scheduler) => scheduler.total_cards(),
-            UsedScheduler::Adept(scheduler) => scheduler.total_cards(),
-            UsedScheduler::V2(scheduler) => scheduler.total_cards(),
+    pub fn current_progress(&self) -> &TrainProgress {
+        &self.progresses[&self.current_scheme]
+    }
+
+    pub fn current_progress_mut(&mut self) -> &mut TrainProgress {
+        self.progresses.get_mut(&self.current_scheme).unwrap()
+    }
+
+    pub fn reset_current_progress(&mut self) {
+        if self.progresses.contains_key(&self.current_scheme) {
+            self.progresses.remove(&self.current_scheme);
         }
     }
 }
@@ -3,26 +3,43 @@ use std::rc::Rc;
 
 use chrono::{DateTime, Utc};
 use dioxus::prelude::*;
+use dioxus::web::WebEventExt;
 use dioxus_logger::tracing;
+use web_sys::wasm_bindgen::JsCast;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
 // use dioxus_sdk::utils::timing::use_debounce;
 
-use crate::scheduler::{Rating, ZigenCard};
+use crate::component::{QwertyKeyboard, RichText};
+use crate::scheme::{CodeStructure, KeySet, SchemeZigen, ZigenDecomposition, ZigenGroup};
+use zigen_trainer::font;
+use zigen_trainer::progress::CurrentCard;
+use zigen_trainer::scheduler::{Mistake, Rating, find_mistake};
 
 #[derive(PartialEq, Clone, Props)]
 pub struct CardProps {
-    zigens: ReadSignal<Box<dyn ZigenCard>>,
+    zigens: ReadSignal<CurrentCard>,
     adept: bool,
-    on_card_completed: EventHandler<Rating>,
+    /// 方案的编码结构，用于分开显示与判断大码、小码
+    code_structure: Option<CodeStructure>,
+    /// 方案的按键定义
+    keyboard: KeySet,
+    /// 作答前便显示例字
+    examples_as_prompt: bool,
+    /// 参数：评分、作答耗时（秒）
+    on_card_completed: EventHandler<(Rating, f64)>,
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn handle_input(
     input_boxes: &mut Memo<Vec<Vec<char>>>,
     mut asked_hint: Memo<bool>,
     mut is_wrong: Signal<bool>,
+    mut mistake: Signal<Option<Mistake>>,
     confusable: bool,
-    expected_answer: &String,
+    accepted_answers: &[Vec<String>],
+    code_structure: Option<&CodeStructure>,
     start_time: Rc<RefCell<DateTime<Utc>>>,
-    on_card_completed: EventHandler<Rating>,
+    on_card_completed: EventHandler<(Rating, f64)>,
 ) {
     let filled_up = input_boxes
         .read()
@@ -33,13 +50,26 @@ async fn handle_input(
         .is_none(); // 当 pos 为 None，证明已无空白输入格。
 
     if filled_up {
-        let user_answer = input_boxes.read().iter().flatten().collect::<String>();
+        // 每个归并组的作答都必须是该组可接受的编码之一
+        let answers = input_boxes
+            .read()
+            .iter()
+            .map(|box_group| box_group.iter().collect::<String>())
+            .collect::<Vec<_>>();
+        let new_mistake = find_mistake(&answers, accepted_answers, code_structure);
+        let is_correct = new_mistake.is_none();
+
+        let answer_len = accepted_answers
+            .iter()
+            .filter_map(|codes| codes.first())
+            .map(|code| code.len())
+            .sum::<usize>();
 
         // 根据用户作答耗时判断该字根的难度，以秒为单位。
         let easy_time = if !confusable {
-            2.0 + (expected_answer.len() as f64) * 0.3
+            2.0 + (answer_len as f64) * 0.3
         } else {
-            2.0 + (expected_answer.len() as f64) * 0.2
+            2.0 + (answer_len as f64) * 0.2
         };
 
         let time_diff = (Utc::now() - *start_time.borrow()).as_seconds_f64();
@@ -47,23 +77,26 @@ async fn handle_input(
         clear_input(input_boxes);
         *start_time.borrow_mut() = Utc::now();
 
-        if user_answer == expected_answer.as_str() {
+        if is_correct {
             if !asked_hint() {
                 if time_diff <= easy_time {
-                    on_card_completed.call(Rating::Easy)
+                    on_card_completed.call((Rating::Easy, time_diff))
                 } else if time_diff <= easy_time + 2.0 {
-                    on_card_completed.call(Rating::Good)
+                    on_card_completed.call((Rating::Good, time_diff))
                 } else {
-                    on_card_completed.call(Rating::Hard)
+                    on_card_completed.call((Rating::Hard, time_diff))
                 }
             } else {
+                let rating = mistake().map_or(Rating::Again, Mistake::rating);
                 asked_hint.set(false);
                 is_wrong.set(false);
-                on_card_completed.call(Rating::Again)
+                mistake.set(None);
+                on_card_completed.call((rating, time_diff))
             }
         } else {
             asked_hint.set(true);
             is_wrong.set(true);
+            mistake.set(mistake().max(new_mistake));
 
             // use_debounce(Duration::from_secs(2), move |_| {
             //     is_wrong.set(false);
@@ -75,15 +108,19 @@ async fn handle_input(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn handle_key_event(
     input_boxes: &mut Memo<Vec<Vec<char>>>,
     event: Event<KeyboardData>,
     mut asked_hint: Memo<bool>,
     is_wrong: Signal<bool>,
+    mut mistake: Signal<Option<Mistake>>,
     confusable: bool,
-    expected_answer: &String,
+    accepted_answers: &[Vec<String>],
+    code_structure: Option<&CodeStructure>,
+    keyboard: &KeySet,
     start_time: Rc<RefCell<DateTime<Utc>>>,
-    on_card_completed: EventHandler<Rating>,
+    on_card_completed: EventHandler<(Rating, f64)>,
 ) {
     event.stop_propagation();
 
@@ -101,8 +138,9 @@ async fn handle_key_event(
 
             if c == ' ' {
                 asked_hint.set(true);
-            } else {
-                receive_input(input_boxes, c);
+                mistake.set(Some(Mistake::Revealed));
+            } else if let Some(key) = keyboard.normalize(c) {
+                receive_input(input_boxes, key);
             }
         }
 
@@ -119,33 +157,43 @@ async fn handle_key_event(
         input_boxes,
         asked_hint,
         is_wrong,
+        mistake,
         confusable,
-        expected_answer,
+        accepted_answers,
+        code_structure,
         start_time,
         on_card_completed,
     )
     .await
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn handle_input_event(
     input_boxes: &mut Memo<Vec<Vec<char>>>,
     box_idx: usize,
     event: Event<FormData>,
     mut asked_hint: Memo<bool>,
     is_wrong: Signal<bool>,
+    mut mistake: Signal<Option<Mistake>>,
     confusable: bool,
-    expected_answer: &String,
+    accepted_answers: &[Vec<String>],
+    code_structure: Option<&CodeStructure>,
+    keyboard: &KeySet,
     start_time: Rc<RefCell<DateTime<Utc>>>,
-    on_card_completed: EventHandler<Rating>,
+    on_card_completed: EventHandler<(Rating, f64)>,
 ) {
     let value = event.value();
 
-    let value = if value.contains(' ') {
+    if value.contains(' ') {
         asked_hint.set(true);
-        value.trim_ascii()
-    } else {
-        value.as_str()
-    };
+        mistake.set(Some(Mistake::Revealed));
+    }
+
+    // 不属于方案的字符一律忽略，别名则转换为对应的按键
+    let value = value
+        .chars()
+        .filter_map(|c| keyboard.normalize(c))
+        .collect::<Vec<_>>();
 
     // 有些手机游览器似乎有bug，在切换输入框时会莫名发送一个重复的事件
     // 因为切换输入框一般发生在用户输入了某组字根的编码后，重复的事件
@@ -155,9 +203,8 @@ async fn handle_input_event(
     let diff = input_boxes.read()[box_idx]
         .iter()
         .take_while(|c| **c != ' ')
-        .take(value.len())
-        .enumerate()
-        .take_while(|(i, c)| **c as u8 == value.as_bytes()[*i])
+        .zip(value.iter())
+        .take_while(|(c, v)| c == v)
         .count();
     if diff.abs_diff(value.len()) > 1 {
         tracing::info!("input too fast, ignoring");
@@ -168,8 +215,8 @@ async fn handle_input_event(
         .iter_mut()
         .enumerate()
         .for_each(|(i, c)| {
-            if let Some(v) = value.chars().nth(i) {
-                *c = v;
+            if let Some(v) = value.get(i) {
+                *c = *v;
             } else {
                 *c = ' ';
             }
@@ -179,8 +226,10 @@ async fn handle_input_event(
         input_boxes,
         asked_hint,
         is_wrong,
+        mistake,
         confusable,
-        expected_answer,
+        accepted_answers,
+        code_structure,
         start_time,
         on_card_completed,
     )
@@ -196,7 +245,7 @@ fn receive_input(input_boxes: &mut Memo<Vec<Vec<char>>>, input: char) {
         .next();
 
     if let Some((i, j)) = pos {
-        input_boxes.write()[i][j] = input.to_ascii_lowercase();
+        input_boxes.write()[i][j] = input;
     }
 }
 
@@ -233,6 +282,238 @@ fn move_focus(box_idx: usize) {
     .send(box_idx);
 }
 
+/// 答错时给出的提示。
+fn mistake_feedback(mistake: Mistake, structured: bool) -> &'static str {
+    match mistake {
+        Mistake::Minor => "大码正确，小码错误",
+        Mistake::Major if structured => "大码错误",
+        Mistake::Major => "编码错误",
+        Mistake::Revealed => "",
+    }
+}
+
+/// 显示归并组的例字。若例字附有拆分，则一并显示拆分，并突出归并组内的字根。
+fn render_examples(group: &ZigenGroup) -> Element {
+    rsx! {
+        div {
+            class: "trainer-zigen-examples",
+
+            for example in group.examples.iter() {
+                span {
+                    key: "{example.character}:{example.split:?}",
+                    class: "trainer-zigen-example",
+
+                    ExampleCharacter {
+                        character: example.character.clone(),
+                        roots: example_roots(group, &example.split),
+                    }
+
+                    if !example.split.is_empty() {
+                        span {
+                            class: "trainer-zigen-example-split",
+
+                            for part in example.split.iter() {
+                                span {
+                                    class: if group.zigens.iter().any(|zigen| zigen.0 == *part) { "highlight" },
+                                    "{part}"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 要在例字中突出显示的字根：拆分中属于该归并组的字根；没有拆分时则逐一尝试组内所有字根。
+fn example_roots(group: &ZigenGroup, split: &[String]) -> Vec<String> {
+    let roots = group.zigens.iter().map(|zigen| zigen.0.clone());
+
+    if split.is_empty() {
+        roots.collect()
+    } else {
+        roots.filter(|root| split.contains(root)).collect()
+    }
+}
+
+/// 例字画布的边长（CSS 像素）。在此大小上寻找字根的位置。
+const EXAMPLE_SIZE: usize = 48;
+
+/// 以画布显示例字，并在字体许可时把字根所在的笔画涂上突出显示的颜色。
+///
+/// 浏览器以卡片的字体（字根字体与系统字体）分别绘出例字与字根，再以 font::locate 找出字根在例字中的
+/// 位置。字体缺少字根的字形，或者字根在例字中变形太多而无法找到时，只显示例字本身。
+#[component]
+fn ExampleCharacter(character: String, roots: Vec<String>) -> Element {
+    rsx! {
+        canvas {
+            class: "trainer-zigen-example-char",
+            aria_label: "{character}",
+            onmounted: move |event: MountedEvent| {
+                let Some(canvas) = event
+                    .try_as_web_event()
+                    .and_then(|element| element.dyn_into::<HtmlCanvasElement>().ok())
+                else {
+                    return;
+                };
+
+                if let Err(err) = draw_example(&canvas, &character, &roots) {
+                    tracing::warn!("unable to draw example {character}: {err}");
+                }
+            },
+        }
+    }
+}
+
+fn context_2d(canvas: &HtmlCanvasElement) -> Result<CanvasRenderingContext2d, String> {
+    canvas
+        .get_context("2d")
+        .ok()
+        .flatten()
+        .and_then(|context| context.dyn_into::<CanvasRenderingContext2d>().ok())
+        .ok_or_else(|| String::from("无法取得画布"))
+}
+
+/// 以 font 在 EXAMPLE_SIZE × EXAMPLE_SIZE 的画布上居中绘出 text，并读回为灰度位图。
+fn rasterize(
+    context: &CanvasRenderingContext2d,
+    font: &str,
+    text: &str,
+) -> Result<font::Bitmap, String> {
+    let size = EXAMPLE_SIZE as f64;
+    context.clear_rect(0.0, 0.0, size, size);
+    context.set_font(font);
+    context.set_text_align("center");
+    context.set_text_baseline("middle");
+    context.set_fill_style_str("black");
+    context
+        .fill_text(text, size / 2.0, size / 2.0)
+        .map_err(|_| String::from("无法绘制文字"))?;
+
+    let data = context
+        .get_image_data(0.0, 0.0, size, size)
+        .map_err(|_| String::from("无法读取画布"))?
+        .data();
+
+    Ok(font::Bitmap {
+        size: EXAMPLE_SIZE,
+        // 只取不透明度
+        pixels: data
+            .iter()
+            .skip(3)
+            .step_by(4)
+            .map(|&a| a as f32 / 255.0)
+            .collect(),
+    })
+}
+
+fn draw_example(
+    canvas: &HtmlCanvasElement,
+    character: &str,
+    roots: &[String],
+) -> Result<(), String> {
+    let window = web_sys::window().ok_or_else(|| String::from("没有 window"))?;
+    let style = window
+        .get_computed_style(canvas)
+        .ok()
+        .flatten()
+        .ok_or_else(|| String::from("无法取得样式"))?;
+    let color = style.get_property_value("color").unwrap_or_default();
+    let highlight = style
+        .get_property_value("--example-highlight-color")
+        .map(|value| value.trim().to_owned())
+        .unwrap_or_default();
+    let font = format!(
+        "{}px {}",
+        EXAMPLE_SIZE * 3 / 4,
+        style.get_property_value("font-family").unwrap_or_default()
+    );
+
+    // 在隐藏的画布上寻找字根，避免受显示用画布的缩放影响
+    let scratch = window
+        .document()
+        .and_then(|document| document.create_element("canvas").ok())
+        .and_then(|element| element.dyn_into::<HtmlCanvasElement>().ok())
+        .ok_or_else(|| String::from("无法建立画布"))?;
+    scratch.set_width(EXAMPLE_SIZE as u32);
+    scratch.set_height(EXAMPLE_SIZE as u32);
+    let scratch = context_2d(&scratch)?;
+
+    let glyph = rasterize(&scratch, &font, character)?;
+    let mut placement: Option<font::Placement> = None;
+    for root in roots {
+        if let Some(found) = font::locate(&glyph, &rasterize(&scratch, &font, root)?)
+            && placement
+                .as_ref()
+                .is_none_or(|best| found.similarity > best.similarity)
+        {
+            placement = Some(found);
+        }
+    }
+
+    // 按装置像素比放大画布，使例字在高分辨率屏幕上仍然清晰
+    let ratio = window.device_pixel_ratio();
+    let size = EXAMPLE_SIZE as f64;
+    canvas.set_width((size * ratio) as u32);
+    canvas.set_height((size * ratio) as u32);
+    let context = context_2d(canvas)?;
+    let _ = context.scale(ratio, ratio);
+
+    context.set_font(&font);
+    context.set_text_align("center");
+    context.set_text_baseline("middle");
+    context.set_fill_style_str(&color);
+    context
+        .fill_text(character, size / 2.0, size / 2.0)
+        .map_err(|_| String::from("无法绘制文字"))?;
+
+    if let Some(placement) = placement {
+        // source-atop 只在已有笔画的地方上色
+        let _ = context.set_global_composite_operation("source-atop");
+        context.set_fill_style_str(&highlight);
+        for (i, _) in placement
+            .mask
+            .pixels
+            .iter()
+            .enumerate()
+            .filter(|&(_, &p)| p > 0.0)
+        {
+            let (x, y) = (i % EXAMPLE_SIZE, i / EXAMPLE_SIZE);
+            context.fill_rect(x as f64, y as f64, 1.0, 1.0);
+        }
+        let _ = context.set_global_composite_operation("source-over");
+    }
+
+    Ok(())
+}
+
+/// 卡片内各归并组主编码的第一个按键。
+fn zigen_groups_first_keys(zigen: &SchemeZigen, keyboard: &KeySet) -> Vec<char> {
+    let (groups, _) = zigen.as_raw_parts();
+
+    groups
+        .iter()
+        .filter_map(|group| group.code.chars().next())
+        .filter_map(|c| keyboard.normalize(c))
+        .collect()
+}
+
+/// 按顺序列出拆分所用的字根。
+fn display_split(decomposition: &ZigenDecomposition) -> String {
+    decomposition
+        .split
+        .iter()
+        .map(|zigen| zigen.0.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 以「主编码 / 备选编码」的形式显示归并组的所有编码。
+fn display_codes(group: &ZigenGroup) -> String {
+    group.codes().collect::<Vec<_>>().join(" / ")
+}
+
 fn clear_input(input_boxes: &mut Memo<Vec<Vec<char>>>) {
     input_boxes
         .write()
@@ -244,14 +525,16 @@ fn clear_input(input_boxes: &mut Memo<Vec<Vec<char>>>) {
 pub fn Card(props: CardProps) -> Element {
     let start_time = use_hook(|| Rc::new(RefCell::new(Utc::now())));
 
-    let asked_hint = use_memo(move || props.zigens.read().is_new_card() && !props.adept);
+    let asked_hint = use_memo(move || props.zigens.read().is_new && !props.adept);
     let is_wrong = use_signal(|| false);
+    let mistake = use_signal(|| None);
+    let major_len = props.code_structure.as_ref().map(|cs| cs.major_len);
 
-    let zigens = &**props.zigens.read();
+    let zigens = &*props.zigens.read();
 
     let mut input_boxes = use_memo(move || {
-        let zigens = &**props.zigens.read();
-        let (zigen_groups, _) = zigens.zigen().as_raw_parts();
+        let zigens = &*props.zigens.read();
+        let (zigen_groups, _) = zigens.zigen.as_raw_parts();
 
         let mut boxes = Vec::with_capacity(zigen_groups.len());
         for group in zigen_groups.iter() {
@@ -261,19 +544,27 @@ pub fn Card(props: CardProps) -> Element {
         boxes
     });
 
-    let expected_answer = use_memo(move || {
-        let zigens = &**props.zigens.read();
-        let (zigen_groups, _) = zigens.zigen().as_raw_parts();
+    let keyboard = props.keyboard.clone();
+    let accepted_answers = use_memo(move || {
+        let zigens = &*props.zigens.read();
+        let (zigen_groups, _) = zigens.zigen.as_raw_parts();
 
         zigen_groups
             .iter()
-            .map(|group| group.code.as_str())
-            .fold(String::new(), |acc, x| acc + x)
-            .to_ascii_lowercase()
+            .map(|group| group.accepted_codes(&keyboard))
+            .collect::<Vec<_>>()
     });
 
-    let (zigen_groups, description) = zigens.zigen().as_raw_parts();
-    let confusable = matches!(zigens.zigen(), crate::scheme::SchemeZigen::Confusable(_));
+    // 显示答案时，在键盘上标出各归并组的键面
+    let answer_keys = zigen_groups_first_keys(&props.zigens.read().zigen, &props.keyboard);
+
+    let (zigen_groups, description) = zigens.zigen.as_raw_parts();
+    let decomposition = match &zigens.zigen {
+        SchemeZigen::Decomposition(dec) => Some(dec),
+        _ => None,
+    };
+    // 拆分练习与混淆集一样需要逐一辨认字根，作答时限按同样的标准计算
+    let confusable = matches!(&zigens.zigen, SchemeZigen::Confusable(_)) || decomposition.is_some();
 
     use_effect(move || {
         let pos = input_boxes
@@ -292,6 +583,8 @@ pub fn Card(props: CardProps) -> Element {
 
     let start_time0 = start_time.clone();
     let start_time1 = start_time.clone();
+    let keyboard0 = props.keyboard.clone();
+    let keyboard1 = props.keyboard.clone();
 
     rsx! {
         div {
@@ -302,21 +595,36 @@ pub fn Card(props: CardProps) -> Element {
             onclick: move |_event| {},
             onkeydown: move |event| {
                 let start_time0 = start_time0.clone();
+                let keyboard0 = keyboard0.clone();
                 async move {
                     handle_key_event(
                         &mut input_boxes,
                         event,
                         asked_hint,
                         is_wrong,
+                        mistake,
                         confusable,
-                        &expected_answer.read(),
+                        &accepted_answers(),
+                        props.code_structure.as_ref(),
+                        &keyboard0,
                         Rc::clone(&start_time0),
                         props.on_card_completed,
                     ).await
                 }
             },
 
-            if confusable {
+            if let Some(dec) = decomposition {
+                div {
+                    class: "trainer-zigen-confusable",
+
+                    "拆分练习"
+                }
+                div {
+                    class: "trainer-zigen-decomposition",
+
+                    "{dec.character}"
+                }
+            } else if confusable {
                 div {
                     class: "trainer-zigen-confusable",
 
@@ -324,6 +632,13 @@ pub fn Card(props: CardProps) -> Element {
                 }
             }
 
+            if let Some(mistake) = mistake().filter(|m| is_wrong() && *m != Mistake::Revealed) {
+                div {
+                    class: "trainer-zigen-feedback",
+                    "{mistake_feedback(mistake, major_len.is_some())}"
+                }
+            }
+
             for (i, group) in zigen_groups.iter().enumerate() {
                 div {
                     class: "trainer-zigen-group",
@@ -331,9 +646,17 @@ pub fn Card(props: CardProps) -> Element {
                     div {
                         class: "trainer-zigen-display",
 
-                        for zigen in &group.zigens {
+                        // 拆分练习在提示前只显示字根的序号
+                        if decomposition.is_some() && !*asked_hint.read() {
                             p {
-                                "{zigen.0}"
+                                class: "trainer-zigen-decomposition-ordinal",
+                                "{i + 1}"
+                            }
+                        } else {
+                            for zigen in &group.zigens {
+                                p {
+                                    "{zigen.0}"
+                                }
                             }
                         }
                     }
@@ -344,23 +667,30 @@ pub fn Card(props: CardProps) -> Element {
                         for (j, _) in group.code.chars().enumerate() {
                             {
                                 let start_time2 = start_time1.clone();
+                                let keyboard2 = keyboard1.clone();
 
                                 rsx! {
-                                        div {
+                                    div {
                                         class: "trainer-zigen-input",
+                                        class: if major_len.is_some_and(|len| j < len) { "trainer-zigen-input-major" },
+                                        class: if major_len == Some(j) { "trainer-zigen-input-minor-start" },
                                         autofocus: true,
                                         tabindex: 0,
                                         onclick: move |_event| {},
                                         onkeydown: move |event| {
                                             let start_time2 = start_time2.clone();
+                                            let keyboard2 = keyboard2.clone();
                                             async move {
                                                 handle_key_event(
                                                     &mut input_boxes,
                                                     event,
                                                     asked_hint,
                                                     is_wrong,
+                                                    mistake,
                                                     confusable,
-                                                    &expected_answer.read(),
+                                                    &accepted_answers(),
+                                                    props.code_structure.as_ref(),
+                                                    &keyboard2,
                                                     Rc::clone(&start_time2),
                                                     props.on_card_completed,
                                                 ).await
@@ -374,6 +704,7 @@ pub fn Card(props: CardProps) -> Element {
 
                         {
                             let start_time2 = start_time1.clone();
+                            let keyboard2 = keyboard1.clone();
 
                             rsx! {
                                 input {
@@ -383,6 +714,7 @@ pub fn Card(props: CardProps) -> Element {
                                     spellcheck: false,
                                     oninput: move |event| {
                                         let start_time2 = start_time2.clone();
+                                        let keyboard2 = keyboard2.clone();
                                         async move {
                                             handle_input_event(
                                                 &mut input_boxes,
@@ -390,8 +722,11 @@ pub fn Card(props: CardProps) -> Element {
                                                 event,
                                                 asked_hint,
                                                 is_wrong,
+                                                mistake,
                                                 confusable,
-                                                &expected_answer.read(),
+                                                &accepted_answers(),
+                                                props.code_structure.as_ref(),
+                                                &keyboard2,
                                                 Rc::clone(&start_time2),
                                                 props.on_card_completed,
                                             ).await
@@ -403,17 +738,24 @@ pub fn Card(props: CardProps) -> Element {
                         }
                     }
 
+                    if props.examples_as_prompt && decomposition.is_none() && !group.examples.is_empty() && !*asked_hint.read() {
+                        {render_examples(group)}
+                    }
+
                     if *asked_hint.read() {
                         div {
                             class: "trainer-zigen-group-answer",
                             p {
-                                "答案：{group.code}"
+                                "答案：{display_codes(group)}"
                             }
                         }
+                        if !group.examples.is_empty() {
+                            {render_examples(group)}
+                        }
                         div {
                             class: "trainer-zigen-group-description",
                             div {
-                                dangerous_inner_html: "{group.description}"
+                                RichText { source: group.description.clone() }
                             }
                         }
                         div {
@@ -424,9 +766,26 @@ pub fn Card(props: CardProps) -> Element {
             }
 
             if *asked_hint.read() {
+                if let Some(dec) = decomposition {
+                    div {
+                        class: "trainer-zigen-decomposition-split",
+                        p {
+                            "拆分：{display_split(dec)}"
+                        }
+                    }
+                }
+
+                div {
+                    class: "trainer-zigen-keyboard",
+                    QwertyKeyboard {
+                        keyboard: props.keyboard.clone(),
+                        highlighted_keys: answer_keys,
+                    }
+                }
+
                 div {
                     class: "trainer-zigen-description",
-                    dangerous_inner_html: "{description}",
+                    RichText { source: description.clone() }
                 }
             }
         }
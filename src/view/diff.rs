@@ -0,0 +1,158 @@
+use dioxus::prelude::*;
+
+use crate::view::{TOOLS_CSS, fetch_catalog, fetch_zigen, load_zigen_fonts};
+use zigen_trainer::catalog::BUNDLED_CATALOG;
+use zigen_trainer::diff::{self, Change};
+
+/// 方案比较工具：列出两个内置方案之间，哪些字根的编码、分类或聚类有所不同。
+#[component]
+pub fn SchemeDiff() -> Element {
+    let mut old_id = use_signal(String::new);
+    let mut new_id = use_signal(String::new);
+    let mut result: Signal<Option<Result<Vec<Change>, String>>> = use_signal(|| None);
+
+    let schemes = use_resource(|| async { fetch_catalog(BUNDLED_CATALOG, false).await });
+
+    let compare = move |_| async move {
+        let Some(Ok(schemes)) = schemes.read().clone() else {
+            return;
+        };
+
+        let find = |id: &str| schemes.iter().find(|scheme| scheme.id == id).cloned();
+        let (Some(old), Some(new)) = (find(&old_id.read()), find(&new_id.read())) else {
+            result.set(Some(Err(String::from("请先选择两个方案"))));
+            return;
+        };
+
+        // 两个方案的字根字体须同时可用；加载失败的字体已记录在日志中
+        load_zigen_fonts(&[old.zigen_font.as_slice(), new.zigen_font.as_slice()].concat()).await;
+
+        let changes = match (fetch_zigen(&old).await, fetch_zigen(&new).await) {
+            (Ok(old), Ok(new)) => Ok(diff::diff(&old, &new)),
+            (Err(err), _) | (_, Err(err)) => Err(err),
+        };
+
+        result.set(Some(changes));
+    };
+
+    rsx! {
+        document::Link { rel: "stylesheet", href: TOOLS_CSS }
+
+        div {
+            class: "trainer-tool",
+
+            h1 {
+                "方案比较"
+            }
+
+            p {
+                "比较两个方案的字根集。归并组以代表字根配对，列出新增与删除的字根，以及编码、分类、归并与聚类的改变。"
+            }
+
+            match &*schemes.read() {
+                Some(Ok(list)) => rsx! {
+                    div {
+                        class: "trainer-tool-row",
+
+                        select {
+                            class: "trainer-tool-select",
+                            onchange: move |event| old_id.set(event.value()),
+
+                            option { value: "", "旧方案" }
+                            for scheme in list.iter() {
+                                option { value: "{scheme.id}", "{scheme.full_name}" }
+                            }
+                        }
+
+                        "→"
+
+                        select {
+                            class: "trainer-tool-select",
+                            onchange: move |event| new_id.set(event.value()),
+
+                            option { value: "", "新方案" }
+                            for scheme in list.iter() {
+                                option { value: "{scheme.id}", "{scheme.full_name}" }
+                            }
+                        }
+
+                        button {
+                            class: "trainer-tool-button",
+                            onclick: compare,
+                            "比较"
+                        }
+                    }
+                },
+
+                Some(Err(e)) => rsx! {
+                    p {
+                        class: "trainer-tool-error",
+                        "无法加载方案列表：{e}"
+                    }
+                },
+
+                None => rsx! {
+                    p {
+                        "数据加载中……"
+                    }
+                },
+            }
+
+            match &*result.read() {
+                Some(Ok(changes)) if changes.is_empty() => rsx! {
+                    p {
+                        "两个方案的字根集没有任何差异。"
+                    }
+                },
+
+                Some(Ok(changes)) => rsx! {
+                    p {
+                        "共{changes.len()}项差异"
+                    }
+
+                    table {
+                        class: "trainer-tool-table",
+
+                        thead {
+                            tr {
+                                th { "字根" }
+                                th { "种类" }
+                                th { "内容" }
+                            }
+                        }
+
+                        tbody {
+                            for change in changes.iter() {
+                                tr {
+                                    class: "trainer-diff-{change_class(change)}",
+
+                                    td { class: "trainer-tool-zigen", "{change.zigen()}" }
+                                    td { "{change.kind()}" }
+                                    td { class: "trainer-tool-zigen", "{change.detail()}" }
+                                }
+                            }
+                        }
+                    }
+                },
+
+                Some(Err(e)) => rsx! {
+                    p {
+                        class: "trainer-tool-error",
+                        "比较失败：{e}"
+                    }
+                },
+
+                None => rsx! {},
+            }
+        }
+    }
+}
+
+/// 差异行的样式：新增、删除与其他改变分别以不同颜色标示。
+fn change_class(change: &Change) -> &'static str {
+    match change {
+        Change::Added { .. } => "added",
+        Change::Removed { .. } => "removed",
+        _ => "changed",
+    }
+}
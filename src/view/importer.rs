@@ -0,0 +1,100 @@
+use dioxus::prelude::*;
+
+use crate::view::{TOOLS_CSS, download_text};
+use zigen_trainer::import::{self, ImportFormat};
+
+/// 码表转换工具：将用户的码表转换为字根集JSON。
+#[component]
+pub fn Importer() -> Element {
+    let mut format = use_signal(|| String::from("auto"));
+    let mut result: Signal<Option<Result<String, String>>> = use_signal(|| None);
+
+    rsx! {
+        document::Link { rel: "stylesheet", href: TOOLS_CSS }
+
+        div {
+            class: "trainer-tool",
+
+            h1 {
+                "码表转换"
+            }
+
+            p {
+                "将制表符分隔的「字根<TAB>编码」码表、RIME码表（*.dict.yaml）或含有分类列的CSV（字根,编码,分类）转换为字根集JSON。"
+                "归并组与聚类是根据编码推测出来的，转换后请手动整理。"
+            }
+
+            div {
+                class: "trainer-tool-row",
+
+                select {
+                    class: "trainer-tool-select",
+                    onchange: move |event| format.set(event.value()),
+
+                    option { value: "auto", "根据扩展名判断" }
+                    option { value: "tsv", "制表符分隔" }
+                    option { value: "rime", "RIME码表" }
+                    option { value: "csv", "CSV" }
+                }
+
+                input {
+                    r#type: "file",
+                    accept: ".txt,.tsv,.yaml,.yml,.csv",
+                    multiple: false,
+                    onchange: move |event| async move {
+                        let Some(file) = event.files().into_iter().next() else {
+                            return;
+                        };
+
+                        let content = match file.read_string().await {
+                            Ok(content) => content,
+                            Err(err) => {
+                                result.set(Some(Err(format!("无法加载文件：{err}"))));
+                                return;
+                            }
+                        };
+
+                        let format = ImportFormat::from_name(&format.read())
+                            .unwrap_or_else(|| ImportFormat::guess(&file.name()));
+
+                        result.set(Some(import::import(&content, format).map(|scheme| {
+                            serde_json::to_string_pretty(&scheme).unwrap()
+                        })));
+                    },
+                }
+            }
+
+            match &*result.read() {
+                Some(Ok(json)) => rsx! {
+                    div {
+                        class: "trainer-tool-row",
+
+                        button {
+                            class: "trainer-tool-button",
+                            onclick: {
+                                let json = json.clone();
+                                move |_| download_text("zigen.json", "application/json", json.clone())
+                            },
+                            "下载字根集"
+                        }
+                    }
+
+                    textarea {
+                        class: "trainer-tool-output",
+                        readonly: true,
+                        value: "{json}",
+                    }
+                },
+
+                Some(Err(e)) => rsx! {
+                    p {
+                        class: "trainer-tool-error",
+                        "转换失败：{e}"
+                    }
+                },
+
+                None => rsx! {},
+            }
+        }
+    }
+}
@@ -1,17 +1,34 @@
 mod card;
+mod diff;
+mod importer;
 mod scheme;
+mod stats;
 mod welcome;
 
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+
 use dioxus_logger::tracing;
 use gloo_net::http::Request;
-use web_sys::FontFace;
-use web_sys::wasm_bindgen::prelude::Closure;
+use wasm_bindgen_futures::{JsFuture, future_to_promise};
+use web_sys::js_sys::{self, Array, Promise, Uint8Array};
+use web_sys::wasm_bindgen::{JsCast, JsValue};
+use web_sys::{FontFace, HtmlElement};
 pub use welcome::*;
 
 use dioxus::prelude::*;
 
 use crate::scheme::{LoadedScheme, Scheme, SchemeOptions, ZigenConfusableUnpopulated};
 use crate::user_state::UserState;
+use diff::SchemeDiff;
+use importer::Importer;
+use stats::SchemeStatistics;
+use zigen_trainer::catalog;
+use zigen_trainer::compact;
+use zigen_trainer::coverage::{self, MissingGlyph};
+use zigen_trainer::font;
+use zigen_trainer::overlay::{self, SchemeFile};
 
 #[used]
 static SCHEMES: Asset = asset!(
@@ -31,53 +48,353 @@ static TUTORIAL_PAGE: Asset = asset!(
     AssetOptions::builder().with_hash_suffix(false)
 );
 
+const TOOLS_CSS: Asset = asset!("/assets/tools.css");
+
+/// 首页以外的工具页面。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Tool {
+    /// 码表转换
+    Importer,
+    /// 方案比较
+    Diff,
+    /// 方案详情与统计
+    Stats,
+}
+
+/// 让用户下载一个文本文件。
+fn download_text(file_name: &str, mime: &str, content: String) {
+    let eval = document::eval(
+        r#"
+        const [file_name, mime, content] = await dioxus.recv();
+        const blob = new Blob([content], { type: mime });
+        const url = URL.createObjectURL(blob);
+        const a = document.createElement('a');
+        a.href = url;
+        a.download = file_name;
+        a.click();
+        URL.revokeObjectURL(url);
+    "#,
+    );
+
+    let _ = eval.send((file_name, mime, content));
+}
+
+/// 下载位于 url 的方案列表，并解析其中的地址。
+async fn fetch_catalog(url: &str, third_party: bool) -> Result<Vec<Scheme>, String> {
+    let schemes = Request::get(url)
+        .send()
+        .await
+        .map_err(|err| err.to_string())?
+        .json::<Vec<Scheme>>()
+        .await
+        .map_err(|err| err.to_string())?;
+
+    Ok(catalog::resolve_catalog(url, schemes, third_party))
+}
+
+/// 字根字体的加载时限（毫秒）。超时的字体视为加载失败，以免卡片迟迟无法显示。
+const FONT_TIMEOUT_MS: i32 = 10_000;
+
+thread_local! {
+    /// 目前已注册的字根字体。加载另一组字体前须先移除，以免新旧字体混用。
+    static ZIGEN_FONTS: RefCell<Vec<FontFace>> = const { RefCell::new(Vec::new()) };
+}
+
+/// 字体在错误信息中的名称。本地字体以 data URL 保存，不宜直接显示。
+fn font_label(url: &str) -> &str {
+    if url.starts_with("data:") {
+        "本地字体文件"
+    } else {
+        url.rsplit('/').next().unwrap_or(url)
+    }
+}
+
+fn js_error(value: JsValue) -> String {
+    value
+        .as_string()
+        .or_else(|| {
+            value
+                .dyn_ref::<js_sys::Error>()
+                .map(|err| err.message().into())
+        })
+        .unwrap_or_else(|| format!("{value:?}"))
+}
+
+/// promise 在 FONT_TIMEOUT_MS 内仍未完成时，以「加载超时」拒绝。
+fn with_timeout(promise: Promise) -> Promise {
+    let timeout = Promise::new(&mut |_, reject| {
+        if let Some(window) = web_sys::window() {
+            let _ = window.set_timeout_with_callback_and_timeout_and_arguments_1(
+                &reject,
+                FONT_TIMEOUT_MS,
+                &JsValue::from_str("加载超时"),
+            );
+        }
+    });
+
+    Promise::race(&Array::of2(&promise, &timeout))
+}
+
+/// 字根字体的加载结果。
+#[derive(Clone, Default)]
+struct ZigenFonts {
+    /// 加载失败（或超时）的字体及其原因
+    errors: Vec<String>,
+    /// 加载成功的字体的URL与文件内容，检查字形覆盖时无须再次下载
+    data: Vec<(String, Rc<[u8]>)>,
+}
+
+/// 下载位于 url 的字体，以 Uint8Array 兑现，以便与其他 promise 一同等待。
+fn fetch_font(url: &str) -> Promise {
+    let url = url.to_owned();
+    future_to_promise(async move {
+        fetch_bytes(&url)
+            .await
+            .map(|bytes| Uint8Array::from(bytes.as_slice()).into())
+            .map_err(|err| JsValue::from_str(&err))
+    })
+}
+
+/// 注册一个已下载的字体，返回注册时所用的 FontFace。字体无法解析时返回错误。
+async fn add_zigen_font(family: &str, data: &[u8]) -> Result<FontFace, String> {
+    let face = FontFace::new_with_u8_array(family, data).map_err(js_error)?;
+    let promise = face.load().map_err(js_error)?;
+    JsFuture::from(with_timeout(promise))
+        .await
+        .map_err(js_error)?;
+    Ok(face)
+}
+
+/// 加载字根字体，依次注册为 zigen-font-0、zigen-font-1……，并按顺序写入 CSS 变量
+/// --zigen-fonts，使前面的字体缺少的字形由后面的字体补上。
+///
+/// 加载失败（或超时）的字体会被跳过，其余字体照常使用。
+async fn load_zigen_fonts(fonts: &[String]) -> ZigenFonts {
+    let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+        return ZigenFonts::default();
+    };
+    let font_set = document.fonts();
+
+    ZIGEN_FONTS.with_borrow_mut(|loaded| {
+        for face in loaded.drain(..) {
+            font_set.delete(&face);
+        }
+    });
+
+    // 先让所有字体同时开始下载，再按顺序等待
+    let pending = fonts
+        .iter()
+        .map(|url| with_timeout(fetch_font(url)))
+        .collect::<Vec<_>>();
+
+    let mut families = Vec::new();
+    let mut loaded = ZigenFonts::default();
+
+    for (i, (url, promise)) in fonts.iter().zip(pending).enumerate() {
+        let family = format!("zigen-font-{i}");
+        let result = match JsFuture::from(promise).await {
+            Ok(bytes) => {
+                let data = Rc::<[u8]>::from(Uint8Array::new(&bytes).to_vec());
+                add_zigen_font(&family, &data)
+                    .await
+                    .map(|face| (face, data))
+            }
+            Err(err) => Err(js_error(err)),
+        };
+
+        match result {
+            Ok((face, data)) => {
+                let _ = font_set.add(&face);
+                families.push(family);
+                ZIGEN_FONTS.with_borrow_mut(|loaded| loaded.push(face));
+                loaded.data.push((url.clone(), data));
+            }
+            Err(err) => {
+                tracing::warn!("unable to load zigen font {}: {err}", font_label(url));
+                loaded.errors.push(format!("{}：{err}", font_label(url)));
+            }
+        }
+    }
+
+    if let Some(root) = document
+        .document_element()
+        .and_then(|root| root.dyn_into::<HtmlElement>().ok())
+    {
+        let style = root.style();
+        if families.is_empty() {
+            let _ = style.remove_property("--zigen-fonts");
+        } else {
+            let _ = style.set_property("--zigen-fonts", &families.join(", "));
+        }
+    }
+
+    loaded
+}
+
+/// 加载方案的字根集：用户方案直接取自 user_state，其他方案则从网络下载。
+///
+/// 返回的字根集尚未加上用户自定义的标签（见 UserState::user_tags）。
+async fn load_zigen(
+    scheme: &Scheme,
+    user_state: ReadSignal<UserState>,
+) -> Result<LoadedScheme<ZigenConfusableUnpopulated>, String> {
+    // 这里不能订阅 user_state，不然每做一张卡片都会重新加载字根集
+    let local = user_state.peek().user_scheme_zigen(&scheme.id).cloned();
+
+    let loaded = match local {
+        Some(loaded) => loaded,
+        None => fetch_zigen(scheme).await?,
+    };
+
+    loaded.check_keys(&scheme.keyboard)?;
+    Ok(loaded)
+}
+
+/// 下载方案的字根集。release版优先使用 zigen-compile 生成的预编译文件，没有或版本不符时才下载JSON。
+///
+/// 调试版一律使用JSON，以免编辑字根集后仍读到旧的预编译文件。
+async fn fetch_zigen(scheme: &Scheme) -> Result<LoadedScheme<ZigenConfusableUnpopulated>, String> {
+    if !cfg!(debug_assertions)
+        && let Some(url) = compact::compiled_url(&scheme.zigen_url)
+    {
+        match fetch_bytes(&url)
+            .await
+            .and_then(|bytes| compact::decode_version(&bytes, &scheme.version))
+        {
+            Ok(loaded) => return Ok(loaded),
+            Err(err) => tracing::info!("compiled zigen {url} unavailable, using JSON: {err}"),
+        }
+    }
+
+    let source = fetch_text(&scheme.zigen_url).await?;
+
+    flatten_zigen(scheme.zigen_url.clone(), source).await
+}
+
+async fn fetch_bytes(url: &str) -> Result<Vec<u8>, String> {
+    let response = Request::get(url)
+        .send()
+        .await
+        .map_err(|err| err.to_string())?;
+
+    if !response.ok() {
+        return Err(format!("HTTP {}", response.status()));
+    }
+
+    response.binary().await.map_err(|err| err.to_string())
+}
+
+async fn fetch_text(url: &str) -> Result<String, String> {
+    Request::get(url)
+        .send()
+        .await
+        .map_err(|err| err.to_string())?
+        .text()
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// 解析位于 url 的字根集。字根集可能是叠加文件，需要顺着 base 一路加载到完整的字根集为止。
+async fn flatten_zigen(
+    mut url: String,
+    mut source: String,
+) -> Result<LoadedScheme<ZigenConfusableUnpopulated>, String> {
+    let mut overlays = Vec::new();
+
+    let base = loop {
+        match SchemeFile::parse(&source)? {
+            SchemeFile::Full(loaded) => break loaded,
+            SchemeFile::Overlay(overlay) => {
+                if overlays.len() >= overlay::MAX_DEPTH {
+                    return Err(String::from("叠加文件嵌套过深，可能存在循环引用"));
+                }
+
+                url = overlay::resolve_relative(&url, &overlay.base);
+                overlays.push(overlay);
+                source = fetch_text(&url).await?;
+            }
+        }
+    };
+
+    overlay::flatten(base, &overlays)
+}
+
+/// 字根字体覆盖检查的结果。
+#[derive(Clone, Default)]
+struct FontCoverage {
+    /// 字根字体缺少的字形
+    missing: Vec<MissingGlyph>,
+    /// 无法读取的字体及原因
+    unchecked: Vec<String>,
+}
+
+/// 找出所有字根字体都缺少字形的私用区字根。任一字体无法解析时，改为报告无法检查的字体。
+///
+/// 字体内容取自 load_zigen_fonts，不再重新下载。有字体加载失败时已另行提示，不再检查。
+fn check_font_coverage(
+    fonts: &ZigenFonts,
+    loaded: &LoadedScheme<ZigenConfusableUnpopulated>,
+) -> FontCoverage {
+    if !fonts.errors.is_empty() {
+        return FontCoverage::default();
+    }
+
+    let mut charset = HashSet::new();
+    let mut unchecked = Vec::new();
+
+    for (url, data) in fonts.data.iter() {
+        match font::charset(data) {
+            Ok(chars) => charset.extend(chars),
+            Err(err) => {
+                tracing::warn!("unable to check glyph coverage of {url}: {err}");
+                unchecked.push(format!("{}：{err}", font_label(url)));
+            }
+        }
+    }
+
+    // 有字体无法读取时，其余字体缺少的字形未必真的缺少，只提示无法检查
+    let missing = if unchecked.is_empty() {
+        coverage::missing_glyphs(loaded, &charset)
+    } else {
+        Vec::new()
+    };
+
+    FontCoverage { missing, unchecked }
+}
+
 #[component]
 pub fn Trainer() -> Element {
     let mut scheme: Signal<Option<Scheme>> = use_signal(|| None);
+    let mut tool: Signal<Option<Tool>> = use_signal(|| None);
     let mut options: Signal<SchemeOptions> = use_signal(SchemeOptions::default);
     let mut user_state: Signal<UserState> = use_signal(UserState::read_from_local_storage);
+    let mut loading_fonts = use_signal(|| false);
 
+    // 字根字体加载完成（或超时）后才显示卡片，否则私用区字根会先显示为方框
     let loaded_scheme = use_resource(move || async move {
-        if let Some(scheme) = &*scheme.read() {
-            if !scheme.zigen_font.is_empty() {
-                let zigen_font = FontFace::new_with_str(
-                    "zigen-font",
-                    &format!("url(./assets/trainer/{})", scheme.zigen_font),
-                );
-
-                if let Ok(zigen_font) = zigen_font {
-                    if let Ok(promise) = zigen_font.load() {
-                        let closure = Closure::new(|font| {
-                            web_sys::window()
-                                .and_then(|window| window.document())
-                                .map(|document| document.fonts())
-                                .map(|fonts| fonts.add(&FontFace::from(font)));
-                        });
-
-                        let _ = promise.then(&closure);
-
-                        // 把闭包交给JS的垃圾回收器处理，不然在promise执行前它早就已经被
-                        // Rust自己的drop代码处理了
-                        closure.forget();
-                    }
-                } else {
-                    tracing::warn!("unable to load zigen font from {}", scheme.id);
-                }
-            }
+        let scheme = scheme.read().clone();
+        if let Some(scheme) = &scheme {
+            let mut loaded = load_zigen(scheme, user_state.into()).await?;
+            loaded.apply_tags(&user_state.peek().user_tags(&scheme.id));
+
+            loading_fonts.set(true);
+            let fonts = load_zigen_fonts(&scheme.zigen_font).await;
+            loading_fonts.set(false);
 
-            Request::get(&(String::from("./assets/trainer/") + &scheme.zigen_url))
-                .send()
-                .await
-                .map_err(|err| err.to_string())?
-                .json::<LoadedScheme<ZigenConfusableUnpopulated>>()
-                .await
-                .map_err(|err| err.to_string())
-                .map(|loaded| (scheme.id.clone(), loaded))
+            Ok((scheme.clone(), loaded, fonts))
         } else {
             Err(String::new())
         }
     });
 
+    let font_coverage = use_resource(move || async move {
+        let Some(Ok((_, loaded, fonts))) = loaded_scheme.read().clone() else {
+            return FontCoverage::default();
+        };
+
+        check_font_coverage(&fonts, &loaded)
+    });
+
     let mut to_confirm_reset = use_signal(|| false);
 
     rsx! {
@@ -86,7 +403,10 @@ pub fn Trainer() -> Element {
                 class: "root-nav",
 
                 a {
-                    onclick: move |_| scheme.set(None),
+                    onclick: move |_| {
+                        scheme.set(None);
+                        tool.set(None);
+                    },
                     "首页"
                 }
 
@@ -123,6 +443,21 @@ pub fn Trainer() -> Element {
                             "使用教程"
                         }
 
+                        a {
+                            onclick: move |_| tool.set(Some(Tool::Importer)),
+                            "码表转换"
+                        }
+
+                        a {
+                            onclick: move |_| tool.set(Some(Tool::Diff)),
+                            "方案比较"
+                        }
+
+                        a {
+                            onclick: move |_| tool.set(Some(Tool::Stats)),
+                            "方案详情"
+                        }
+
                         a {
                             onclick: move |_| {
                                 document::eval(r#"document.getElementById("import-file-button").click();"#);
@@ -203,7 +538,13 @@ pub fn Trainer() -> Element {
         div {
             class: "trainer-root",
 
-            if scheme.read_unchecked().is_none() {
+            if let Some(tool) = tool() {
+                match tool {
+                    Tool::Importer => rsx! { Importer {} },
+                    Tool::Diff => rsx! { SchemeDiff {} },
+                    Tool::Stats => rsx! { SchemeStatistics {} },
+                }
+            } else if scheme.read_unchecked().is_none() {
                 Welcome {
                     user_state,
                     on_scheme_selected: move |(selected, opts)| {
@@ -216,18 +557,30 @@ pub fn Trainer() -> Element {
             match &*loaded_scheme.read_unchecked() {
                 None => rsx! {
                     p {
-                        "数据加载中……"
+                        if loading_fonts() {
+                            "正在加载字根字体……"
+                        } else {
+                            "数据加载中……"
+                        }
                     }
                 },
 
-                Some(Ok((scheme_id, scheme))) => rsx! {
+                Some(Ok((meta, scheme, fonts))) => rsx! {
                     scheme::Scheme {
-                        scheme_id: scheme_id,
+                        meta: meta.clone(),
                         scheme: scheme.clone(),
                         options: options(),
                         user_state,
                         on_scheme_completed: |()| {},
                     }
+
+                    if !fonts.errors.is_empty() {
+                        scheme::FontWarning {
+                            title: "字根字体加载失败",
+                            message: "以下字体未能加载，部分字根可能会显示为方框。请检查网络后刷新页面：",
+                            items: fonts.errors.clone(),
+                        }
+                    }
                 },
 
                 Some(Err(e)) if e.is_empty() => rsx! {},
@@ -238,6 +591,26 @@ pub fn Trainer() -> Element {
                     }
                 }
             }
+
+            if let Some(coverage) = font_coverage.read().clone() {
+                if !coverage.unchecked.is_empty() {
+                    scheme::FontWarning {
+                        title: "无法检查字根字体",
+                        message: "以下字体无法读取，无法确认所有字根都能正常显示。如有字根显示为方框，请向方案作者反馈：",
+                        items: coverage.unchecked,
+                    }
+                } else if !coverage.missing.is_empty() {
+                    scheme::FontWarning {
+                        title: "字根字体缺少字形",
+                        message: "以下字根无法以字根字体显示，会显示为方框。请尝试刷新页面，或向方案作者反馈：",
+                        items: coverage
+                            .missing
+                            .iter()
+                            .map(|glyph| format!("{}（{}）", glyph.code, glyph.codepoints()))
+                            .collect::<Vec<_>>(),
+                    }
+                }
+            }
         }
     }
 }
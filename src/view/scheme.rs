@@ -1,13 +1,17 @@
-use crate::scheme::{LoadedScheme, SchemeOptions, ZigenConfusableUnpopulated};
+use crate::scheme::{self, LoadedScheme, SchemeOptions, ZigenConfusableUnpopulated};
 use crate::user_state::UserState;
 use crate::view::card::Card;
+use crate::view::download_text;
+use zigen_trainer::export::anki_tsv;
+use zigen_trainer::progress::SchemeUpdate;
 
 use dioxus::prelude::*;
 use dioxus_logger::tracing;
 
 #[derive(PartialEq, Clone, Props)]
 pub struct SchemeProps {
-    scheme_id: String,
+    /// 方案信息（来自 schemes.json）
+    meta: scheme::Scheme,
     scheme: LoadedScheme<ZigenConfusableUnpopulated>,
     options: SchemeOptions,
     user_state: Signal<UserState>,
@@ -17,9 +21,9 @@ pub struct SchemeProps {
 #[component]
 pub fn Scheme(mut props: SchemeProps) -> Element {
     let res = props.user_state.write().try_initialize_scheme(
-        &props.scheme_id,
+        &props.meta,
         &props.scheme,
-        props.options,
+        props.options.clone(),
     );
 
     if let Err(e) = res {
@@ -37,10 +41,12 @@ pub fn Scheme(mut props: SchemeProps) -> Element {
         };
     }
 
-    tracing::info!("initialized scheme! {}", &props.scheme_id);
+    tracing::info!("initialized scheme! {}", &props.meta.id);
 
     let zigens = props.user_state.write().current_progress_mut().get_card();
+    let update = props.user_state.read().current_progress().update().cloned();
     let adept = props.user_state.read().current_progress().is_adept();
+    let examples_as_prompt = props.options.examples_as_prompt;
 
     let progress = use_memo(move || {
         let user_state = props.user_state.read();
@@ -51,6 +57,44 @@ pub fn Scheme(mut props: SchemeProps) -> Element {
         (completed as f64 / total as f64 * 100.0, completed, total)
     });
 
+    let due_work_done = use_memo(move || {
+        props
+            .user_state
+            .read()
+            .current_progress()
+            .is_due_work_done()
+    });
+    let extra_remaining = use_memo(move || {
+        props
+            .user_state
+            .read()
+            .current_progress()
+            .extra_practice_remaining()
+    });
+    let mut extra_count = use_signal(|| String::from("20"));
+    let mut extra_update_schedule = use_signal(|| false);
+    // 上一次尝试开始加练时，是否因为没有可加练的卡片而失败
+    let mut extra_unavailable = use_signal(|| false);
+
+    let export_anki = move |_| {
+        // 导出时不打乱顺序，方便用户在 Anki 内按原顺序学习
+        let options = SchemeOptions {
+            shuffle: false,
+            ..props.options.clone()
+        };
+
+        let Ok(mut scheme) = props.scheme.clone().populate_confusables() else {
+            return;
+        };
+        scheme.sort_to_options(&options);
+
+        download_text(
+            &format!("{}.txt", props.meta.id),
+            "text/tab-separated-values",
+            anki_tsv(&scheme, &props.meta.id),
+        );
+    };
+
     use_effect(|| {
         document::eval(
             r#"
@@ -66,6 +110,92 @@ pub fn Scheme(mut props: SchemeProps) -> Element {
             p {
                 "进度： {progress().0:.1}% （{progress().1} / {progress().2}）"
             }
+
+            div {
+                class: "trainer-nav-right",
+
+                if let Some(remaining) = extra_remaining() {
+                    div {
+                        class: "trainer-nav-extra",
+
+                        p {
+                            "弱项加练：剩余 {remaining} 张"
+                        }
+
+                        a {
+                            onclick: move |_| {
+                                let mut user_state = props.user_state.write();
+                                user_state.current_progress_mut().stop_extra_practice();
+                                user_state.write_to_local_storage();
+                            },
+                            "结束加练"
+                        }
+                    }
+                } else if due_work_done() {
+                    div {
+                        class: "trainer-nav-extra",
+
+                        a {
+                            onclick: move |_| {
+                                let count = extra_count.read().parse::<usize>().unwrap_or(0).max(1);
+                                let mut user_state = props.user_state.write();
+                                let started = user_state
+                                    .current_progress_mut()
+                                    .start_extra_practice(count, extra_update_schedule());
+
+                                if started {
+                                    user_state.write_to_local_storage();
+                                }
+                                extra_unavailable.set(!started);
+                            },
+                            "弱项加练"
+                        }
+
+                        if extra_unavailable() {
+                            p {
+                                "尚无学过的卡片，无法加练"
+                            }
+                        }
+
+                        input {
+                            r#type: "number",
+                            min: "1",
+                            value: "{extra_count}",
+                            oninput: move |event| extra_count.set(event.value()),
+                        }
+
+                        label {
+                            input {
+                                r#type: "checkbox",
+                                checked: extra_update_schedule(),
+                                onclick: move |_| {
+                                    let new_value = !extra_update_schedule();
+                                    extra_update_schedule.set(new_value);
+                                },
+                            }
+                            "计入调度"
+                        }
+                    }
+                }
+
+                div {
+                    class: "trainer-nav-extra",
+
+                    a {
+                        onclick: export_anki,
+                        "导出Anki卡组"
+                    }
+                }
+            }
+        }
+
+        if let Some(update) = update {
+            UpdateNotice {
+                update,
+                on_dismiss: move |()| {
+                    props.user_state.write().current_progress_mut().dismiss_update();
+                },
+            }
         }
 
         match res {
@@ -73,14 +203,17 @@ pub fn Scheme(mut props: SchemeProps) -> Element {
                 Card {
                     zigens: zigens,
                     adept: adept,
-                    on_card_completed: move |rating| {
-                        tracing::debug!("completed card! {rating:?}");
+                    code_structure: props.meta.code_structure,
+                    keyboard: props.meta.keyboard.clone(),
+                    examples_as_prompt: examples_as_prompt,
+                    on_card_completed: move |(rating, latency)| {
+                        tracing::debug!("completed card! {rating:?} in {latency:.1}s");
 
                         let mut user_state = props.user_state.write();
-                        user_state.current_progress_mut().rate_card(rating);
+                        user_state.current_progress_mut().rate_card(rating, latency);
 
                         // 将同个聚类内的归并字根集的顺序打乱，避免发生“首尾记忆”效应（即：记住了前后的字根，而中间的却忘了）。
-                        user_state.current_progress_mut().get_card_mut().shuffle();
+                        user_state.current_progress_mut().shuffle_current_card();
 
                         user_state.write_to_local_storage();
                     },
@@ -103,3 +236,89 @@ pub fn Scheme(mut props: SchemeProps) -> Element {
         }
     }
 }
+
+/// 卡片ID中的字根部分，比如「类:口,日@Jr,Kk」中的「口,日」。
+fn card_roots(id: &str) -> &str {
+    let roots = id.split_once(':').map(|(_, roots)| roots).unwrap_or(id);
+    roots
+        .split_once('@')
+        .map(|(roots, _)| roots)
+        .unwrap_or(roots)
+}
+
+/// 继续旧进度时，提示用户字根集有所变动。
+#[component]
+fn UpdateNotice(update: SchemeUpdate, on_dismiss: EventHandler<()>) -> Element {
+    let title = match (update.from_version.as_str(), update.to_version.as_str()) {
+        (from, to) if !from.is_empty() && from != to => format!("字根集已由 {from} 更新至 {to}"),
+        _ => String::from("字根集有所变动"),
+    };
+    let added = update
+        .added
+        .iter()
+        .map(|id| card_roots(id))
+        .collect::<Vec<_>>();
+    let removed = update
+        .removed
+        .iter()
+        .map(|id| card_roots(id))
+        .collect::<Vec<_>>();
+
+    rsx! {
+        div {
+            class: "trainer-update-notice",
+
+            h3 { "{title}" }
+
+            if !update.changelog.is_empty() {
+                p { "{update.changelog}" }
+            }
+
+            if !added.is_empty() {
+                p {
+                    "新增{added.len()}张卡片（已排在新卡片之后）："
+                    span { class: "trainer-update-zigens", {added.join(" ")} }
+                }
+            }
+
+            if !removed.is_empty() {
+                p {
+                    "删除{removed.len()}张卡片（其进度已一并删除）："
+                    span { class: "trainer-update-zigens", {removed.join(" ")} }
+                }
+            }
+
+            a {
+                onclick: move |_| on_dismiss(()),
+                "知道了"
+            }
+        }
+    }
+}
+
+/// 字根字体的问题：字体加载失败，或者缺少部分字根的字形。
+#[component]
+pub fn FontWarning(title: String, message: String, items: Vec<String>) -> Element {
+    let mut dismissed = use_signal(|| false);
+
+    if dismissed() {
+        return rsx! {};
+    }
+
+    rsx! {
+        div {
+            class: "trainer-font-warning",
+
+            h3 { "{title}" }
+
+            p { "{message}" }
+
+            p { {items.join("、")} }
+
+            a {
+                onclick: move |_| dismissed.set(true),
+                "知道了"
+            }
+        }
+    }
+}
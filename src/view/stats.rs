@@ -0,0 +1,172 @@
+use dioxus::prelude::*;
+
+use crate::component::RichText;
+use crate::scheme::Scheme;
+use crate::view::{TOOLS_CSS, fetch_catalog, fetch_zigen};
+use zigen_trainer::catalog::BUNDLED_CATALOG;
+use zigen_trainer::stats::{self, SchemeStats};
+
+/// 方案详情：显示内置方案的基本资料与字根集的统计数据，可另选一个方案并排比较。
+#[component]
+pub fn SchemeStatistics() -> Element {
+    let mut first_id = use_signal(String::new);
+    let mut second_id = use_signal(String::new);
+
+    let schemes = use_resource(|| async { fetch_catalog(BUNDLED_CATALOG, false).await });
+
+    let loaded = use_resource(move || async move {
+        let Some(Ok(schemes)) = schemes.read().clone() else {
+            return Ok(Vec::new());
+        };
+
+        let ids = [first_id.read().clone(), second_id.read().clone()];
+        let mut loaded: Vec<(Scheme, SchemeStats)> = Vec::new();
+
+        for id in ids.iter().filter(|id| !id.is_empty()) {
+            let Some(scheme) = schemes.iter().find(|scheme| scheme.id == *id) else {
+                continue;
+            };
+
+            let zigen = fetch_zigen(scheme).await?;
+            loaded.push((scheme.clone(), SchemeStats::compute(&zigen)));
+        }
+
+        Ok::<_, String>(loaded)
+    });
+
+    rsx! {
+        document::Link { rel: "stylesheet", href: TOOLS_CSS }
+
+        div {
+            class: "trainer-tool",
+
+            h1 {
+                "方案详情"
+            }
+
+            p {
+                "查看方案的基本资料与字根集的统计数据：各键的字根数、聚类大小、分类与编码长度的分布、混淆集数，以及需要字根字体才能显示的私用区字根所占比例。另选一个方案即可并排比较。"
+            }
+
+            match &*schemes.read() {
+                Some(Ok(list)) => rsx! {
+                    div {
+                        class: "trainer-tool-row",
+
+                        select {
+                            class: "trainer-tool-select",
+                            onchange: move |event| first_id.set(event.value()),
+
+                            option { value: "", "选择方案" }
+                            for scheme in list.iter() {
+                                option { value: "{scheme.id}", "{scheme.full_name}" }
+                            }
+                        }
+
+                        select {
+                            class: "trainer-tool-select",
+                            onchange: move |event| second_id.set(event.value()),
+
+                            option { value: "", "（不比较）" }
+                            for scheme in list.iter() {
+                                option { value: "{scheme.id}", "{scheme.full_name}" }
+                            }
+                        }
+                    }
+                },
+
+                Some(Err(e)) => rsx! {
+                    p {
+                        class: "trainer-tool-error",
+                        "无法加载方案列表：{e}"
+                    }
+                },
+
+                None => rsx! {
+                    p {
+                        "数据加载中……"
+                    }
+                },
+            }
+
+            match &*loaded.read() {
+                Some(Ok(loaded)) if !loaded.is_empty() => {
+                    let all = loaded.iter().map(|(_, stats)| stats).collect::<Vec<_>>();
+                    let sections = stats::sections(&all);
+
+                    rsx! {
+                        table {
+                            class: "trainer-tool-table",
+
+                            thead {
+                                tr {
+                                    th {}
+                                    for (scheme, _) in loaded.iter() {
+                                        th { "{scheme.full_name}" }
+                                    }
+                                }
+                            }
+
+                            tbody {
+                                tr {
+                                    th { "作者" }
+                                    for (scheme, _) in loaded.iter() {
+                                        td { "{scheme.author}" }
+                                    }
+                                }
+
+                                tr {
+                                    th { "维护者" }
+                                    for (scheme, _) in loaded.iter() {
+                                        td { "{scheme.maintainer}" }
+                                    }
+                                }
+
+                                tr {
+                                    th { "版本" }
+                                    for (scheme, _) in loaded.iter() {
+                                        td { "{scheme.version}" }
+                                    }
+                                }
+
+                                tr {
+                                    th { "简介" }
+                                    for (scheme, _) in loaded.iter() {
+                                        td { RichText { source: scheme.description.clone() } }
+                                    }
+                                }
+
+                                for section in sections.into_iter() {
+                                    tr {
+                                        th {
+                                            colspan: "{all.len() + 1}",
+                                            "{section.title}"
+                                        }
+                                    }
+
+                                    for (label, values) in section.rows.into_iter() {
+                                        tr {
+                                            td { "{label}" }
+                                            for value in values.into_iter() {
+                                                td { "{value}" }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                Some(Err(e)) => rsx! {
+                    p {
+                        class: "trainer-tool-error",
+                        "无法加载字根集：{e}"
+                    }
+                },
+
+                _ => rsx! {},
+            }
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use dioxus::prelude::*;
+
+use crate::scheme::{Scheme, ZigenGroup};
+use crate::user_state::UserState;
+use crate::view::{TOOLS_CSS, load_zigen, load_zigen_fonts};
+
+#[derive(PartialEq, Clone, Props)]
+pub struct CardBrowserProps {
+    scheme: Scheme,
+    user_state: Signal<UserState>,
+    on_back: EventHandler<()>,
+}
+
+/// 归并组是否符合筛选条件：字根、编码或标签含有 filter 即可。
+fn matches(group: &ZigenGroup, user_tags: &[String], filter: &str) -> bool {
+    let filter = filter.trim().to_lowercase();
+
+    filter.is_empty()
+        || group.zigens.iter().any(|zigen| zigen.contains(&filter))
+        || group
+            .codes()
+            .any(|code| code.to_lowercase().starts_with(&filter))
+        || group
+            .tags
+            .iter()
+            .chain(user_tags)
+            .any(|tag| tag.contains(&filter))
+}
+
+/// 卡片浏览器：列出方案的所有归并组，并让用户为其添加或删除自定义标签。
+#[component]
+pub fn CardBrowser(mut props: CardBrowserProps) -> Element {
+    let mut filter = use_signal(String::new);
+    let mut new_tag = use_signal(String::new);
+    let mut error: Signal<Option<String>> = use_signal(|| None);
+
+    let scheme = props.scheme.clone();
+    let loaded = use_resource(move || {
+        let scheme = scheme.clone();
+        async move {
+            load_zigen_fonts(&scheme.zigen_font).await;
+            load_zigen(&scheme, props.user_state.into()).await
+        }
+    });
+
+    let scheme_id = props.scheme.id.clone();
+    let user_tags = use_memo(move || props.user_state.read().user_tags(&scheme_id));
+
+    let scheme_id = props.scheme.id.clone();
+    let add_tag = use_callback(move |roots: Vec<String>| {
+        let tag = new_tag.read().clone();
+        let mut user_state = props.user_state.write();
+
+        for root in roots.iter() {
+            if let Err(err) = user_state.add_user_tag(&scheme_id, root, &tag) {
+                error.set(Some(err));
+                return;
+            }
+        }
+
+        error.set(None);
+    });
+
+    let scheme_id = props.scheme.id.clone();
+
+    rsx! {
+        document::Link { rel: "stylesheet", href: TOOLS_CSS }
+
+        div {
+            class: "trainer-tool",
+
+            h1 {
+                "卡片与标签：{props.scheme.full_name}"
+            }
+
+            p {
+                "为字根加上自定义标签后，便可在练习设置中按标签筛选字根。标签只保存在当前浏览器中；方案自带的标签不能删除。"
+            }
+
+            div {
+                class: "trainer-tool-row",
+
+                input {
+                    class: "trainer-tool-select",
+                    r#type: "search",
+                    placeholder: "按字根、编码或标签筛选",
+                    value: "{filter}",
+                    oninput: move |event| filter.set(event.value()),
+                }
+
+                input {
+                    class: "trainer-tool-select",
+                    r#type: "text",
+                    placeholder: "要添加的标签",
+                    value: "{new_tag}",
+                    oninput: move |event| new_tag.set(event.value()),
+                }
+
+                button {
+                    class: "trainer-tool-button",
+                    onclick: move |_| (props.on_back)(()),
+                    "返回设置"
+                }
+            }
+
+            if let Some(error) = error() {
+                p { class: "trainer-tool-error", "{error}" }
+            }
+
+            match &*loaded.read_unchecked() {
+                Some(Ok(zigen)) => {
+                    let user_tags = user_tags.read();
+                    let no_tags = Vec::new();
+                    let rows = zigen
+                        .groups()
+                        .filter_map(|group| {
+                            let root = group.zigens.first()?.0.clone();
+                            let tags = user_tags.get(&root).unwrap_or(&no_tags).clone();
+                            matches(group, &tags, &filter.read()).then(|| (group.clone(), root, tags))
+                        })
+                        .collect::<Vec<_>>();
+                    let roots = rows.iter().map(|(_, root, _)| root.clone()).collect::<Vec<_>>();
+                    let count = rows.len();
+                    let no_new_tag = new_tag.read().trim().is_empty();
+
+                    rsx! {
+                        div {
+                            class: "trainer-tool-row",
+
+                            "共{count}个归并组"
+
+                            button {
+                                class: "trainer-tool-button",
+                                disabled: no_new_tag || count == 0,
+                                onclick: move |_| add_tag(roots.clone()),
+                                "为以上所有字根添加标签"
+                            }
+                        }
+
+                        table {
+                            class: "trainer-tool-table",
+
+                            thead {
+                                tr {
+                                    th { "字根" }
+                                    th { "编码" }
+                                    th { "分类" }
+                                    th { "标签" }
+                                    th {}
+                                }
+                            }
+
+                            tbody {
+                                for (group, root, tags) in rows.into_iter() {
+                                    tr {
+                                        td {
+                                            class: "trainer-tool-zigen",
+                                            {group.zigens.iter().map(|zigen| zigen.0.as_str()).collect::<Vec<_>>().join(" ")}
+                                        }
+                                        td { {group.codes().collect::<Vec<_>>().join(" / ")} }
+                                        td { "{group.classify.label()}" }
+                                        td {
+                                            for tag in group.tags.iter() {
+                                                span { class: "trainer-browser-tag", "{tag}" }
+                                            }
+                                            for tag in tags.into_iter() {
+                                                span {
+                                                    class: "trainer-browser-tag user",
+                                                    "{tag}"
+                                                    button {
+                                                        title: "删除标签",
+                                                        onclick: {
+                                                            let (scheme_id, root) = (scheme_id.clone(), root.clone());
+                                                            move |_| props.user_state.write().remove_user_tag(&scheme_id, &root, &tag)
+                                                        },
+                                                        "×"
+                                                    }
+                                                }
+                                            }
+                                        }
+                                        td {
+                                            button {
+                                                class: "trainer-tool-button",
+                                                disabled: no_new_tag,
+                                                onclick: move |_| add_tag(vec![root.clone()]),
+                                                "添加"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                Some(Err(e)) => rsx! {
+                    p {
+                        class: "trainer-tool-error",
+                        "数据加载失败！错误信息：{e}"
+                    }
+                },
+
+                None => rsx! {
+                    p {
+                        "数据加载中……"
+                    }
+                },
+            }
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use dioxus::prelude::*;
+
+use crate::user_state::UserState;
+use zigen_trainer::catalog::catalog_name;
+
+#[derive(PartialEq, Clone, Props)]
+pub struct CatalogsProps {
+    user_state: Signal<UserState>,
+}
+
+/// 让用户添加或删除第三方方案列表。
+#[component]
+pub fn Catalogs(mut props: CatalogsProps) -> Element {
+    let mut url = use_signal(String::new);
+    let mut error: Signal<Option<String>> = use_signal(|| None);
+
+    let catalogs = use_memo(move || props.user_state.read().catalogs().to_vec());
+
+    rsx! {
+        details {
+            class: "selector-local-scheme",
+
+            summary {
+                "添加方案列表"
+            }
+
+            p {
+                "方案列表的格式与练习器自带的 schemes.json 相同，其中的相对地址以方案列表的所在目录为根目录。"
+                "方案列表所在的服务器须允许跨域访问（CORS）。"
+            }
+
+            div {
+                class: "selector-local-scheme-row",
+
+                input {
+                    class: "selector-catalog-url",
+                    r#type: "url",
+                    placeholder: "https://example.com/schemes.json",
+                    value: "{url}",
+                    oninput: move |event| url.set(event.value()),
+                }
+
+                button {
+                    class: "selector-local-scheme-button",
+                    disabled: url.read().trim().is_empty(),
+                    onclick: move |_| {
+                        let result = props.user_state.write().add_catalog(&url.read());
+                        match result {
+                            Ok(()) => {
+                                url.set(String::new());
+                                error.set(None);
+                            }
+                            Err(err) => error.set(Some(err)),
+                        }
+                    },
+                    "添加"
+                }
+            }
+
+            if let Some(error) = error() {
+                p { class: "selector-local-scheme-error", "{error}" }
+            }
+
+            if !catalogs.read().is_empty() {
+                ul {
+                    for catalog in catalogs.read().iter().cloned() {
+                        li {
+                            title: "{catalog}",
+                            "{catalog_name(&catalog)}"
+                            button {
+                                class: "selector-local-scheme-remove",
+                                onclick: move |_| props.user_state.write().remove_catalog(&catalog),
+                                "删除"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use dioxus::prelude::*;
+
+use crate::scheme::{
+    KeySet, LoadedScheme, Scheme, SchemeOptions, SchemeZigen, ZigenConfusableUnpopulated,
+};
+use crate::user_state::UserState;
+use crate::view::flatten_zigen;
+
+/// 用户方案在方案选择界面中的分类。
+pub const LOCAL_CATEGORY: &str = "本地方案";
+
+#[derive(PartialEq, Clone, Props)]
+pub struct LocalSchemeProps {
+    user_state: Signal<UserState>,
+}
+
+/// 让用户打开本地的字根集（以及字体），登记为用户方案。
+#[component]
+pub fn LocalScheme(mut props: LocalSchemeProps) -> Element {
+    // (文件名, 文件内容)
+    let mut zigen_file: Signal<Option<(String, String)>> = use_signal(|| None);
+    // 字体的 data URL
+    let mut font_file: Signal<Option<String>> = use_signal(|| None);
+    let mut message: Signal<Option<Result<String, String>>> = use_signal(|| None);
+
+    let user_schemes = use_memo(move || {
+        props
+            .user_state
+            .read()
+            .user_schemes()
+            .map(|scheme| (scheme.id.clone(), scheme.full_name.clone()))
+            .collect::<Vec<_>>()
+    });
+
+    let register = move |_| async move {
+        let Some((name, source)) = zigen_file.read().clone() else {
+            message.set(Some(Err(String::from("请先选择字根集文件"))));
+            return;
+        };
+
+        // 叠加文件的 base 视为相对于内置字根集的所在目录
+        let url = format!("./assets/trainer/zigen/{name}");
+        let result = match flatten_zigen(url, source).await {
+            Ok(zigen) => {
+                let mut scheme = local_scheme(&name, font_file.read().clone());
+                scheme.keyboard = infer_keyboard(&zigen);
+                let full_name = scheme.full_name.clone();
+
+                props
+                    .user_state
+                    .write()
+                    .add_user_scheme(scheme, zigen)
+                    .map(|()| format!("已载入「{full_name}」，可在「{LOCAL_CATEGORY}」分类中选择"))
+            }
+            Err(err) => Err(err),
+        };
+
+        message.set(Some(result));
+    };
+
+    rsx! {
+        details {
+            class: "selector-local-scheme",
+
+            summary {
+                "打开本地字根集"
+            }
+
+            p {
+                "字根集的格式与内置方案相同，也可以是以内置字根集为基础的叠加文件。"
+                "载入的方案只保存在本浏览器中。再次载入同名文件会更新该方案，原有的进度会保留。"
+            }
+
+            div {
+                class: "selector-local-scheme-row",
+
+                label { "字根集：" }
+                input {
+                    r#type: "file",
+                    accept: "application/json",
+                    multiple: false,
+                    onchange: move |event| async move {
+                        let Some(file) = event.files().into_iter().next() else {
+                            return;
+                        };
+
+                        match file.read_string().await {
+                            Ok(content) => zigen_file.set(Some((file.name(), content))),
+                            Err(_) => message.set(Some(Err(String::from("无法读取字根集文件")))),
+                        }
+                    }
+                }
+            }
+
+            div {
+                class: "selector-local-scheme-row",
+
+                label { "字体（可选）：" }
+                input {
+                    r#type: "file",
+                    accept: ".ttf,.otf,.woff,.woff2",
+                    multiple: false,
+                    onchange: move |event| async move {
+                        let Some(file) = event.files().into_iter().next() else {
+                            font_file.set(None);
+                            return;
+                        };
+
+                        match file.read_bytes().await {
+                            Ok(bytes) => font_file.set(Some(font_data_url(
+                                &file.name(),
+                                file.content_type(),
+                                &bytes,
+                            ))),
+                            Err(_) => message.set(Some(Err(String::from("无法读取字体文件")))),
+                        }
+                    }
+                }
+            }
+
+            button {
+                class: "selector-local-scheme-button",
+                disabled: zigen_file.read().is_none(),
+                onclick: register,
+                "载入"
+            }
+
+            match &*message.read() {
+                Some(Ok(text)) => rsx! {
+                    p { class: "selector-local-scheme-ok", "{text}" }
+                },
+                Some(Err(text)) => rsx! {
+                    p { class: "selector-local-scheme-error", "载入失败：{text}" }
+                },
+                None => rsx! {},
+            }
+
+            if !user_schemes.read().is_empty() {
+                ul {
+                    for (id, name) in user_schemes.read().iter().cloned() {
+                        li {
+                            "{name}"
+                            button {
+                                class: "selector-local-scheme-remove",
+                                onclick: move |_| {
+                                    props.user_state.write().remove_user_scheme(&id);
+                                    message.set(None);
+                                },
+                                "删除"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 为本地字根集生成方案信息。方案ID由文件名决定，因此同名文件会覆盖原有的方案。
+fn local_scheme(file_name: &str, font: Option<String>) -> Scheme {
+    let name = file_name
+        .rsplit_once('.')
+        .map(|(stem, _)| stem)
+        .unwrap_or(file_name);
+
+    Scheme {
+        id: format!("local:{name}"),
+        full_name: name.to_owned(),
+        description: format!("本地字根集 {file_name}"),
+        author: String::new(),
+        maintainer: String::new(),
+        icon: name.chars().next().map(String::from).unwrap_or_default(),
+        category: vec![String::from(LOCAL_CATEGORY)],
+        zigen_url: String::new(),
+        zigen_font: font.into_iter().collect(),
+        version: String::new(),
+        changelog: String::new(),
+        code_structure: None,
+        keyboard: KeySet::default(),
+        default_options: SchemeOptions::recommended(),
+    }
+}
+
+/// 本地字根集没有按键定义，因此以26个字母加上编码中出现的其他字符为准。
+fn infer_keyboard(zigen: &LoadedScheme<ZigenConfusableUnpopulated>) -> KeySet {
+    let mut keyboard = KeySet::default();
+
+    let groups = zigen.0.iter().filter_map(|entry| match entry {
+        SchemeZigen::Cluster(cluster) => Some(cluster.groups.iter()),
+        SchemeZigen::Confusable(_) | SchemeZigen::Decomposition(_) => None,
+    });
+
+    for group in groups.flatten() {
+        for c in group.codes().flat_map(str::chars) {
+            if !c.is_whitespace() && keyboard.normalize(c).is_none() {
+                keyboard.keys.push(c);
+            }
+        }
+    }
+
+    keyboard
+}
+
+/// 把字体文件编码为 data URL，以便与方案一同保存。
+fn font_data_url(file_name: &str, content_type: Option<String>, bytes: &[u8]) -> String {
+    let mime = content_type
+        .filter(|mime| !mime.is_empty())
+        .unwrap_or_else(|| {
+            let extension = file_name.rsplit('.').next().unwrap_or_default();
+            format!("font/{}", extension.to_ascii_lowercase())
+        });
+
+    format!("data:{mime};base64,{}", STANDARD.encode(bytes))
+}
@@ -1,12 +1,19 @@
+mod card_browser;
+mod catalogs;
+mod local_scheme;
 mod scheme_selector;
 mod settings;
 
 use crate::scheme::{Scheme, SchemeOptions};
 use crate::user_state::UserState;
+use crate::view::fetch_catalog;
+use crate::view::welcome::card_browser::CardBrowser;
+use crate::view::welcome::catalogs::Catalogs;
+use crate::view::welcome::local_scheme::LocalScheme;
 use crate::view::welcome::scheme_selector::SchemeSelector;
 use crate::view::welcome::settings::Settings;
 use dioxus::prelude::*;
-use gloo_net::http::Request;
+use zigen_trainer::catalog::BUNDLED_CATALOG;
 
 const SCHEME_SELECTOR_CSS: Asset = asset!("/assets/scheme_selector.css");
 const SCHEME_SETTINGS_CSS: Asset = asset!("/assets/scheme_settings.css");
@@ -21,6 +28,7 @@ pub struct WelcomeProps {
 enum WelcomeState {
     ChooseScheme,
     Settings,
+    CardBrowser,
 }
 
 #[component]
@@ -28,16 +36,23 @@ pub fn Welcome(mut props: WelcomeProps) -> Element {
     let mut selected_scheme = use_signal(String::new);
     let mut state = use_signal(|| WelcomeState::ChooseScheme);
 
+    // 只在方案列表有所增减时才重新加载
+    let catalogs = use_memo(move || props.user_state.read().catalogs().to_vec());
+
     let schemes_loader = {
         use_resource(move || {
             async move {
-                let schemes = Request::get("./assets/trainer/schemes.json")
-                    .send()
-                    .await
-                    .map_err(|err| err.to_string())?
-                    .json::<Vec<Scheme>>()
-                    .await
-                    .map_err(|err| err.to_string());
+                let catalogs = catalogs();
+                let mut schemes = fetch_catalog(BUNDLED_CATALOG, false).await?;
+
+                // 第三方方案列表加载失败时，只显示警告，不影响其他方案
+                let mut warnings = Vec::new();
+                for url in catalogs.iter() {
+                    match fetch_catalog(url, true).await {
+                        Ok(extra) => schemes.extend(extra),
+                        Err(err) => warnings.push(format!("无法加载方案列表「{url}」：{err}")),
+                    }
+                }
 
                 // let user_state = props.user_state.read();
                 // let user_scheme = user_state.current_scheme();
@@ -52,7 +67,7 @@ pub fn Welcome(mut props: WelcomeProps) -> Element {
                 //     }
                 // }
 
-                schemes
+                Ok::<_, String>((schemes, warnings))
             }
         })
     };
@@ -60,9 +75,22 @@ pub fn Welcome(mut props: WelcomeProps) -> Element {
     let schemes = use_memo(move || {
         let loaded_schemes = schemes_loader.read().clone();
 
-        loaded_schemes
+        let mut schemes = loaded_schemes
             .and_then(|loaded| loaded.ok())
-            .unwrap_or(Vec::new())
+            .map(|(schemes, _)| schemes)
+            .unwrap_or(Vec::new());
+
+        // 用户方案排在内置方案之后
+        schemes.extend(props.user_state.read().user_schemes().cloned());
+        schemes
+    });
+
+    let selected = use_memo(move || {
+        schemes
+            .read()
+            .iter()
+            .find(|scheme| scheme.id == *selected_scheme.read())
+            .cloned()
     });
 
     use_effect(move || {
@@ -93,7 +121,7 @@ pub fn Welcome(mut props: WelcomeProps) -> Element {
 
             match *schemes_loader.read_unchecked() {
                 // 加载成功
-                Some(Ok(_)) => {
+                Some(Ok((_, ref warnings))) => {
                     match state() {
                         WelcomeState::ChooseScheme => rsx! {
                             SchemeSelector {
@@ -112,29 +140,57 @@ pub fn Welcome(mut props: WelcomeProps) -> Element {
                                             .find(|scheme| scheme.id == selected_scheme_id)
                                             .cloned()
                                             .unwrap();
-                                        (props.on_scheme_selected)((scheme, SchemeOptions::default()))
+                                        // 「继续上次练习」跳过设置页面，因此直接使用方案推荐的设置
+                                        let options = scheme.default_options.clone();
+                                        (props.on_scheme_selected)((scheme, options))
                                     }
                                 }
                             }
+
+                            for warning in warnings.iter() {
+                                p {
+                                    class: "selector-catalog-warning",
+                                    "{warning}"
+                                }
+                            }
+
+                            LocalScheme {
+                                user_state: props.user_state,
+                            }
+
+                            Catalogs {
+                                user_state: props.user_state,
+                            }
                         },
 
                         WelcomeState::Settings => rsx! {
-                            Settings {
-                                selected_scheme,
-                                user_state: props.user_state,
-                                on_back: move || state.set(WelcomeState::ChooseScheme),
-                                on_confirm: move |(opts, reset)| {
-                                    let scheme = schemes
-                                        .read()
-                                        .iter()
-                                        .find(|scheme| scheme.id == *selected_scheme.read())
-                                        .cloned()
-                                        .unwrap();
-                                    if reset {
-                                        props.user_state.write().reset_progress(&scheme.id);
-                                    }
-                                    (props.on_scheme_selected)((scheme, opts))
-                                },
+                            if let Some(scheme) = selected() {
+                                Settings {
+                                    selected_scheme,
+                                    scheme,
+                                    user_state: props.user_state,
+                                    on_back: move || state.set(WelcomeState::ChooseScheme),
+                                    on_browse: move || state.set(WelcomeState::CardBrowser),
+                                    on_confirm: move |(opts, reset)| {
+                                        let Some(scheme) = selected() else {
+                                            return;
+                                        };
+                                        if reset {
+                                            props.user_state.write().reset_progress(&scheme.id);
+                                        }
+                                        (props.on_scheme_selected)((scheme, opts))
+                                    },
+                                }
+                            }
+                        },
+
+                        WelcomeState::CardBrowser => rsx! {
+                            if let Some(scheme) = selected() {
+                                CardBrowser {
+                                    scheme,
+                                    user_state: props.user_state,
+                                    on_back: move || state.set(WelcomeState::Settings),
+                                }
                             }
                         }
                     }
@@ -1,6 +1,7 @@
 use std::collections::BTreeMap;
 
 use crate::scheme::Scheme;
+use crate::user_state::UserState;
 
 use super::CategoryNode;
 use dioxus::prelude::*;
@@ -9,6 +10,7 @@ use dioxus::prelude::*;
 pub struct CategoryProps {
     category: ReadSignal<CategoryNode>,
     schemes: ReadSignal<Vec<Scheme>>,
+    user_state: ReadSignal<UserState>,
     on_scheme_selected: EventHandler<String>,
 }
 
@@ -17,11 +19,15 @@ struct SchemeInfo {
     id: String,
     name: String,
     icon: String,
+    /// 用户开始练习后，字根集是否已经更新
+    updated: bool,
 }
 
 #[component]
 pub fn Category(props: CategoryProps) -> Element {
     let schemes = use_memo(move || {
+        let user_state = props.user_state.read();
+
         props
             .schemes
             .read()
@@ -44,6 +50,7 @@ pub fn Category(props: CategoryProps) -> Element {
                         id: scheme.id.to_owned(),
                         name: scheme.full_name.to_owned(),
                         icon: scheme.icon.to_owned(),
+                        updated: user_state.is_progress_outdated(scheme),
                     },
                 )
             })
@@ -61,10 +68,12 @@ pub fn Category(props: CategoryProps) -> Element {
     let selected_scheme_name = use_memo(move || {
         if !selected_scheme.read().is_empty() {
             let schemes = props.schemes.read();
-            let scheme = schemes
+            // 用户方案可能在选中后被删除
+            schemes
                 .iter()
-                .find(|scheme| scheme.id == *selected_scheme.read());
-            scheme.unwrap().full_name.clone()
+                .find(|scheme| scheme.id == *selected_scheme.read())
+                .map(|scheme| scheme.full_name.clone())
+                .unwrap_or_default()
         } else {
             String::new()
         }
@@ -107,6 +116,14 @@ pub fn Category(props: CategoryProps) -> Element {
                                 class: "selector-scheme-card-name",
                                 "{info.name}"
                             }
+
+                            if info.updated {
+                                span {
+                                    class: "selector-scheme-card-badge",
+                                    title: "开始练习后，该方案的字根集已经更新",
+                                    "已更新"
+                                }
+                            }
                         }
                     }
                 }
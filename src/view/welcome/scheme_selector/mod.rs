@@ -89,6 +89,7 @@ pub fn SchemeSelector(props: SchemeSelectorProps) -> Element {
         Category {
             category: selected_main_category,
             schemes: props.schemes,
+            user_state: props.user_state,
             on_scheme_selected: move |scheme_id| (props.on_scheme_selected)((scheme_id, false)),
         }
     }
@@ -1,16 +1,23 @@
 mod setting_option;
 
+use std::collections::BTreeSet;
+
 use dioxus::prelude::*;
 
-use crate::scheme::{CombineMode, SchemeOptions};
+use crate::scheme::{CombineMode, Scheme, SchemeOptions};
 use crate::user_state::UserState;
-use setting_option::{BooleanSetting, DropdownSetting, TextboxSetting};
+use crate::view::load_zigen;
+use setting_option::{BooleanSetting, ButtonSetting, DropdownSetting, TagSetting, TextboxSetting};
 
 #[derive(Clone, Debug, PartialEq, Props)]
 pub struct SettingsProp {
     selected_scheme: ReadSignal<String>,
+    /// 所选方案，用于解读「仅训练键面」与列出可供筛选的标签
+    scheme: Scheme,
     user_state: ReadSignal<UserState>,
     on_back: EventHandler<()>,
+    /// 打开卡片浏览器
+    on_browse: EventHandler<()>,
     /// 参数：方案设置、是否重置
     on_confirm: EventHandler<(SchemeOptions, bool)>,
 }
@@ -23,18 +30,62 @@ pub fn Settings(props: SettingsProp) -> Element {
         user_state.has_progress(&selected_scheme)
     });
 
-    let shuffle = use_signal(|| true);
-    let combined_training = use_signal(|| false);
-    let prioritize_trad = use_signal(|| false);
-    let adept = use_signal(|| false);
-    let combined_mode_str = use_signal(String::new);
+    // 以方案推荐的设置预先填写
+    let defaults = props.scheme.default_options.clone();
+
+    let shuffle = use_signal(|| defaults.shuffle);
+    let combined_training = use_signal(|| defaults.combined_training);
+    let prioritize_trad = use_signal(|| defaults.prioritize_trad);
+    let adept = use_signal(|| defaults.adept);
+    let combined_mode_str = use_signal(|| {
+        String::from(match defaults.combine_mode {
+            CombineMode::Cluster => "cluster",
+            CombineMode::Group => "group",
+            CombineMode::None => "none",
+        })
+    });
     let combine_mode = use_memo(move || match combined_mode_str.read().as_str() {
         "group" => CombineMode::Group,
         "none" => CombineMode::None,
-        "cluster" | _ => CombineMode::Cluster,
+        _ => CombineMode::Cluster,
+    });
+    let limit_keys = use_signal(|| {
+        defaults
+            .limit_keys
+            .as_ref()
+            .map(|keys| keys.iter().collect::<String>())
+            .unwrap_or_default()
+    });
+    let v2_sched = use_signal(|| defaults.v2_sched);
+    let examples_as_prompt = use_signal(|| defaults.examples_as_prompt);
+    let include_tags = use_signal(|| defaults.include_tags.clone());
+    let exclude_tags = use_signal(|| defaults.exclude_tags.clone());
+
+    // 字根集内的标签，加上用户自定义的标签
+    let scheme = props.scheme.clone();
+    let available_tags = use_resource(move || {
+        let scheme = scheme.clone();
+        async move {
+            let loaded = load_zigen(&scheme, props.user_state).await.ok()?;
+
+            let mut tags = loaded
+                .tags()
+                .into_iter()
+                .map(String::from)
+                .collect::<BTreeSet<_>>();
+            tags.extend(
+                props
+                    .user_state
+                    .peek()
+                    .user_tags(&scheme.id)
+                    .into_values()
+                    .flatten(),
+            );
+
+            Some(tags.into_iter().collect::<Vec<_>>())
+        }
     });
-    let limit_keys = use_signal(String::new);
-    let v2_sched = use_signal(|| false);
+    let keyboard = props.scheme.keyboard.clone();
 
     let mut confirm_reset = use_signal(|| false);
     let mut show_advanced = use_signal(|| false);
@@ -84,6 +135,12 @@ pub fn Settings(props: SettingsProp) -> Element {
                     value: adept,
                 }
 
+                BooleanSetting {
+                    name: "例字提示",
+                    description: "作答前便显示含有该字根的例字。（仅适用于提供了例字的方案）",
+                    value: examples_as_prompt,
+                }
+
                 // 高级设置
                 div {
                     class: "scheme-settings-advanced-section-container",
@@ -139,6 +196,25 @@ pub fn Settings(props: SettingsProp) -> Element {
                             value: limit_keys,
                         }
 
+                        if let Some(Some(tags)) = available_tags()
+                            && !tags.is_empty()
+                        {
+                            TagSetting {
+                                name: "标签筛选",
+                                description: "只练习带有「只练」标签的字根（若有），并跳过带有「排除」标签的字根。",
+                                tags,
+                                include: include_tags,
+                                exclude: exclude_tags,
+                            }
+                        }
+
+                        ButtonSetting {
+                            name: "卡片与标签",
+                            description: "浏览本方案的所有字根，并为其添加自定义标签。",
+                            label: "浏览卡片",
+                            onclick: move |_| (props.on_browse)(()),
+                        }
+
                         BooleanSetting {
                             name: "使用新型调度器（BETA）",
                             description: "（开发中。）",
@@ -172,11 +248,14 @@ pub fn Settings(props: SettingsProp) -> Element {
                                 adept: adept(),
                                 combine_mode: combine_mode(),
                                 limit_keys: if !limit_keys.read().is_empty() {
-                                    Some(limit_keys.read().chars().map(|c| c.to_ascii_uppercase()).collect())
+                                    Some(limit_keys.read().chars().filter_map(|c| keyboard.normalize(c)).collect())
                                 } else {
                                     None
                                 },
-                                v2_sched: v2_sched()
+                                v2_sched: v2_sched(),
+                                examples_as_prompt: examples_as_prompt(),
+                                include_tags: include_tags(),
+                                exclude_tags: exclude_tags(),
                             };
 
                             if !has_existing_session() {
@@ -72,9 +72,10 @@ pub fn DropdownSetting(
                     value.set(event.value());
                 },
 
-                for (value, label) in options.iter() {
+                for (option_value, label) in options.iter() {
                     option {
-                        value,
+                        value: *option_value,
+                        selected: *value.read() == *option_value,
                         "{label}"
                     }
                 }
@@ -99,8 +100,88 @@ pub fn TextboxSetting(
                 class: "scheme-setting-textbox",
                 r#type: "text",
                 placeholder,
+                value: "{value}",
                 oninput: move |event| { value.set(event.value()) },
             }
         }
     }
 }
+
+/// 按标签筛选字根。每个标签都可以设为「不限」「只练」或「排除」。
+#[component]
+pub fn TagSetting(
+    name: &'static str,
+    description: &'static str,
+    tags: Vec<String>,
+    mut include: Signal<Vec<String>>,
+    mut exclude: Signal<Vec<String>>,
+) -> Element {
+    rsx! {
+        Setting {
+            name,
+            description,
+
+            div {
+                class: "scheme-setting-tags",
+
+                for (tag, included, excluded) in tags.iter().map(|tag| {
+                    (tag.clone(), include.read().contains(tag), exclude.read().contains(tag))
+                }) {
+                    label {
+                        class: "scheme-setting-tag",
+
+                        span { "{tag}" }
+
+                        select {
+                            class: "scheme-setting-dropdown",
+
+                            onchange: move |event| {
+                                include.write().retain(|t| *t != tag);
+                                exclude.write().retain(|t| *t != tag);
+
+                                match event.value().as_str() {
+                                    "include" => include.write().push(tag.clone()),
+                                    "exclude" => exclude.write().push(tag.clone()),
+                                    _ => {}
+                                }
+                            },
+
+                            option { value: "any", "不限" }
+                            option {
+                                value: "include",
+                                selected: included,
+                                "只练"
+                            }
+                            option {
+                                value: "exclude",
+                                selected: excluded,
+                                "排除"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn ButtonSetting(
+    name: &'static str,
+    description: &'static str,
+    label: &'static str,
+    onclick: EventHandler<()>,
+) -> Element {
+    rsx! {
+        Setting {
+            name,
+            description,
+
+            button {
+                class: "selector-confirm-button",
+                onclick: move |_| onclick(()),
+                "{label}"
+            }
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! 判断作答是否正确、犯了哪种错误的测试。
+
+use zigen_trainer::scheduler::{Mistake, Rating, find_mistake};
+use zigen_trainer::scheme::{CodeStructure, KeySet, ZigenGroup};
+
+fn keyboard() -> KeySet {
+    serde_json::from_str(
+        r#"{ "keys": "abcdefghijklmnopqrstuvwxyz;,./", "aliases": { "；": ";", "，": "," } }"#,
+    )
+    .unwrap()
+}
+
+fn group(code: &str, alt_codes: &[&str]) -> ZigenGroup {
+    serde_json::from_value(serde_json::json!({
+        "zigens": ["口"],
+        "code": code,
+        "alt_codes": alt_codes,
+        "classify": "通",
+        "description": "",
+    }))
+    .unwrap()
+}
+
+#[test]
+fn answers_are_case_insensitive() {
+    let keyboard = keyboard();
+    let group = group("Kk", &[]);
+
+    assert!(group.accepts(&keyboard, "kk"));
+    assert!(group.accepts(&keyboard, "KK"));
+    assert!(!group.accepts(&keyboard, "kj"));
+    assert_eq!(group.accepted_codes(&keyboard), vec!["kk"]);
+}
+
+#[test]
+fn aliases_count_as_their_keys() {
+    let keyboard = keyboard();
+    let group = group(";k", &[]);
+
+    assert!(group.accepts(&keyboard, "；k"));
+    assert!(group.accepts(&keyboard, ";K"));
+    assert!(!group.accepts(&keyboard, "，k"));
+}
+
+#[test]
+fn alternative_codes_are_accepted() {
+    let keyboard = keyboard();
+    let group = group("Kk", &["Kj", "Ki"]);
+
+    assert!(group.accepts(&keyboard, "kk"));
+    assert!(group.accepts(&keyboard, "kj"));
+    assert!(group.accepts(&keyboard, "KI"));
+    assert!(!group.accepts(&keyboard, "kl"));
+    assert_eq!(group.accepted_codes(&keyboard), vec!["kk", "kj", "ki"]);
+}
+
+#[test]
+fn codes_split_into_major_and_minor_parts() {
+    let structure = CodeStructure { major_len: 1 };
+
+    assert_eq!(structure.split("Kk"), ("K", "k"));
+    assert_eq!(structure.split("K"), ("K", ""));
+    assert_eq!(structure.split(""), ("", ""));
+    assert_eq!(CodeStructure { major_len: 2 }.split("；ka"), ("；k", "a"));
+}
+
+#[test]
+fn mistakes_are_graded_by_code_part() {
+    let structure = CodeStructure { major_len: 1 };
+    let accepted = vec![
+        vec![String::from("kk")],
+        vec![String::from("jr"), String::from("jd")],
+    ];
+    let answers = |a: &str, b: &str| vec![String::from(a), String::from(b)];
+
+    assert_eq!(
+        find_mistake(&answers("kk", "jd"), &accepted, Some(&structure)),
+        None
+    );
+
+    // 大码正确而小码错误：最终答对时评为 Hard
+    let minor = find_mistake(&answers("kk", "jx"), &accepted, Some(&structure));
+    assert_eq!(minor, Some(Mistake::Minor));
+    assert_eq!(minor.unwrap().rating(), Rating::Hard);
+
+    // 任一组大码错误便算大码错误：评为 Again
+    let major = find_mistake(&answers("kx", "xr"), &accepted, Some(&structure));
+    assert_eq!(major, Some(Mistake::Major));
+    assert_eq!(major.unwrap().rating(), Rating::Again);
+
+    // 不区分大小码的方案，任何错误都算大码错误
+    assert_eq!(
+        find_mistake(&answers("kk", "jx"), &accepted, None),
+        Some(Mistake::Major)
+    );
+}
+
+#[test]
+fn revealed_answers_are_rated_again() {
+    assert_eq!(Mistake::Revealed.rating(), Rating::Again);
+
+    // 查看答案后再犯小码错误，仍按查看答案计算
+    assert_eq!(
+        Some(Mistake::Revealed).max(Some(Mistake::Minor)),
+        Some(Mistake::Revealed)
+    );
+}
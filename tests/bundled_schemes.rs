@@ -0,0 +1,331 @@
+//! 加载所有内置方案，确认 populate_confusables 与 sort_to_options 在任何设置组合下都能正常工作。
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+use zigen_trainer::scheme::{
+    CombineMode, LoadedScheme, SchemeOptions, SchemeZigen, ZigenClass, ZigenConfusable,
+    ZigenConfusableUnpopulated, ZigenGroup, card_ids,
+};
+
+const LIMIT_KEYS: &[char] = &['A', 'G', 'Q', 'X'];
+
+fn bundled_schemes() -> Vec<(String, LoadedScheme<ZigenConfusableUnpopulated>)> {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets/trainer/zigen");
+
+    let mut schemes = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .map(|path| {
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
+            let source = std::fs::read_to_string(&path).unwrap();
+            let scheme = serde_json::from_str(&source)
+                .unwrap_or_else(|err| panic!("{name} 无法解析：{err}"));
+            (name, scheme)
+        })
+        .collect::<Vec<_>>();
+
+    schemes.sort_by(|(a, _), (b, _)| a.cmp(b));
+    schemes
+}
+
+/// 测试用的标签，由 tag_every_other_group 加在一半的归并组上。
+const TAG: &str = "测试";
+
+/// 没有任何归并组带有的标签。只练这个标签时，卡组应为空。
+const MISSING_TAG: &str = "不存在的标签";
+
+/// 为每隔一个归并组加上 TAG，使标签筛选有东西可以筛。
+fn tag_every_other_group(scheme: &mut LoadedScheme<ZigenConfusableUnpopulated>) {
+    let tags = scheme
+        .groups()
+        .step_by(2)
+        .filter_map(|group| group.zigens.first())
+        .map(|root| (root.0.clone(), vec![TAG.to_owned()]))
+        .collect::<BTreeMap<_, _>>();
+
+    scheme.apply_tags(&tags);
+}
+
+fn all_options() -> Vec<SchemeOptions> {
+    let mut options = Vec::new();
+
+    // 最后一项筛掉所有卡片，用于检查空卡组
+    let tag_filters = [
+        (Vec::new(), Vec::new()),
+        (vec![TAG.to_owned()], Vec::new()),
+        (Vec::new(), vec![TAG.to_owned()]),
+        (vec![MISSING_TAG.to_owned()], Vec::new()),
+    ];
+
+    // 复习模式、V2调度器与例字提示只影响练习时的调度与显示，不影响 sort_to_options
+    for bits in 0..(1 << 3) {
+        for combine_mode in [CombineMode::Cluster, CombineMode::Group, CombineMode::None] {
+            for limit_keys in [None, Some(LIMIT_KEYS.to_vec())] {
+                for (include_tags, exclude_tags) in tag_filters.iter().cloned() {
+                    options.push(SchemeOptions {
+                        shuffle: bits & 1 != 0,
+                        combined_training: bits & 2 != 0,
+                        prioritize_trad: bits & 4 != 0,
+                        combine_mode: combine_mode.clone(),
+                        limit_keys: limit_keys.clone(),
+                        include_tags,
+                        exclude_tags,
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+    }
+
+    options
+}
+
+fn is_allowed(group: &ZigenGroup, options: &SchemeOptions) -> bool {
+    let key_allowed = match &options.limit_keys {
+        Some(keys) => keys.contains(&group.code.chars().next().unwrap().to_ascii_uppercase()),
+        None => true,
+    };
+    let tagged = |tags: &[String]| tags.iter().any(|tag| group.tags.contains(tag));
+
+    key_allowed
+        && (options.include_tags.is_empty() || tagged(&options.include_tags))
+        && !tagged(&options.exclude_tags)
+}
+
+/// 卡片应处于的练习阶段：0为常用字根，1为另一字形的字根，2为罕用字根，3为混淆集。
+fn class_rank(group: &ZigenGroup, options: &SchemeOptions) -> usize {
+    let outlier = if options.prioritize_trad {
+        ZigenClass::Simplified
+    } else {
+        ZigenClass::Traditional
+    };
+
+    match group.classify {
+        ZigenClass::Uncommon => 2,
+        ref class if *class == outlier && !options.combined_training => 1,
+        _ => 0,
+    }
+}
+
+/// 把聚类卡片展开成（字根，编码）的计数表。
+fn count_roots<'a>(
+    groups: impl Iterator<Item = &'a ZigenGroup>,
+) -> HashMap<(String, String), usize> {
+    let mut counts = HashMap::new();
+
+    for group in groups {
+        for zigen in group.zigens.iter() {
+            *counts
+                .entry((zigen.0.clone(), group.code.clone()))
+                .or_default() += 1;
+        }
+    }
+
+    counts
+}
+
+fn check_invariants(
+    name: &str,
+    populated: &LoadedScheme<ZigenConfusable>,
+    options: &SchemeOptions,
+) {
+    let mut sorted = populated.clone();
+    sorted.sort_to_options(options);
+
+    let context = format!("{name}（{options:?}）");
+
+    // 筛掉所有卡片时应得到空卡组，练习器据此提示“无练习卡片”
+    if options.include_tags == [MISSING_TAG] {
+        assert!(sorted.0.is_empty(), "{context} 筛选后仍有卡片");
+    }
+
+    // 不可出现空卡片
+    for card in sorted.0.iter() {
+        let (groups, _) = card.as_raw_parts();
+        assert!(!groups.is_empty(), "{context} 出现了空卡片");
+        assert!(
+            groups.iter().all(|group| !group.zigens.is_empty()),
+            "{context} 出现了空归并组"
+        );
+    }
+
+    // 只能出现规定键面上的字根
+    for card in sorted.0.iter() {
+        let (groups, _) = card.as_raw_parts();
+        assert!(
+            groups.iter().all(|group| is_allowed(group, options)),
+            "{context} 出现了规定键面或标签以外的字根"
+        );
+    }
+
+    // 字根不可遗漏，也不可重复
+    let expected = count_roots(
+        populated
+            .0
+            .iter()
+            .filter_map(|zigen| match zigen {
+                SchemeZigen::Cluster(cluster) => Some(cluster.groups.iter()),
+                SchemeZigen::Confusable(_) | SchemeZigen::Decomposition(_) => None,
+            })
+            .flatten()
+            .filter(|group| is_allowed(group, options)),
+    );
+    let actual = count_roots(
+        sorted
+            .0
+            .iter()
+            .filter_map(|zigen| match zigen {
+                SchemeZigen::Cluster(cluster) => Some(cluster.groups.iter()),
+                SchemeZigen::Confusable(_) | SchemeZigen::Decomposition(_) => None,
+            })
+            .flatten(),
+    );
+    assert_eq!(expected, actual, "{context} 字根遗漏或重复");
+
+    let expected_confusables = populated
+        .0
+        .iter()
+        .filter(|zigen| match zigen {
+            SchemeZigen::Confusable(_) => zigen
+                .as_raw_parts()
+                .0
+                .iter()
+                .all(|group| is_allowed(group, options)),
+            SchemeZigen::Cluster(_) | SchemeZigen::Decomposition(_) => false,
+        })
+        .count();
+    let actual_confusables = sorted
+        .0
+        .iter()
+        .filter(|zigen| matches!(zigen, SchemeZigen::Confusable(_)))
+        .count();
+    assert_eq!(
+        expected_confusables, actual_confusables,
+        "{context} 混淆集遗漏或重复"
+    );
+
+    // 练习阶段必须依次为：常用字根、另一字形的字根、罕用字根、混淆集、拆分
+    let mut last_rank = 0;
+    for card in sorted.0.iter() {
+        let rank = match card {
+            SchemeZigen::Decomposition(_) => 4,
+            SchemeZigen::Confusable(_) => 3,
+            SchemeZigen::Cluster(cluster) => {
+                let rank = class_rank(&cluster.groups[0], options);
+                assert!(
+                    cluster
+                        .groups
+                        .iter()
+                        .all(|group| class_rank(group, options) == rank),
+                    "{context} 同一张卡片内混杂了不同练习阶段的字根"
+                );
+                rank
+            }
+        };
+
+        assert!(rank >= last_rank, "{context} 字根分类的顺序有误");
+        last_rank = rank;
+    }
+}
+
+#[test]
+fn all_bundled_schemes_are_loaded() {
+    let schemes = bundled_schemes();
+    assert_eq!(schemes.len(), 13);
+}
+
+#[test]
+fn bundled_schemes_populate_confusables() {
+    for (name, scheme) in bundled_schemes() {
+        if let Err(err) = scheme.populate_confusables() {
+            panic!("{name}: {err}");
+        }
+    }
+}
+
+#[test]
+fn bundled_schemes_sort_to_all_options() {
+    let options = all_options();
+
+    for (name, mut scheme) in bundled_schemes() {
+        tag_every_other_group(&mut scheme);
+        let populated = scheme.populate_confusables().unwrap();
+
+        for options in options.iter() {
+            check_invariants(&name, &populated, options);
+        }
+    }
+}
+
+/// 把所有编码的大码与小码对调，模拟方案改版时重新编码。
+fn recode(scheme: &mut LoadedScheme<ZigenConfusableUnpopulated>) {
+    for zigen in scheme.0.iter_mut() {
+        if let SchemeZigen::Cluster(cluster) = zigen {
+            for group in cluster.groups.iter_mut() {
+                group.code = group.code.chars().rev().collect();
+            }
+        }
+    }
+}
+
+/// 以 options 排列卡片，返回卡片ID到卡片的对应。ID重复时测试失败。
+fn cards_by_id(
+    name: &str,
+    scheme: &LoadedScheme<ZigenConfusableUnpopulated>,
+    options: &SchemeOptions,
+) -> HashMap<String, SchemeZigen> {
+    let mut cards = scheme.clone().populate_confusables().unwrap();
+    cards.sort_to_options(options);
+
+    let ids = card_ids(&cards.0);
+    let count = ids.len();
+    let by_id = ids.into_iter().zip(cards.0).collect::<HashMap<_, _>>();
+    assert_eq!(by_id.len(), count, "{name}: 卡片ID重复（{options:?}）");
+    by_id
+}
+
+#[test]
+fn bundled_card_ids_are_unique_and_stable() {
+    for (name, scheme) in bundled_schemes() {
+        let mut recoded = scheme.clone();
+        recode(&mut recoded);
+
+        for combine_mode in [CombineMode::Cluster, CombineMode::Group, CombineMode::None] {
+            for bits in 0..4 {
+                let options = SchemeOptions {
+                    combine_mode: combine_mode.clone(),
+                    combined_training: bits & 1 != 0,
+                    prioritize_trad: bits & 2 != 0,
+                    shuffle: false,
+                    ..Default::default()
+                };
+                let ordered = cards_by_id(&name, &scheme, &options);
+
+                // 卡片ID不应随练习顺序改变
+                let shuffled = cards_by_id(
+                    &name,
+                    &scheme,
+                    &SchemeOptions {
+                        shuffle: true,
+                        ..options.clone()
+                    },
+                );
+                assert_eq!(ordered, shuffled, "{name}: 卡片ID随顺序改变（{options:?}）");
+
+                // 也不应随编码改变
+                let mut ordered_ids = ordered.into_keys().collect::<Vec<_>>();
+                let mut recoded_ids = cards_by_id(&name, &recoded, &options)
+                    .into_keys()
+                    .collect::<Vec<_>>();
+                ordered_ids.sort();
+                recoded_ids.sort();
+                assert_eq!(
+                    ordered_ids, recoded_ids,
+                    "{name}: 卡片ID随编码改变（{options:?}）"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,270 @@
+//! 方案列表中的地址解析与命名空间的测试。
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+
+use zigen_trainer::catalog::{self, BUNDLED_CATALOG};
+use zigen_trainer::scheme::{LoadedScheme, Scheme, SchemeOptions, ZigenConfusableUnpopulated};
+
+fn scheme(id: &str, zigen_url: &str, zigen_font: &str) -> Scheme {
+    Scheme {
+        id: id.into(),
+        full_name: String::new(),
+        description: String::new(),
+        author: String::new(),
+        maintainer: String::new(),
+        icon: String::from("🌠"),
+        category: vec![String::from("宇浩系列")],
+        zigen_url: zigen_url.into(),
+        zigen_font: Some(zigen_font.to_owned())
+            .filter(|font| !font.is_empty())
+            .into_iter()
+            .collect(),
+        version: String::new(),
+        changelog: String::new(),
+        code_structure: None,
+        keyboard: Default::default(),
+        default_options: Default::default(),
+    }
+}
+
+#[test]
+fn bundled_catalog_is_resolved_in_place() {
+    let resolved = catalog::resolve_catalog(
+        BUNDLED_CATALOG,
+        vec![scheme("star", "./zigen/star.json", "")],
+        false,
+    );
+
+    assert_eq!(resolved[0].id, "star");
+    assert_eq!(resolved[0].zigen_url, "./assets/trainer/zigen/star.json");
+    assert!(resolved[0].zigen_font.is_empty());
+    assert_eq!(resolved[0].category, vec![String::from("宇浩系列")]);
+}
+
+#[test]
+fn third_party_catalogs_are_namespaced() {
+    let url = "http://127.0.0.1:8000/schemes/schemes.json";
+    let resolved = catalog::resolve_catalog(
+        url,
+        vec![
+            scheme("star", "zigen/star.json", "../fonts/star.woff"),
+            scheme(
+                "ming",
+                "https://cdn.example.com/ming.json",
+                "data:font/woff;base64,AAAA",
+            ),
+        ],
+        true,
+    );
+
+    assert_eq!(catalog::catalog_name(url), "127.0.0.1:8000");
+
+    assert_eq!(resolved[0].id, format!("{url}#star"));
+    assert_eq!(
+        resolved[0].zigen_url,
+        "http://127.0.0.1:8000/schemes/zigen/star.json"
+    );
+    assert_eq!(
+        resolved[0].zigen_font,
+        vec![String::from("http://127.0.0.1:8000/fonts/star.woff")]
+    );
+    assert_eq!(
+        resolved[0].category,
+        vec![String::from("127.0.0.1:8000"), String::from("宇浩系列")]
+    );
+
+    assert_eq!(resolved[1].zigen_url, "https://cdn.example.com/ming.json");
+    assert_eq!(
+        resolved[1].zigen_font,
+        vec![String::from("data:font/woff;base64,AAAA")]
+    );
+}
+
+#[test]
+fn urls_stay_within_the_catalog_origin() {
+    let url = "https://example.com/catalogs/v2/schemes.json?v=3#top";
+
+    // 以「/」开头的路径相对于方案列表的来源，而不是练习器的来源
+    assert_eq!(
+        catalog::resolve_url(url, "/fonts/x.woff"),
+        "https://example.com/fonts/x.woff"
+    );
+    assert_eq!(
+        catalog::resolve_url(url, "./zigen/../zigen/star.json"),
+        "https://example.com/catalogs/v2/zigen/star.json"
+    );
+
+    // 「../」再多也不会越过主机名
+    assert_eq!(
+        catalog::resolve_url(url, "../../../../evil.example.net/x.json"),
+        "https://example.com/evil.example.net/x.json"
+    );
+    assert_eq!(
+        catalog::resolve_url("http://127.0.0.1:8000", "../x.json"),
+        "http://127.0.0.1:8000/x.json"
+    );
+
+    // 协议相对URL与自带方案列表中以「/」开头的路径保持不变
+    assert_eq!(
+        catalog::resolve_url(url, "//cdn.example.com/x.woff"),
+        "//cdn.example.com/x.woff"
+    );
+    assert_eq!(
+        catalog::resolve_url(BUNDLED_CATALOG, "/fonts/x.woff"),
+        "/fonts/x.woff"
+    );
+}
+
+/// 在本机随机端口上启动一个只支持 GET 的静态文件服务器，以 root 为根目录。返回其地址。
+fn serve(root: PathBuf) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(&stream);
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            // 读完请求头，以免客户端仍在写入时连接便已关闭
+            let mut header = String::new();
+            while reader.read_line(&mut header).unwrap() > 2 {
+                header.clear();
+            }
+
+            let path = request_line.split(' ').nth(1).unwrap_or("/");
+            let path = path.split(['?', '#']).next().unwrap();
+            let response = match std::fs::read(root.join(path.trim_start_matches('/'))) {
+                Ok(body) if !path.contains("..") => {
+                    let mut response =
+                        format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n", body.len())
+                            .into_bytes();
+                    response.extend(body);
+                    response
+                }
+                _ => b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n".to_vec(),
+            };
+            let _ = stream.write_all(&response);
+        }
+    });
+
+    format!("http://{addr}")
+}
+
+/// 以 HTTP GET 下载 url，返回状态码与内容。
+fn get(url: &str) -> (u16, Vec<u8>) {
+    let rest = url.strip_prefix("http://").unwrap();
+    let (host, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+
+    let mut stream = TcpStream::connect(host).unwrap();
+    let request = format!("GET {path} HTTP/1.1\r\nHost: {host}\r\nConnection: close\r\n\r\n");
+    stream.write_all(request.as_bytes()).unwrap();
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).unwrap();
+    let split = response.windows(4).position(|w| w == b"\r\n\r\n").unwrap();
+    let status = String::from_utf8_lossy(&response[..split])
+        .split(' ')
+        .nth(1)
+        .unwrap()
+        .parse()
+        .unwrap();
+
+    (status, response[split + 4..].to_vec())
+}
+
+fn write_file(root: &Path, path: &str, content: &[u8]) {
+    let path = root.join(path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+#[test]
+fn third_party_catalog_is_loaded_from_a_static_server() {
+    let root = std::env::temp_dir().join(format!("zigen-catalog-{}", std::process::id()));
+    let zigen = r#"[{ "type": "类", "description": "", "groups": [
+        { "zigens": ["口"], "code": "kk", "classify": "通", "description": "" }
+    ] }]"#;
+
+    write_file(
+        &root,
+        "catalogs/v2/schemes.json",
+        br#"[{
+            "id": "demo", "full_name": "", "description": "", "author": "", "maintainer": "",
+            "icon": "./icons/demo.svg", "category": [], "zigen_url": "zigen/demo.json",
+            "zigen_font": ["/fonts/demo.woff", "../../../../fonts/fallback.ttf"]
+        }]"#,
+    );
+    write_file(&root, "catalogs/v2/zigen/demo.json", zigen.as_bytes());
+    write_file(&root, "catalogs/v2/icons/demo.svg", b"<svg/>");
+    write_file(&root, "fonts/demo.woff", b"wOFF");
+    write_file(&root, "fonts/fallback.ttf", b"\0\x01\0\0");
+
+    let origin = serve(root.clone());
+    let catalog_url = format!("{origin}/catalogs/v2/schemes.json");
+
+    let (status, body) = get(&catalog_url);
+    assert_eq!(status, 200);
+    let schemes = serde_json::from_slice::<Vec<Scheme>>(&body).unwrap();
+    let resolved = catalog::resolve_catalog(&catalog_url, schemes, true);
+
+    assert_eq!(resolved[0].id, format!("{catalog_url}#demo"));
+
+    // 所有解析后的地址都能直接下载
+    let (status, body) = get(&resolved[0].zigen_url);
+    assert_eq!(status, 200);
+    serde_json::from_slice::<LoadedScheme<ZigenConfusableUnpopulated>>(&body).unwrap();
+
+    for url in resolved[0].zigen_font.iter().chain([&resolved[0].icon]) {
+        assert!(url.starts_with(&origin), "{url}");
+        assert_eq!(get(url).0, 200, "{url}");
+    }
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn default_options_fill_in_recommended_values() {
+    let schemes: Vec<Scheme> = serde_json::from_str(
+        r#"[{
+            "id": "trad", "full_name": "", "description": "", "author": "", "maintainer": "",
+            "icon": "", "category": [], "zigen_url": "./trad.json", "zigen_font": "",
+            "default_options": { "prioritize_trad": true }
+        }, {
+            "id": "plain", "full_name": "", "description": "", "author": "", "maintainer": "",
+            "icon": "", "category": [], "zigen_url": "./plain.json", "zigen_font": ""
+        }]"#,
+    )
+    .unwrap();
+
+    assert!(schemes[0].default_options.prioritize_trad);
+    assert!(schemes[0].default_options.shuffle);
+    assert_eq!(schemes[1].default_options, SchemeOptions::recommended());
+}
+
+#[test]
+fn zigen_font_accepts_a_string_or_a_list() {
+    let schemes: Vec<Scheme> = serde_json::from_str(
+        r#"[{
+            "id": "one", "full_name": "", "description": "", "author": "", "maintainer": "",
+            "icon": "", "category": [], "zigen_url": "./one.json", "zigen_font": "./a.woff"
+        }, {
+            "id": "many", "full_name": "", "description": "", "author": "", "maintainer": "",
+            "icon": "", "category": [], "zigen_url": "./many.json",
+            "zigen_font": ["./a.woff", "", "./b.ttf"]
+        }, {
+            "id": "none", "full_name": "", "description": "", "author": "", "maintainer": "",
+            "icon": "", "category": [], "zigen_url": "./none.json"
+        }]"#,
+    )
+    .unwrap();
+
+    assert_eq!(schemes[0].zigen_font, vec![String::from("./a.woff")]);
+    assert_eq!(
+        schemes[1].zigen_font,
+        vec![String::from("./a.woff"), String::from("./b.ttf")]
+    );
+    assert!(schemes[2].zigen_font.is_empty());
+}
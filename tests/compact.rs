@@ -0,0 +1,107 @@
+//! 预编译字根集格式的测试。
+
+use std::path::PathBuf;
+
+use zigen_trainer::compact::{self, compiled_url, decode, decode_version, encode};
+use zigen_trainer::overlay;
+use zigen_trainer::scheme::Scheme;
+
+#[test]
+fn bundled_schemes_round_trip() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets/trainer/zigen");
+
+    for entry in std::fs::read_dir(&dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+
+        let scheme = overlay::load_file(&path.display().to_string()).unwrap();
+        let bytes = encode(&scheme, "v1.2");
+
+        assert_eq!(decode(&bytes).as_ref(), Ok(&scheme), "{}", path.display());
+        assert_eq!(
+            decode_version(&bytes, "v1.2").as_ref(),
+            Ok(&scheme),
+            "{}",
+            path.display()
+        );
+    }
+}
+
+#[test]
+fn malformed_input_is_rejected() {
+    let scheme = serde_json::from_str(
+        r#"[
+            { "type": "类", "description": "", "groups": [
+                { "zigens": ["高"], "code": "Ag", "classify": "通", "description": "" }
+            ] },
+            { "type": "混", "zigens": ["高"] }
+        ]"#,
+    )
+    .unwrap();
+    let bytes = encode(&scheme, "");
+
+    assert!(decode(b"[]").is_err());
+    assert!(decode(&bytes[..bytes.len() - 1]).is_err());
+
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert!(decode(&trailing).is_err());
+
+    let mut newer = bytes;
+    newer[compact::MAGIC.len() - 1] += 1;
+    assert!(decode(&newer).is_err());
+}
+
+#[test]
+fn compiled_url_replaces_json_extension() {
+    assert_eq!(
+        compiled_url("./zigen/yuhao_star.json").as_deref(),
+        Some("./zigen/yuhao_star.zgb")
+    );
+    assert_eq!(compiled_url("https://example.com/zigen"), None);
+}
+
+#[test]
+fn outdated_compilations_are_rejected() {
+    let scheme = serde_json::from_str(
+        r#"[{ "type": "类", "description": "", "groups": [
+            { "zigens": ["高"], "code": "Ag", "classify": "通", "description": "" }
+        ] }]"#,
+    )
+    .unwrap();
+    let bytes = encode(&scheme, "2024-01");
+
+    assert_eq!(decode_version(&bytes, "2024-01"), Ok(scheme));
+    assert!(decode_version(&bytes, "2024-02").is_err());
+    assert!(decode_version(&bytes, "").is_err());
+}
+
+/// 本地执行过 zigen-compile 后，修改字根集却没有重新编译时，此测试会失败。
+#[test]
+fn existing_compilations_match_their_sources() {
+    let trainer = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets/trainer");
+    let catalog = std::fs::read_to_string(trainer.join("schemes.json")).unwrap();
+    let schemes = serde_json::from_str::<Vec<Scheme>>(&catalog).unwrap();
+
+    for scheme in schemes.iter() {
+        let Some(compiled) = compiled_url(&scheme.zigen_url) else {
+            continue;
+        };
+        let compiled = trainer.join(compiled.trim_start_matches("./"));
+        let Ok(bytes) = std::fs::read(&compiled) else {
+            continue;
+        };
+
+        let source = trainer.join(scheme.zigen_url.trim_start_matches("./"));
+        let expected = overlay::load_file(&source.display().to_string()).unwrap();
+
+        assert_eq!(
+            decode_version(&bytes, &scheme.version),
+            Ok(expected),
+            "「{}」已过时，请重新执行 zigen-compile",
+            compiled.display()
+        );
+    }
+}
@@ -0,0 +1,69 @@
+//! 字体渲染与混淆集建议的测试。
+
+use std::path::PathBuf;
+
+use zigen_trainer::confusable::{self, SuggestOptions};
+use zigen_trainer::font::Font;
+use zigen_trainer::scheme::{LoadedScheme, SchemeZigen, ZigenConfusableUnpopulated};
+
+fn font(name: &str) -> Font {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("assets/trainer")
+        .join(name);
+    Font::load(&path.display().to_string()).unwrap()
+}
+
+#[test]
+fn ttf_and_woff_glyphs_are_rendered() {
+    // ChaiPUA 为未压缩的 TTF，Yuniversus 为 zlib 压缩的 WOFF
+    for (font, c) in [
+        (font("ChaiPUA-0.2.7.ttf"), '\u{e002}'),
+        (font("Yuniversus.woff"), '\u{f5c5}'),
+    ] {
+        let bitmap = font.render(c, 32).unwrap().unwrap();
+        assert!(!bitmap.is_blank());
+        assert!((bitmap.similarity(&bitmap) - 1.0).abs() < 1e-4);
+        assert!(font.render('\u{10ffff}', 32).unwrap().is_none());
+    }
+}
+
+#[test]
+fn suggestions_skip_roots_already_practiced_together() {
+    let scheme: LoadedScheme<ZigenConfusableUnpopulated> = serde_json::from_str(
+        r#"[
+            { "type": "类", "description": "", "groups": [
+                { "zigens": [""], "code": "Aa", "classify": "通", "description": "" },
+                { "zigens": [""], "code": "Ab", "classify": "通", "description": "" }
+            ] },
+            { "type": "类", "description": "", "groups": [
+                { "zigens": [""], "code": "Ba", "classify": "通", "description": "" },
+                { "zigens": ["\U0010ffff"], "code": "Bb", "classify": "通", "description": "" }
+            ] }
+        ]"#
+        .replace("\\U0010ffff", "\u{10ffff}")
+        .as_str(),
+    )
+    .unwrap();
+
+    let options = SuggestOptions {
+        threshold: 0.0,
+        max_size: 2,
+        ..Default::default()
+    };
+    let rendered =
+        confusable::render_roots(&scheme, &[font("ChaiPUA-0.2.7.ttf")], options.bitmap_size)
+            .unwrap();
+
+    assert_eq!(rendered.roots.len(), 3);
+    assert_eq!(rendered.missing, vec![String::from("\u{10ffff}")]);
+
+    let suggestions = confusable::suggest(&scheme, &rendered, &options);
+
+    assert_eq!(suggestions.len(), 1);
+    assert!(suggestions[0].zigens.contains(&String::from("\u{e010}")));
+
+    // 输出须能作为混淆集放回字根集
+    let json = serde_json::to_string(&vec![suggestions[0].to_confusable()]).unwrap();
+    let parsed: LoadedScheme<ZigenConfusableUnpopulated> = serde_json::from_str(&json).unwrap();
+    assert!(matches!(parsed.0[0], SchemeZigen::Confusable(_)));
+}
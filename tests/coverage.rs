@@ -0,0 +1,52 @@
+//! 字根字体覆盖检查的测试。
+
+use std::path::PathBuf;
+
+use zigen_trainer::coverage::missing_glyphs;
+use zigen_trainer::font;
+use zigen_trainer::lint::{self, Severity};
+use zigen_trainer::scheme::{LoadedScheme, ZigenConfusableUnpopulated};
+
+fn asset(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("assets/trainer")
+        .join(name)
+}
+
+#[test]
+fn missing_pua_roots_are_reported_once() {
+    let charset = font::charset(&std::fs::read(asset("Yuniversus.woff")).unwrap()).unwrap();
+    assert!(charset.contains(&'\u{f5c5}'));
+
+    let scheme: LoadedScheme<ZigenConfusableUnpopulated> = serde_json::from_str(
+        r#"[
+            { "type": "类", "description": "", "groups": [
+                { "zigens": ["高", ""], "code": "Aa", "classify": "通", "description": "" },
+                { "zigens": ["􏿽"], "code": "Ab", "classify": "通", "description": "" }
+            ] },
+            { "type": "类", "description": "", "groups": [
+                { "zigens": ["􏿽"], "code": "Ba", "classify": "通", "description": "" }
+            ] }
+        ]"#,
+    )
+    .unwrap();
+
+    let missing = missing_glyphs(&scheme, &charset);
+    assert_eq!(missing.len(), 1);
+    assert_eq!(missing[0].code, "Ab");
+    assert_eq!(missing[0].codepoints(), "U+10FFFD");
+}
+
+#[test]
+fn bundled_fonts_cover_their_schemes() {
+    // 无法读取的字体只是警告，但自带的字体必须都能检查
+    let errors = lint::lint_catalog(&asset("schemes.json"))
+        .into_iter()
+        .filter(|d| {
+            d.severity == Severity::Error && d.message.contains("字形")
+                || d.message.contains("无法检查字根字体")
+        })
+        .collect::<Vec<_>>();
+
+    assert!(errors.is_empty(), "{errors:?}");
+}
@@ -0,0 +1,118 @@
+//! 拆分练习的测试。
+
+use zigen_trainer::compact::{decode, encode};
+use zigen_trainer::lint::{self, Severity};
+use zigen_trainer::scheme::{
+    LoadedScheme, SchemeOptions, SchemeZigen, ZigenConfusableUnpopulated, card_ids,
+};
+
+const SCHEME: &str = r#"[
+    { "type": "类", "description": "", "groups": [
+        { "zigens": ["日", "曰"], "code": "Jr", "classify": "通", "description": "" },
+        { "zigens": ["口"], "code": "Kk", "classify": "通", "description": "" }
+    ] },
+    { "type": "类", "description": "", "groups": [
+        { "zigens": ["刀"], "code": "Dd", "classify": "通", "description": "" },
+        { "zigens": ["灬"], "code": "Hh", "classify": "罕", "description": "" }
+    ] },
+    { "type": "混", "zigens": ["日", "口"] },
+    { "type": "拆", "char": "照", "split": ["日", "刀", "口", "灬"] },
+    { "type": "拆", "char": "昌", "split": ["曰", "日"], "description": "上扁下方" }
+]"#;
+
+fn scheme(source: &str) -> LoadedScheme<ZigenConfusableUnpopulated> {
+    serde_json::from_str(source).unwrap()
+}
+
+#[test]
+fn decompositions_resolve_roots_in_order() {
+    let populated = scheme(SCHEME).populate_confusables().unwrap();
+
+    let SchemeZigen::Decomposition(dec) = &populated.0[4] else {
+        panic!("第五项应为拆分");
+    };
+    let (groups, description) = populated.0[4].as_raw_parts();
+
+    // 非代表性字根也可以使用，且只保留该字根本身
+    assert_eq!(dec.character, "昌");
+    assert_eq!(description, "上扁下方");
+    assert_eq!(
+        groups
+            .iter()
+            .map(|group| (
+                group.zigens[0].0.as_str(),
+                group.zigens.len(),
+                group.code.as_str()
+            ))
+            .collect::<Vec<_>>(),
+        vec![("曰", 1, "Jr"), ("日", 1, "Jr")]
+    );
+
+    let ids = card_ids(&populated.0);
+    assert_eq!(&ids[3..], ["拆:照", "拆:昌"]);
+}
+
+#[test]
+fn decompositions_come_last_and_follow_key_limits() {
+    let mut sorted = scheme(SCHEME).populate_confusables().unwrap();
+    sorted.sort_to_options(&SchemeOptions::default());
+
+    let ids = card_ids(&sorted.0);
+    assert_eq!(ids.last().map(String::as_str), Some("拆:昌"));
+    assert!(matches!(
+        sorted.0[sorted.0.len() - 2],
+        SchemeZigen::Decomposition(_)
+    ));
+
+    // 「照」用到了不在键面 J、K 上的字根，因此不会出现
+    let mut limited = scheme(SCHEME).populate_confusables().unwrap();
+    limited.sort_to_options(&SchemeOptions {
+        limit_keys: Some(vec!['j', 'k']),
+        ..Default::default()
+    });
+    let ids = card_ids(&limited.0);
+    assert!(ids.contains(&String::from("拆:昌")));
+    assert!(!ids.contains(&String::from("拆:照")));
+}
+
+#[test]
+fn unknown_roots_are_rejected() {
+    let source = r#"[
+        { "type": "类", "description": "", "groups": [
+            { "zigens": ["日"], "code": "Jr", "classify": "通", "description": "" }
+        ] },
+        { "type": "拆", "char": "明", "split": ["日", "月"] }
+    ]"#;
+
+    let err = scheme(source).populate_confusables().unwrap_err();
+    assert!(err.contains("明（月）"), "{err}");
+
+    let errors = lint::lint_zigen_table("test.json", source, "abcdefghijklmnopqrstuvwxyz")
+        .into_iter()
+        .filter(|diagnostic| diagnostic.severity == Severity::Error)
+        .collect::<Vec<_>>();
+    assert_eq!(errors.len(), 1, "{errors:?}");
+    assert!(errors[0].message.contains("月"));
+}
+
+#[test]
+fn decompositions_survive_compilation() {
+    let scheme = scheme(SCHEME);
+    assert_eq!(decode(&encode(&scheme, "")).as_ref(), Ok(&scheme));
+}
+
+#[test]
+fn decompositions_without_character_or_roots_are_rejected() {
+    // 这样的拆分会成为无法作答的卡片；练习器不执行 zigen-lint，因此展开时就须报错
+    for entry in [
+        r#"{ "type": "拆", "char": "照", "split": [] }"#,
+        r#"{ "type": "拆", "char": "", "split": ["日", "刀"] }"#,
+    ] {
+        let source = SCHEME.replace(
+            r#"{ "type": "混", "zigens": ["日", "口"] },"#,
+            &format!("{entry},"),
+        );
+        let err = scheme(&source).populate_confusables().unwrap_err();
+        assert!(err.contains("拆分"), "{err}");
+    }
+}
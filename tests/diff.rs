@@ -0,0 +1,88 @@
+//! 字根集比较的测试。
+
+use zigen_trainer::diff::{Change, diff};
+use zigen_trainer::scheme::{LoadedScheme, ZigenClass, ZigenConfusableUnpopulated};
+
+fn scheme(source: &str) -> LoadedScheme<ZigenConfusableUnpopulated> {
+    serde_json::from_str(source).unwrap()
+}
+
+const OLD: &str = r#"[
+    { "type": "类", "description": "", "groups": [
+        { "zigens": ["高"], "code": "Ag", "classify": "通", "description": "" },
+        { "zigens": ["亠", "二"], "code": "At", "classify": "通", "description": "" }
+    ] },
+    { "type": "类", "description": "", "groups": [
+        { "zigens": ["車"], "code": "Cc", "classify": "繁", "description": "" },
+        { "zigens": ["目"], "code": "Jm", "classify": "通", "description": "" }
+    ] }
+]"#;
+
+#[test]
+fn identical_schemes_have_no_changes() {
+    assert_eq!(diff(&scheme(OLD), &scheme(OLD)), Vec::new());
+}
+
+#[test]
+fn all_kinds_of_changes_are_reported() {
+    let new = scheme(
+        r#"[
+            { "type": "类", "description": "", "groups": [
+                { "zigens": ["高"], "code": "Bg", "classify": "通", "description": "" },
+                { "zigens": ["亠", "丄"], "code": "At", "classify": "通", "description": "" },
+                { "zigens": ["車"], "code": "Cc", "classify": "通", "description": "" }
+            ] },
+            { "type": "类", "description": "", "groups": [
+                { "zigens": ["曰"], "code": "Jy", "classify": "通", "description": "" }
+            ] }
+        ]"#,
+    );
+
+    let changes = diff(&scheme(OLD), &new);
+
+    assert_eq!(
+        changes,
+        vec![
+            Change::Recoded {
+                zigen: "高".into(),
+                from: "Ag".into(),
+                to: "Bg".into(),
+            },
+            Change::Regrouped {
+                zigen: "高".into(),
+                from: vec!["亠".into()],
+                to: vec!["亠".into(), "車".into()],
+            },
+            Change::Members {
+                zigen: "亠".into(),
+                added: vec!["丄".into()],
+                removed: vec!["二".into()],
+            },
+            Change::Regrouped {
+                zigen: "亠".into(),
+                from: vec!["高".into()],
+                to: vec!["高".into(), "車".into()],
+            },
+            Change::Reclassified {
+                zigen: "車".into(),
+                from: ZigenClass::Traditional,
+                to: ZigenClass::Common,
+            },
+            Change::Regrouped {
+                zigen: "車".into(),
+                from: vec![],
+                to: vec!["高".into(), "亠".into()],
+            },
+            Change::Added {
+                zigen: "曰".into(),
+                code: "Jy".into(),
+            },
+            Change::Removed {
+                zigen: "目".into(),
+                code: "Jm".into(),
+            },
+        ]
+    );
+
+    assert_eq!(changes[0].to_string(), "~ 高 改码：Ag → Bg（换键）");
+}
@@ -0,0 +1,98 @@
+//! V2 调度器到期时间扰动与负载均衡的测试。
+
+use chrono::{Duration, Utc};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use zigen_trainer::scheduler::{Rating, ZigenCard};
+use zigen_trainer::scheduler_v2::{FUZZ_FACTOR, SchedulerV2, SchedulerV2Card, balanced_due};
+
+#[test]
+fn cards_learned_together_get_spread_due_dates() {
+    let cards = (0..6)
+        .map(|i| SchedulerV2Card::new(format!("类:{i}")))
+        .collect();
+    let mut scheduler = SchedulerV2::new(cards, true);
+    let mut rng = StdRng::seed_from_u64(26);
+
+    // 同时引入的卡片一起学会，进入复习阶段
+    for _ in 0..100 {
+        if scheduler.cards().all(|card| card.interval().is_some()) {
+            break;
+        }
+        scheduler.get_card();
+        scheduler.rate_card(Rating::Good, &mut rng);
+    }
+
+    let mut intervals = scheduler
+        .cards()
+        .map(|card| card.interval().unwrap().num_seconds())
+        .collect::<Vec<_>>();
+    assert_eq!(intervals.len(), 6);
+
+    // 首次复习的间隔为300秒，扰动后仍须在范围之内，且不会全部挤在同一时间
+    let fuzz = (300.0 * FUZZ_FACTOR) as i64;
+    assert!(
+        intervals
+            .iter()
+            .all(|interval| (300 - fuzz..=300 + fuzz).contains(interval)),
+        "{intervals:?}"
+    );
+    intervals.sort();
+    intervals.dedup();
+    assert!(intervals.len() >= 4, "{intervals:?}");
+}
+
+#[test]
+fn fuzz_stays_within_factor() {
+    let last_reviewed = Utc::now();
+    let interval = Duration::days(10);
+    let fuzz = interval.as_seconds_f64() * FUZZ_FACTOR;
+
+    for seed in 0..200 {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let due = balanced_due(last_reviewed, interval, &[], &mut rng);
+
+        let offset = (due - last_reviewed - interval).as_seconds_f64();
+        assert!(offset.abs() <= fuzz, "seed {seed}: {offset}");
+    }
+}
+
+/// 同时引入 count 张卡片，全部答对直到进入复习阶段，返回各卡片首次复习的间隔（秒）。
+fn first_review_intervals(count: usize, seed: u64) -> Vec<f64> {
+    let cards = (0..count)
+        .map(|i| SchedulerV2Card::new(format!("类:{i}")))
+        .collect();
+    let mut scheduler = SchedulerV2::new(cards, true);
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    while !scheduler.cards().all(|card| card.interval().is_some()) {
+        scheduler.get_card();
+        scheduler.rate_card(Rating::Good, &mut rng);
+    }
+
+    scheduler
+        .cards()
+        .map(|card| card.interval().unwrap().as_seconds_f64())
+        .collect()
+}
+
+#[test]
+fn due_dates_of_many_cards_are_evenly_spread() {
+    // 首次复习的间隔为300秒，扰动范围分为10段，40张卡片理想上每段4张
+    let fuzz = 300.0 * FUZZ_FACTOR;
+    let bin_width = 2.0 * fuzz / 10.0;
+
+    for seed in 0..10 {
+        let mut bins = [0; 10];
+        for interval in first_review_intervals(40, seed) {
+            let bin = ((interval - (300.0 - fuzz)) / bin_width) as usize;
+            bins[bin.min(9)] += 1;
+        }
+
+        // 只靠随机扰动时，最拥挤的一段常有七八张卡片，也常有整段空着
+        assert!(
+            bins.iter().all(|&count| (1..=6).contains(&count)),
+            "seed {seed}: {bins:?}"
+        );
+    }
+}
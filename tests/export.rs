@@ -0,0 +1,39 @@
+//! Anki 卡组导出的测试。
+
+use zigen_trainer::export::anki_tsv;
+use zigen_trainer::scheme::{LoadedScheme, ZigenConfusableUnpopulated};
+
+fn scheme() -> LoadedScheme<ZigenConfusableUnpopulated> {
+    serde_json::from_str(
+        r#"[
+            {
+                "type": "类",
+                "groups": [
+                    { "zigens": ["高", "亠"], "code": "Ag", "classify": "通", "description": "高 <b>g</b>āo" },
+                    { "zigens": ["來"], "code": "Al", "classify": "繁", "description": "\"来\"" }
+                ],
+                "description": "这类字根以亠为头。"
+            },
+            { "type": "混", "zigens": ["高", "來"] }
+        ]"#,
+    )
+    .unwrap()
+}
+
+#[test]
+fn exports_one_note_per_card() {
+    let scheme = scheme().populate_confusables().unwrap();
+    let tsv = anki_tsv(&scheme, "test");
+    let lines = tsv.lines().collect::<Vec<_>>();
+
+    assert_eq!(lines[0], "#separator:tab");
+    assert_eq!(lines[2], "#columns:字根\t编码\t说明\t聚类说明\t标签");
+    assert_eq!(lines[3], "#tags column:5");
+    assert_eq!(lines.len(), 6);
+
+    assert_eq!(
+        lines[4],
+        "高 亠<br>來\tAg<br>Al\t\"高 <b>g</b>āo<br>\"\"来\"\"\"\t这类字根以亠为头。\t慧根 test 聚类"
+    );
+    assert!(lines[5].ends_with("慧根 test 混淆"));
+}
@@ -0,0 +1,160 @@
+//! 弱项加练的测试。
+
+use chrono::Duration;
+use zigen_trainer::progress::TrainProgress;
+use zigen_trainer::scheduler::{CardStats, Rating, ZigenCard, weakest_cards};
+use zigen_trainer::scheme::{LoadedScheme, SchemeOptions, SchemeZigen, ZigenConfusableUnpopulated};
+
+/// 只有调度状态的卡片，用于检查排序。
+struct TestCard {
+    id: String,
+    legacy_zigen: Option<SchemeZigen>,
+    easiness_factor: Option<f64>,
+    is_new: bool,
+    stats: CardStats,
+}
+
+impl TestCard {
+    fn new(easiness_factor: Option<f64>, lapses: usize, latencies: &[f64]) -> Self {
+        Self {
+            id: String::new(),
+            legacy_zigen: None,
+            easiness_factor,
+            is_new: false,
+            stats: CardStats {
+                lapses,
+                latencies: latencies.iter().copied().collect(),
+            },
+        }
+    }
+}
+
+impl ZigenCard for TestCard {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn id_mut(&mut self) -> &mut String {
+        &mut self.id
+    }
+
+    fn legacy_zigen_mut(&mut self) -> &mut Option<SchemeZigen> {
+        &mut self.legacy_zigen
+    }
+
+    fn is_new_card(&self) -> bool {
+        self.is_new
+    }
+
+    fn stats(&self) -> &CardStats {
+        &self.stats
+    }
+
+    fn stats_mut(&mut self) -> &mut CardStats {
+        &mut self.stats
+    }
+
+    fn easiness_factor(&self) -> Option<f64> {
+        self.easiness_factor
+    }
+
+    fn interval(&self) -> Option<Duration> {
+        None
+    }
+}
+
+#[test]
+fn cards_are_ranked_by_easiness_then_lapses_then_latency() {
+    let cards = [
+        TestCard::new(Some(2.5), 0, &[1.0]),
+        TestCard::new(Some(1.3), 0, &[1.0]),
+        // 容易系数相同时，答错次数多者优先
+        TestCard::new(Some(2.0), 1, &[1.0]),
+        TestCard::new(Some(2.0), 3, &[1.0]),
+        // 容易系数与答错次数都相同时，平均耗时长者优先
+        TestCard::new(Some(2.2), 0, &[1.0, 2.0]),
+        TestCard::new(Some(2.2), 0, &[4.0, 6.0]),
+        // 学习阶段的卡片视为初始容易系数 2.5
+        TestCard::new(None, 2, &[1.0]),
+        // 未曾学习过的卡片不参与排序
+        TestCard {
+            is_new: true,
+            ..TestCard::new(Some(1.3), 9, &[9.0])
+        },
+    ];
+
+    assert_eq!(weakest_cards(cards.iter(), 10), vec![1, 3, 2, 5, 4, 6, 0]);
+    assert_eq!(weakest_cards(cards.iter(), 2), vec![1, 3]);
+    assert!(weakest_cards(cards[7..].iter(), 10).is_empty());
+}
+
+fn progress(v2_sched: bool) -> TrainProgress {
+    let scheme: LoadedScheme<ZigenConfusableUnpopulated> = serde_json::from_str(
+        r#"[
+            { "type": "类", "description": "", "groups": [
+                { "zigens": ["日"], "code": "Jr", "classify": "通", "description": "" },
+                { "zigens": ["口"], "code": "Kk", "classify": "通", "description": "" }
+            ] },
+            { "type": "类", "description": "", "groups": [
+                { "zigens": ["刀"], "code": "Dd", "classify": "通", "description": "" }
+            ] }
+        ]"#,
+    )
+    .unwrap();
+
+    let options = SchemeOptions {
+        v2_sched,
+        ..Default::default()
+    };
+    let mut cards = scheme.populate_confusables().unwrap();
+    cards.sort_to_options(&options);
+
+    let mut progress = TrainProgress::new(cards.0, options);
+    for _ in 0..6 {
+        progress.get_card();
+        progress.rate_card(Rating::Good, 1.0);
+    }
+    progress
+}
+
+#[test]
+fn practice_without_updating_leaves_schedule_untouched() {
+    for v2_sched in [false, true] {
+        let mut progress = progress(v2_sched);
+        let before = serde_json::to_value(&progress).unwrap();
+
+        assert!(progress.start_extra_practice(2, false));
+        while progress.extra_practice_remaining().is_some() {
+            progress.get_card();
+            progress.rate_card(Rating::Again, 9.0);
+        }
+
+        assert_eq!(serde_json::to_value(&progress).unwrap(), before);
+    }
+}
+
+#[test]
+fn practice_with_updating_records_results() {
+    for v2_sched in [false, true] {
+        let mut progress = progress(v2_sched);
+        let before = serde_json::to_value(&progress).unwrap();
+
+        assert!(progress.start_extra_practice(1, true));
+        assert_eq!(progress.extra_practice_remaining(), Some(1));
+        progress.get_card();
+        progress.rate_card(Rating::Again, 9.0);
+
+        assert_eq!(progress.extra_practice_remaining(), None);
+        assert_ne!(serde_json::to_value(&progress).unwrap(), before);
+    }
+}
+
+#[test]
+fn due_work_includes_new_and_learning_cards() {
+    for v2_sched in [false, true] {
+        // 每张卡片只答对了几次，仍在学习阶段
+        let progress = progress(v2_sched);
+        assert!(!progress.is_due_work_done(), "v2_sched={v2_sched}");
+        assert!(progress.reviewed_cards() > 0);
+    }
+}
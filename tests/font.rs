@@ -0,0 +1,200 @@
+//! 字体格式解析的测试：WOFF2 的解压与 glyf 还原，以及损坏的 cmap 表。
+
+use std::path::PathBuf;
+
+use zigen_trainer::font::{self, Font};
+
+fn be16(value: i32) -> [u8; 2] {
+    (value as u16).to_be_bytes()
+}
+
+/// 以不压缩的 meta-block 写成 Brotli 流，免得测试依赖压缩器。
+fn brotli_stored(data: &[u8]) -> Vec<u8> {
+    assert!(!data.is_empty() && data.len() <= 1 << 16);
+
+    // WBITS = 16（1 位 0）、ISLAST = 0、MNIBBLES = 4（2 位 0）、MLEN - 1（16 位）、ISUNCOMPRESSED = 1，
+    // 共 21 位，由低位起依序排列，再补齐到字节边界
+    let header = (((data.len() - 1) as u32) << 4) | (1 << 20);
+    let mut stream = header.to_le_bytes()[..3].to_vec();
+    stream.extend_from_slice(data);
+    // ISLAST = 1、ISLASTEMPTY = 1
+    stream.push(0b11);
+    stream
+}
+
+/// 一个只有三个字形的 WOFF2 字体：0 号为空白，1 号（A）为 600 × 700 的方块，
+/// 2 号（B）为向右平移 100 的 1 号复合字形。glyf 与 loca 表经过转换。
+///
+/// 方块的四个点记为一个轮廓，points 为该轮廓在 nPoints 流中记录的点数，正确的值为 4。
+fn woff2_font(points: u8) -> Vec<u8> {
+    let mut head = vec![0; 54];
+    head[18..20].copy_from_slice(&be16(1000));
+
+    let mut hhea = vec![0; 36];
+    hhea[4..6].copy_from_slice(&be16(800));
+    hhea[6..8].copy_from_slice(&be16(-200));
+
+    // 格式4：A、B -> 1、2，以及结尾的 0xFFFF 段
+    let cmap = [
+        &[0, 1, 3, 1, 0, 12][..],
+        &[4, 32, 0, 4, 4, 1, 0],
+        // 各段的终点、保留字段、起点、差值、范围偏移
+        &[0x42, 0xffff, 0, 0x41, 0xffff, 1 - 0x41, 1, 0, 0],
+    ]
+    .concat()
+    .into_iter()
+    .flat_map(be16)
+    .collect::<Vec<_>>();
+
+    // 点以三元组编码：127 为 4 字节的正向 dx、dy，5 与 4 为 1 字节的正、负 dy
+    let glyph_stream = [&[0, 0, 0, 0][..], &[188], &[0x02, 0x58, 0, 0], &[188], &[0]].concat();
+    let streams: [Vec<u8>; 7] = [
+        [0, 1, -1].map(be16).concat(),
+        vec![points],
+        vec![127, 5, 127, 4],
+        glyph_stream,
+        [0x0003, 1, 100, 0].map(be16).concat(),
+        [&[0x20, 0, 0, 0][..], &[100, 0, 700, 700].map(be16).concat()].concat(),
+        Vec::new(),
+    ];
+
+    let mut glyf = [0, 0, 3, 0].map(be16).concat();
+    for stream in &streams {
+        glyf.extend((stream.len() as u32).to_be_bytes());
+    }
+    glyf.extend(streams.concat());
+
+    // 表目录：标志字节（表名索引 | 转换版本 << 6）、原始长度，转换过的表再加上转换后的长度
+    let mut directory = Vec::new();
+    for (index, table) in [(0, &cmap), (1, &head), (2, &hhea)] {
+        directory.extend([index, table.len() as u8]);
+    }
+    directory.extend([10, 64, glyf.len() as u8]);
+    directory.extend([11, 16, 0]);
+
+    let tables = [cmap, head, hhea, glyf].concat();
+    let compressed = brotli_stored(&tables);
+
+    let mut data = b"wOF2\0\x01\0\0".to_vec();
+    data.extend([0; 4]);
+    data.extend(be16(5));
+    data.extend([0; 6]);
+    data.extend((compressed.len() as u32).to_be_bytes());
+    data.resize(48, 0);
+    data.extend(directory);
+    data.extend(compressed);
+    data
+}
+
+#[test]
+fn woff2_glyf_is_reconstructed() {
+    let data = woff2_font(4);
+    let font = Font::parse(&data).unwrap();
+
+    let square = font.outline(font.glyph_id('A').unwrap()).unwrap();
+    let shifted = font.outline(font.glyph_id('B').unwrap()).unwrap();
+    assert_eq!(square.len(), 1);
+
+    let bounds = square[0]
+        .iter()
+        .fold((f32::MAX, f32::MAX, f32::MIN, f32::MIN), |b, p| {
+            (b.0.min(p.x), b.1.min(p.y), b.2.max(p.x), b.3.max(p.y))
+        });
+    assert_eq!(bounds, (0.0, 0.0, 600.0, 700.0));
+
+    let expected = square[0]
+        .iter()
+        .map(|p| (p.x + 100.0, p.y))
+        .collect::<Vec<_>>();
+    let actual = shifted[0].iter().map(|p| (p.x, p.y)).collect::<Vec<_>>();
+    assert_eq!(actual, expected);
+
+    assert!(!font.render('A', 32).unwrap().unwrap().is_blank());
+    assert_eq!(
+        font::charset(&data).unwrap(),
+        ['A', 'B'].into_iter().collect()
+    );
+
+    // 截断的字体只会报错
+    for len in [8, 60, data.len() - 3] {
+        assert!(Font::parse(&data[..len]).is_err(), "{len}");
+    }
+
+    // 损坏的字形数据同样只会报错
+    for points in [0, 3, 5, 253] {
+        assert!(Font::parse(&woff2_font(points)).is_err(), "{points}");
+    }
+}
+
+#[test]
+fn woff2_charset_is_read_from_cff_fonts() {
+    // 虽然扩展名为 .woff，Wubi98 实为 CFF 轮廓的 WOFF2 字体：可以检查覆盖，但无法渲染。
+    // 其字根在补充私用区A，须读取格式12的 cmap 子表
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets/trainer/Wubi98.woff");
+    let data = std::fs::read(path).unwrap();
+    assert_eq!(&data[..4], b"wOF2");
+
+    let charset = font::charset(&data).unwrap();
+    assert!(
+        charset
+            .iter()
+            .any(|c| ('\u{f0000}'..='\u{ffffd}').contains(c))
+    );
+    assert!(Font::parse(&data).err().unwrap().contains("CFF"));
+}
+
+/// 只有一个 cmap 表的 TTF，cmap 中为一个格式12的子表。
+fn sfnt_with_cmap12(declared_groups: u32, groups: &[[u32; 3]]) -> Vec<u8> {
+    let mut cmap = [0, 1, 3, 10].map(be16).concat();
+    cmap.extend(12u32.to_be_bytes());
+    cmap.extend([12, 0].map(be16).concat());
+    cmap.extend(
+        [(16 + groups.len() * 12) as u32, 0, declared_groups]
+            .map(u32::to_be_bytes)
+            .concat(),
+    );
+    for group in groups {
+        cmap.extend(group.map(u32::to_be_bytes).concat());
+    }
+
+    let mut data = [0, 1, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0].to_vec();
+    data.extend(b"cmap");
+    data.extend([0, 28, cmap.len() as u32].map(u32::to_be_bytes).concat());
+    data.extend(cmap);
+    data
+}
+
+#[test]
+fn malformed_cmap_ranges_are_bounded() {
+    // 范围超出 Unicode 与字形编号超出 16 位的部分被略去
+    let charset = font::charset(&sfnt_with_cmap12(
+        3,
+        &[
+            [0x41, 0x42, 1],
+            [0x10fffe, u32::MAX, 5],
+            [0x61, 0x7a, 0xfffe],
+        ],
+    ))
+    .unwrap();
+    assert_eq!(
+        charset,
+        ['A', 'B', '\u{10fffe}', '\u{10ffff}', 'a', 'b']
+            .into_iter()
+            .collect()
+    );
+
+    // 起点大于终点的组被略过
+    assert!(
+        font::charset(&sfnt_with_cmap12(1, &[[0x42, 0x41, 1]]))
+            .unwrap()
+            .is_empty()
+    );
+
+    // 组数多于表中实际的组、重叠的范围过大、偏移量溢出，都只会报错
+    assert!(font::charset(&sfnt_with_cmap12(u32::MAX, &[[0x41, 0x41, 1]])).is_err());
+    assert!(font::charset(&sfnt_with_cmap12(2, &[[0, u32::MAX, 1]; 2])).is_err());
+
+    let mut overflow = sfnt_with_cmap12(1, &[[0x41, 0x41, 1]]);
+    overflow[20..24].copy_from_slice(&u32::MAX.to_be_bytes());
+    assert!(font::charset(&overflow).is_err());
+}
@@ -0,0 +1,101 @@
+//! 码表转换的测试。
+
+use zigen_trainer::import::{ImportFormat, import};
+use zigen_trainer::scheme::{SchemeZigen, ZigenClass};
+
+fn groups(source: &str, format: ImportFormat) -> Vec<(Vec<String>, String, ZigenClass)> {
+    import(source, format)
+        .unwrap()
+        .0
+        .iter()
+        .flat_map(|zigen| match zigen {
+            SchemeZigen::Cluster(cluster) => cluster.groups.clone(),
+            SchemeZigen::Confusable(_) | SchemeZigen::Decomposition(_) => unreachable!(),
+        })
+        .map(|group| {
+            (
+                group.zigens.iter().map(|z| z.0.clone()).collect(),
+                group.code,
+                group.classify,
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn tsv_groups_roots_with_same_code() {
+    let source = "# 注释\n高\tAg\n亠\tAt\n二\tAt\n\n來\tAl\n";
+
+    assert_eq!(
+        groups(source, ImportFormat::Tsv),
+        vec![
+            (vec!["高".into()], "Ag".into(), ZigenClass::Common),
+            (
+                vec!["亠".into(), "二".into()],
+                "At".into(),
+                ZigenClass::Common
+            ),
+            (vec!["來".into()], "Al".into(), ZigenClass::Common),
+        ]
+    );
+}
+
+#[test]
+fn rime_respects_declared_columns() {
+    let source = "---\nname: test\ncolumns:\n  - code\n  - text\n...\n\nag\t高\n# 注释\nat\t亠\n";
+
+    assert_eq!(
+        groups(source, ImportFormat::Rime),
+        vec![
+            (vec!["高".into()], "ag".into(), ZigenClass::Common),
+            (vec!["亠".into()], "at".into(), ZigenClass::Common),
+        ]
+    );
+}
+
+#[test]
+fn rime_skips_comments_before_header() {
+    let source = "# Rime dictionary\n# encoding: utf-8\n\n---\nname: test\nversion: \"1.0\"\nsort: original\n...\n\n口\tk\n";
+
+    assert_eq!(
+        groups(source, ImportFormat::Rime),
+        vec![(vec!["口".into()], "k".into(), ZigenClass::Common)]
+    );
+}
+
+#[test]
+fn csv_reads_classify_column() {
+    let source = "code,字根,分类\nag,高,通\nal,\"來\",繁\nal,来,简\n";
+
+    assert_eq!(
+        groups(source, ImportFormat::Csv),
+        vec![
+            (vec!["高".into()], "ag".into(), ZigenClass::Common),
+            (vec!["來".into()], "al".into(), ZigenClass::Traditional),
+            (vec!["来".into()], "al".into(), ZigenClass::Simplified),
+        ]
+    );
+}
+
+#[test]
+fn clusters_are_split_by_major_key() {
+    let source = "一\tga\n二\tgb\n三\tgc\n四\tgd\n五\tge\n六\tha\n";
+    let scheme = import(source, ImportFormat::Tsv).unwrap();
+
+    let sizes = scheme
+        .0
+        .iter()
+        .map(|zigen| match zigen {
+            SchemeZigen::Cluster(cluster) => cluster.groups.len(),
+            SchemeZigen::Confusable(_) | SchemeZigen::Decomposition(_) => unreachable!(),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(sizes, vec![4, 1, 1]);
+}
+
+#[test]
+fn malformed_lines_are_reported() {
+    assert!(import("高\n", ImportFormat::Tsv).is_err());
+    assert!(import("高,ag,奇\n", ImportFormat::Csv).is_err());
+    assert!(import("", ImportFormat::Tsv).is_err());
+}
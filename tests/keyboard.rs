@@ -0,0 +1,55 @@
+//! 方案按键定义的测试。
+
+use zigen_trainer::scheme::{KeySet, LoadedScheme, SchemeOptions, ZigenConfusableUnpopulated};
+
+fn keyboard() -> KeySet {
+    serde_json::from_str(
+        r#"{ "keys": "abcdefghijklmnopqrstuvwxyz;,./", "aliases": { "；": ";", "，": "," } }"#,
+    )
+    .unwrap()
+}
+
+#[test]
+fn input_is_normalized_to_scheme_keys() {
+    let keyboard = keyboard();
+
+    assert_eq!(keyboard.normalize('A'), Some('a'));
+    assert_eq!(keyboard.normalize(';'), Some(';'));
+    assert_eq!(keyboard.normalize('；'), Some(';'));
+    assert_eq!(keyboard.normalize('1'), None);
+    assert_eq!(keyboard.normalize_code("A；"), "a;");
+    assert!(keyboard.problems().is_empty());
+}
+
+#[test]
+fn invalid_definitions_are_reported() {
+    let keyboard: KeySet =
+        serde_json::from_str(r#"{ "keys": "abA ", "aliases": { "b": "a", "x": "y" } }"#).unwrap();
+
+    assert_eq!(keyboard.problems().len(), 4);
+}
+
+#[test]
+fn codes_outside_the_key_set_are_rejected() {
+    let scheme: LoadedScheme<ZigenConfusableUnpopulated> = serde_json::from_str(
+        r#"[{ "type": "类", "description": "", "groups": [
+            { "zigens": ["口"], "code": ";k", "classify": "通", "description": "" }
+        ] }]"#,
+    )
+    .unwrap();
+
+    assert!(scheme.check_keys(&keyboard()).is_ok());
+    assert!(scheme.check_keys(&KeySet::default()).is_err());
+}
+
+#[test]
+fn limit_keys_ignore_case() {
+    let options = SchemeOptions {
+        limit_keys: Some(vec!['a', ';']),
+        ..Default::default()
+    };
+
+    assert!(options.allows_code("Ag"));
+    assert!(options.allows_code(";k"));
+    assert!(!options.allows_code("Bk"));
+}
@@ -0,0 +1,155 @@
+//! 字根集检查规则的测试。
+
+use std::path::PathBuf;
+
+use zigen_trainer::lint::{self, Diagnostic, Severity};
+
+const KEYS: &str = "abcdefghijklmnopqrstuvwxyz";
+
+fn lint(source: &str) -> Vec<Diagnostic> {
+    lint::lint_zigen_table("test.json", source, KEYS)
+}
+
+/// 以（严重程度、行号、信息）列出所有诊断，方便比较。
+fn summary(diagnostics: &[Diagnostic]) -> Vec<(Severity, Option<usize>, &str)> {
+    diagnostics
+        .iter()
+        .map(|d| (d.severity, d.line, d.message.as_str()))
+        .collect()
+}
+
+#[test]
+fn duplicate_roots_are_reported_on_their_lines() {
+    let source = r#"[
+    { "type": "类", "description": "", "groups": [
+        { "zigens": ["日"], "code": "jr", "classify": "通", "description": "" },
+        { "zigens": ["口",
+            "口"], "code": "kk", "classify": "通", "description": "" }
+    ] },
+    { "type": "类", "description": "", "groups": [
+        { "zigens": ["日"], "code": "jj", "classify": "通", "description": "" }
+    ] }
+]"#;
+
+    assert_eq!(
+        summary(&lint(source)),
+        vec![
+            (
+                Severity::Warning,
+                Some(5),
+                "字根「口」在同一归并组内重复出现（以笔画表示补码时可忽略）"
+            ),
+            (
+                Severity::Warning,
+                Some(8),
+                "字根「日」出现在2个归并组内（第3、8行）"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn empty_groups_bad_keys_and_code_lengths_are_errors() {
+    let source = r#"[
+    { "type": "类", "description": "", "groups": [
+        { "zigens": [], "code": "aa", "classify": "通", "description": "" },
+        { "zigens": ["日"], "code": "j;", "classify": "通", "description": "" },
+        { "zigens": ["口"], "code": "kk", "alt_codes": ["kkk"], "classify": "通", "description": "" }
+    ] },
+    { "type": "类", "description": "", "groups": [] }
+]"#;
+
+    let errors = lint(source)
+        .into_iter()
+        .filter(|d| d.severity == Severity::Error)
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        summary(&errors),
+        vec![
+            (Severity::Error, Some(3), "归并组内没有任何字根"),
+            (
+                Severity::Error,
+                Some(4),
+                "编码「j;」含有方案键位以外的字符：;"
+            ),
+            (
+                Severity::Error,
+                Some(5),
+                "备选编码「kkk」的长度与主编码「kk」不一致"
+            ),
+            (Severity::Error, Some(7), "聚类内没有任何归并组"),
+        ]
+    );
+}
+
+#[test]
+fn confusables_must_name_representative_roots() {
+    let source = r#"[
+    { "type": "类", "description": "", "groups": [
+        { "zigens": ["日", "曰"], "code": "jr", "classify": "通", "description": "" },
+        { "zigens": ["口"], "code": "kk", "classify": "通", "description": "" }
+    ] },
+    { "type": "混", "description": "", "zigens": ["口",
+        "曰",
+        "目"] }
+]"#;
+
+    assert_eq!(
+        summary(&lint(source)),
+        vec![
+            (
+                Severity::Error,
+                Some(7),
+                "混淆集引用的字根「曰」不是其归并组的代表性字根（见第3行）"
+            ),
+            (
+                Severity::Error,
+                Some(8),
+                "混淆集引用的字根「目」不在字根码表内"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn examples_must_be_single_characters_with_valid_splits() {
+    let source = r#"[
+    { "type": "类", "description": "", "groups": [
+        { "zigens": ["口"], "code": "kk", "classify": "通", "description": "", "examples": [
+            { "char": "回", "split": ["口", "口"] },
+            { "char": "回" },
+            { "char": "品字", "split": ["口"] },
+            { "char": "叶", "split": ["口", ""] },
+            { "char": "十", "split": ["十"] },
+            { "char": "吕", "split": ["口", "口"] }
+        ] }
+    ] }
+]"#;
+
+    assert_eq!(
+        summary(&lint(source)),
+        vec![
+            (Severity::Warning, Some(3), "归并组有6个例字，建议不超过5个"),
+            (Severity::Warning, Some(5), "例字「回」重复"),
+            (Severity::Error, Some(6), "例字「品字」必须是单个字符"),
+            (Severity::Error, Some(7), "例字的拆分不能含有空字符串"),
+            (
+                Severity::Error,
+                Some(8),
+                "例字「十」的拆分中没有该归并组的字根"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn bundled_catalog_has_no_errors() {
+    let catalog = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets/trainer/schemes.json");
+    let errors = lint::lint_catalog(&catalog)
+        .into_iter()
+        .filter(|d| d.severity == Severity::Error)
+        .collect::<Vec<_>>();
+
+    assert!(errors.is_empty(), "{errors:?}");
+}
@@ -0,0 +1,42 @@
+//! 本地字根集文件的测试：练习器不会对本地文件执行 zigen-lint，格式错误的文件须在展开时报错，而不是 panic。
+
+use zigen_trainer::scheme::{LoadedScheme, ZigenConfusableUnpopulated};
+
+fn populate(source: &str) -> Result<(), String> {
+    let scheme: LoadedScheme<ZigenConfusableUnpopulated> = serde_json::from_str(source).unwrap();
+    scheme.populate_confusables().map(|_| ())
+}
+
+#[test]
+fn groups_without_roots_are_rejected() {
+    let err = populate(
+        r#"[
+            { "type": "类", "description": "", "groups": [
+                { "zigens": ["日"], "code": "Jr", "classify": "通", "description": "" },
+                { "zigens": [], "code": "Kk", "classify": "通", "description": "" }
+            ] },
+            { "type": "混", "zigens": ["日"] }
+        ]"#,
+    )
+    .unwrap_err();
+
+    assert!(err.contains("Kk"), "{err}");
+}
+
+#[test]
+fn roots_in_several_confusables_are_rejected() {
+    let err = populate(
+        r#"[
+            { "type": "类", "description": "", "groups": [
+                { "zigens": ["日"], "code": "Jr", "classify": "通", "description": "" },
+                { "zigens": ["曰"], "code": "Jy", "classify": "通", "description": "" },
+                { "zigens": ["口"], "code": "Kk", "classify": "通", "description": "" }
+            ] },
+            { "type": "混", "zigens": ["日", "曰"] },
+            { "type": "混", "zigens": ["口", "日"] }
+        ]"#,
+    )
+    .unwrap_err();
+
+    assert!(err.contains("日") && !err.contains("口"), "{err}");
+}
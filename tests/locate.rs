@@ -0,0 +1,80 @@
+//! 在字形中找出字根位置的测试。
+
+use std::path::PathBuf;
+
+use zigen_trainer::font::{Bitmap, Font, locate};
+
+const SIZE: usize = 48;
+
+fn font() -> Font {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets/trainer/ChaiPUA-0.2.7.ttf");
+    Font::load(&path.display().to_string()).unwrap()
+}
+
+/// 把两个字根缩小一半，左右并排，拼成一个「字」。
+fn compose(font: &Font, left: char, right: char) -> Bitmap {
+    let mut pixels = vec![0.0; SIZE * SIZE];
+
+    for (c, offset_x) in [(left, 0), (right, SIZE / 2)] {
+        let part = font.render(c, SIZE / 2).unwrap().unwrap();
+        for y in 0..part.size {
+            for x in 0..part.size {
+                pixels[(y + SIZE / 4) * SIZE + x + offset_x] = part.pixels[y * part.size + x];
+            }
+        }
+    }
+
+    Bitmap { size: SIZE, pixels }
+}
+
+#[test]
+fn glyph_matches_itself() {
+    let font = font();
+    let root = font.render('\u{e002}', SIZE).unwrap().unwrap();
+
+    let placement = locate(&root, &root).unwrap();
+    assert!(placement.similarity > 0.95, "{placement:?}");
+    assert!(placement.bounds.2 > SIZE / 2 && placement.bounds.3 > SIZE / 2);
+}
+
+#[test]
+fn root_is_found_in_its_half_of_the_character() {
+    let font = font();
+    let (left, right) = ('\u{e002}', '\u{e0a0}');
+    let character = compose(&font, left, right);
+
+    for (root, in_left_half) in [(left, true), (right, false)] {
+        let placement = locate(&character, &font.render(root, SIZE).unwrap().unwrap()).unwrap();
+        let (x, _, width, _) = placement.bounds;
+        let center = x + width / 2;
+
+        assert_eq!(center < SIZE / 2, in_left_half, "{:?}", placement.bounds);
+        // 遮罩只覆盖字根所在的一半
+        let masked = |range: std::ops::Range<usize>| {
+            (0..SIZE)
+                .flat_map(|y| range.clone().map(move |x| y * SIZE + x))
+                .filter(|&i| placement.mask.pixels[i] > 0.0)
+                .count()
+        };
+        let (own, other) = if in_left_half {
+            (masked(0..SIZE / 2), masked(SIZE / 2..SIZE))
+        } else {
+            (masked(SIZE / 2..SIZE), masked(0..SIZE / 2))
+        };
+        assert!(own > other * 4, "{own} vs {other}");
+    }
+}
+
+#[test]
+fn blank_or_mismatched_bitmaps_are_not_located() {
+    let font = font();
+    let root = font.render('\u{e002}', SIZE).unwrap().unwrap();
+    let blank = Bitmap {
+        size: SIZE,
+        pixels: vec![0.0; SIZE * SIZE],
+    };
+
+    assert!(locate(&blank, &root).is_none());
+    assert!(locate(&root, &blank).is_none());
+    assert!(locate(&root, &font.render('\u{e002}', SIZE / 2).unwrap().unwrap()).is_none());
+}
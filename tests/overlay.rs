@@ -0,0 +1,185 @@
+//! 叠加式字根集的测试。
+
+use std::path::PathBuf;
+
+use zigen_trainer::overlay::{self, SchemeFile, SchemeOverlay};
+use zigen_trainer::scheme::{LoadedScheme, SchemeZigen, ZigenConfusableUnpopulated};
+
+const BASE: &str = r#"[
+    { "type": "类", "description": "", "groups": [
+        { "zigens": ["高"], "code": "Ag", "classify": "通", "description": "" },
+        { "zigens": ["亠"], "code": "At", "classify": "通", "description": "" }
+    ] },
+    { "type": "类", "description": "", "groups": [
+        { "zigens": ["車"], "code": "Cc", "classify": "繁", "description": "" },
+        { "zigens": ["车"], "code": "Cc", "classify": "简", "description": "" }
+    ] },
+    { "type": "混", "zigens": ["車", "车"] }
+]"#;
+
+fn base() -> LoadedScheme<ZigenConfusableUnpopulated> {
+    serde_json::from_str(BASE).unwrap()
+}
+
+fn overlay(source: &str) -> SchemeOverlay {
+    match SchemeFile::parse(source).unwrap() {
+        SchemeFile::Overlay(overlay) => overlay,
+        SchemeFile::Full(_) => panic!("应解析为叠加文件"),
+    }
+}
+
+/// 把字根集简化为（各聚类的代表性字根与编码）与混淆集数量，方便比较。
+fn summary(scheme: &LoadedScheme<ZigenConfusableUnpopulated>) -> (Vec<Vec<String>>, usize) {
+    let clusters = scheme
+        .0
+        .iter()
+        .filter_map(|entry| match entry {
+            SchemeZigen::Cluster(cluster) => Some(
+                cluster
+                    .groups
+                    .iter()
+                    .map(|group| format!("{}{}", group.zigens[0].0, group.code))
+                    .collect(),
+            ),
+            SchemeZigen::Confusable(_) | SchemeZigen::Decomposition(_) => None,
+        })
+        .collect();
+    let confusables = scheme
+        .0
+        .iter()
+        .filter(|entry| matches!(entry, SchemeZigen::Confusable(_)))
+        .count();
+
+    (clusters, confusables)
+}
+
+#[test]
+fn overrides_are_applied_in_order() {
+    let overlay = overlay(
+        r#"{
+            "base": "./base.json",
+            "overrides": [
+                { "op": "改码", "zigen": "高", "code": "Ga" },
+                { "op": "添加", "cluster": "亠", "group": { "zigens": ["二"], "code": "Ae", "classify": "通", "description": "" } },
+                { "op": "删除", "zigen": "车" },
+                { "op": "移动", "zigen": "車", "to": "高" },
+                { "op": "移动", "zigen": "亠" }
+            ]
+        }"#,
+    );
+
+    let flattened = overlay::flatten(base(), &[overlay]).unwrap();
+
+    assert_eq!(
+        summary(&flattened),
+        (
+            vec![
+                vec!["高Ga".into(), "二Ae".into(), "車Cc".into()],
+                vec!["亠At".into()],
+            ],
+            // 混淆集只剩下一个字根，因此被删除
+            0
+        )
+    );
+}
+
+#[test]
+fn unknown_roots_are_rejected() {
+    for source in [
+        r#"{ "base": "", "overrides": [{ "op": "改码", "zigen": "日", "code": "Jr" }] }"#,
+        r#"{ "base": "", "overrides": [{ "op": "删除", "zigen": "日" }] }"#,
+        r#"{ "base": "", "overrides": [{ "op": "移动", "zigen": "高", "to": "日" }] }"#,
+        r#"{ "base": "", "overrides": [{ "op": "添加", "group": { "zigens": ["高"], "code": "Ag", "classify": "通", "description": "" } }] }"#,
+    ] {
+        assert!(
+            overlay::flatten(base(), &[overlay(source)]).is_err(),
+            "{source}"
+        );
+    }
+}
+
+#[test]
+fn invalid_moves_leave_the_scheme_untouched() {
+    for source in [
+        r#"{ "base": "", "overrides": [{ "op": "移动", "zigen": "高", "to": "高" }] }"#,
+        r#"{ "base": "", "overrides": [{ "op": "移动", "zigen": "高", "to": "日" }] }"#,
+    ] {
+        let mut scheme = base();
+        assert!(overlay(source).overrides[0].apply(&mut scheme).is_err());
+        assert_eq!(scheme, base(), "{source}");
+    }
+}
+
+#[test]
+fn nested_overlays_load_from_disk() {
+    let dir = std::env::temp_dir().join(format!("zigen-overlay-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("sub")).unwrap();
+
+    let bundled =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets/trainer/zigen/yuhao_star.json");
+    std::fs::write(
+        dir.join("sub/middle.json"),
+        format!(
+            r#"{{ "base": {:?}, "overrides": [{{ "op": "改码", "zigen": "高", "code": "Ga" }}] }}"#,
+            bundled.display().to_string()
+        ),
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("top.json"),
+        r#"{ "base": "./sub/middle.json", "overrides": [{ "op": "删除", "zigen": "亠" }] }"#,
+    )
+    .unwrap();
+
+    let flattened = overlay::load_file(&dir.join("top.json").display().to_string()).unwrap();
+    let (clusters, _) = summary(&flattened);
+
+    assert_eq!(clusters[0], vec![String::from("高Ga")]);
+    assert!(flattened.populate_confusables().is_ok());
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn relative_paths_are_resolved_against_the_file() {
+    let cases = [
+        (
+            "./assets/trainer/zigen/a.json",
+            "./b.json",
+            "./assets/trainer/zigen/b.json",
+        ),
+        (
+            "./assets/trainer/zigen/a.json",
+            "../c.json",
+            "./assets/trainer/c.json",
+        ),
+        ("a.json", "b.json", "b.json"),
+        ("./a.json", "../b.json", "./../b.json"),
+        (
+            "./a.json",
+            "https://example.com/b.json",
+            "https://example.com/b.json",
+        ),
+        ("./a.json", "/srv/b.json", "/srv/b.json"),
+        // 第三方方案列表中的字根集位于绝对URL，路径不能越过其主机名，以「/」开头的路径也相对于其来源
+        (
+            "https://example.com/schemes/zigen/a.json",
+            "../../../../x.json",
+            "https://example.com/x.json",
+        ),
+        (
+            "https://example.com/schemes/zigen/a.json",
+            "/zigen/b.json",
+            "https://example.com/zigen/b.json",
+        ),
+        (
+            "https://example.com/schemes/zigen/a.json",
+            "./base/b.json",
+            "https://example.com/schemes/zigen/base/b.json",
+        ),
+    ];
+
+    for (file, relative, expected) in cases {
+        assert_eq!(overlay::resolve_relative(file, relative), expected);
+    }
+}
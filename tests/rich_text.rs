@@ -0,0 +1,55 @@
+//! 富文本描述的测试。
+
+use zigen_trainer::rich_text::{Inline, parse, parse_or_plain};
+
+fn text(s: &str) -> Inline {
+    Inline::Text(s.to_owned())
+}
+
+#[test]
+fn supported_markup_is_parsed() {
+    assert_eq!(
+        parse("乂 y<b>ì</b> <br>（小根）\n<zigen>口</zigen>").unwrap(),
+        vec![
+            text("乂 y"),
+            Inline::Bold(vec![text("ì")]),
+            text(" "),
+            Inline::Break,
+            text("（小根）"),
+            Inline::Break,
+            Inline::Zigen(String::from("口")),
+        ]
+    );
+
+    assert_eq!(
+        parse(r#"见<a href="https://example.com/?a=1&amp;b=2"><em>说明</em></a> &lt;3"#).unwrap(),
+        vec![
+            text("见"),
+            Inline::Link {
+                href: String::from("https://example.com/?a=1&b=2"),
+                children: vec![Inline::Italic(vec![text("说明")])],
+            },
+            text(" <3"),
+        ]
+    );
+}
+
+#[test]
+fn unsupported_markup_is_rejected() {
+    for source in [
+        "<script>alert(1)</script>",
+        r#"<img src=x onerror="alert(1)">"#,
+        r#"<a href="javascript:alert(1)">x</a>"#,
+        r#"<a href="https://example.com" onclick="alert(1)">x</a>"#,
+        r#"<b style="color:red">x</b>"#,
+        "<b>未闭合",
+        "<b>交错<i></b></i>",
+        "<zigen><b>口</b></zigen>",
+        "a < b",
+    ] {
+        assert!(parse(source).is_err(), "{source}");
+    }
+
+    let source = "<script>alert(1)</script>";
+    assert_eq!(parse_or_plain(source), vec![text(source)]);
+}
@@ -0,0 +1,66 @@
+//! 字根集统计的测试。
+
+use zigen_trainer::scheme::{LoadedScheme, ZigenConfusableUnpopulated};
+use zigen_trainer::stats::{SchemeStats, sections};
+
+fn scheme(source: &str) -> LoadedScheme<ZigenConfusableUnpopulated> {
+    serde_json::from_str(source).unwrap()
+}
+
+const SCHEME: &str = r#"[
+    { "type": "类", "description": "", "groups": [
+        { "zigens": ["高"], "code": "Ag", "classify": "通", "description": "" },
+        { "zigens": ["亠", ""], "code": "At", "classify": "简", "description": "" }
+    ] },
+    { "type": "类", "description": "", "groups": [
+        { "zigens": ["車"], "code": "c", "classify": "繁", "description": "" }
+    ] },
+    { "type": "混", "zigens": ["高", "車"] }
+]"#;
+
+#[test]
+fn counts_roots_classes_and_codes() {
+    let stats = SchemeStats::compute(&scheme(SCHEME));
+
+    assert_eq!(
+        (stats.clusters, stats.groups, stats.roots, stats.confusables),
+        (2, 3, 4, 1)
+    );
+    assert_eq!(stats.roots_per_key.get(&'a'), Some(&3));
+    assert_eq!(stats.roots_per_key.get(&'c'), Some(&1));
+    assert_eq!(stats.classes, [1, 1, 1, 0]);
+    assert_eq!(stats.code_lengths.get(&1), Some(&1));
+    assert_eq!(stats.code_lengths.get(&2), Some(&2));
+    assert_eq!(stats.groups_per_cluster.get(&2), Some(&1));
+    assert_eq!(stats.pua_roots, 1);
+    assert_eq!(stats.pua_share(), 0.25);
+}
+
+#[test]
+fn comparison_aligns_rows_across_schemes() {
+    let first = SchemeStats::compute(&scheme(SCHEME));
+    let second = SchemeStats::compute(&scheme(
+        r#"[
+            { "type": "类", "description": "", "groups": [
+                { "zigens": ["日"], "code": "Jr", "classify": "通", "description": "" }
+            ] }
+        ]"#,
+    ));
+
+    let sections = sections(&[&first, &second]);
+    let keys = sections.iter().find(|s| s.title == "各键字根数").unwrap();
+
+    let rows = keys
+        .rows
+        .iter()
+        .map(|(label, values)| (label.as_str(), values.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        rows,
+        vec![
+            ("a", vec!["3（75.0%）".to_owned(), "0（0.0%）".to_owned()]),
+            ("c", vec!["1（25.0%）".to_owned(), "0（0.0%）".to_owned()]),
+            ("j", vec!["0（0.0%）".to_owned(), "1（100.0%）".to_owned()]),
+        ]
+    );
+}
@@ -0,0 +1,74 @@
+//! 自定义标签的测试。
+
+use std::collections::BTreeMap;
+
+use zigen_trainer::scheme::{LoadedScheme, SchemeOptions, ZigenConfusableUnpopulated};
+
+fn scheme() -> LoadedScheme<ZigenConfusableUnpopulated> {
+    serde_json::from_str(
+        r#"[
+            { "type": "类", "description": "", "groups": [
+                { "zigens": ["一"], "code": "Fi", "classify": "通", "description": "", "tags": ["笔画"] },
+                { "zigens": ["丨"], "code": "Fs", "classify": "通", "description": "", "tags": ["笔画"] }
+            ] },
+            { "type": "类", "description": "", "groups": [
+                { "zigens": ["口"], "code": "Kk", "classify": "通", "description": "" },
+                { "zigens": ["日"], "code": "Jr", "classify": "通", "description": "" }
+            ] },
+            { "type": "混", "description": "", "zigens": ["口", "日"] }
+        ]"#,
+    )
+    .unwrap()
+}
+
+fn roots(
+    scheme: &LoadedScheme<ZigenConfusableUnpopulated>,
+    options: &SchemeOptions,
+) -> Vec<String> {
+    let mut cards = scheme.clone().populate_confusables().unwrap();
+    cards.sort_to_options(options);
+
+    cards
+        .0
+        .iter()
+        .flat_map(|card| card.as_raw_parts().0.iter())
+        .map(|group| group.zigens[0].0.clone())
+        .collect()
+}
+
+#[test]
+fn user_tags_are_merged_into_groups() {
+    let mut scheme = scheme();
+    scheme.apply_tags(&BTreeMap::from([
+        (String::from("口"), vec![String::from("方框")]),
+        (String::from("一"), vec![String::from("笔画")]),
+    ]));
+
+    assert_eq!(
+        scheme.tags().into_iter().collect::<Vec<_>>(),
+        vec!["方框", "笔画"]
+    );
+    assert_eq!(scheme.groups().next().unwrap().tags, vec!["笔画"]);
+}
+
+#[test]
+fn tags_filter_cards() {
+    let mut scheme = scheme();
+    scheme.apply_tags(&BTreeMap::from([(
+        String::from("口"),
+        vec![String::from("方框")],
+    )]));
+
+    let include = SchemeOptions {
+        include_tags: vec![String::from("笔画")],
+        ..Default::default()
+    };
+    assert_eq!(roots(&scheme, &include), vec!["一", "丨"]);
+
+    // 混淆集只有在所有字根都未被排除时才会保留
+    let exclude = SchemeOptions {
+        exclude_tags: vec![String::from("方框")],
+        ..Default::default()
+    };
+    assert_eq!(roots(&scheme, &exclude), vec!["一", "丨", "日"]);
+}